@@ -0,0 +1,62 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Facts about a chain that never change once they exist, such as denom traces or block times,
+/// kept in a JSON file per chain so later runs need not fetch them again.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+#[serde(bound(serialize = "K: Serialize, V: Serialize", deserialize = "K: DeserializeOwned + Ord, V: DeserializeOwned"))]
+pub struct ChainCache<K, V> {
+    chains: BTreeMap<String, BTreeMap<K, V>>,
+}
+
+impl<K, V> Default for ChainCache<K, V> {
+    fn default() -> Self {
+        ChainCache { chains: BTreeMap::new() }
+    }
+}
+
+impl<K: Ord + Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> ChainCache<K, V> {
+    /// Reads the cache file; a missing file is an empty cache.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(ChainCache::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Reads the cache file, reporting an unreadable one on stderr and starting afresh.
+    pub fn load_or_default(path: &Path, what: &str) -> Self {
+        ChainCache::load(path).unwrap_or_else(|error| {
+            eprintln!("Ignoring the {} cache {}: {}", what, path.display(), error);
+            ChainCache::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Everything cached for a chain, if anything was.
+    pub fn chain(&self, chain: &str) -> Option<&BTreeMap<K, V>> {
+        self.chains.get(chain)
+    }
+
+    pub fn get(&self, chain: &str, key: &K) -> Option<&V> {
+        self.chains.get(chain)?.get(key)
+    }
+
+    pub fn insert(&mut self, chain: &str, key: K, value: V) {
+        self.chains.entry(chain.to_string()).or_default().insert(key, value);
+    }
+}
@@ -0,0 +1,486 @@
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use rand::Rng;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::api::endpoints::EndpointPool;
+use crate::api::error::{FetchError, RequestContext};
+use crate::config::config::{Backend, Config, HttpConfig, TxQueryStyle};
+
+/// Path probed by the health check; cheap on every Cosmos SDK node.
+const HEALTH_CHECK_PATH: &str = "/cosmos/base/tendermint/v1beta1/syncing";
+
+/// Path probed by the health check when the endpoints are CometBFT RPCs.
+const RPC_HEALTH_CHECK_PATH: &str = "/status";
+
+/// HTTP client shared by every fetch, applying the timeouts and retry policy from `Config`.
+///
+/// The underlying `reqwest` client keeps a connection pool, so a single `HttpClient`
+/// should be built per command and passed to the fetchers. Cloning it is cheap and
+/// clones share the endpoint health state.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    config: Config,
+    client: reqwest::Client,
+    endpoints: Arc<EndpointPool>,
+    /// The tx search style of the LCD, as configured or detected on the first search.
+    tx_query: Arc<OnceLock<TxQueryStyle>>,
+}
+
+/// A successful response body together with the URL that produced it.
+#[derive(Debug)]
+pub struct Fetched {
+    pub url: Url,
+    pub body: String,
+}
+
+#[derive(Deserialize)]
+struct SyncingResponse {
+    syncing: bool,
+}
+
+#[derive(Deserialize)]
+struct StatusResponse {
+    result: StatusResult,
+}
+
+#[derive(Deserialize)]
+struct StatusResult {
+    sync_info: SyncInfo,
+}
+
+#[derive(Deserialize)]
+struct SyncInfo {
+    catching_up: bool,
+}
+
+impl HttpClient {
+    /// Builds the shared client from the `[http]` and `[failover]` settings of the given configuration.
+    pub fn new(config: &Config) -> Result<Self, FetchError> {
+        let http = config.http();
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(http.connect_timeout_secs))
+            .timeout(Duration::from_secs(http.timeout_secs))
+            .build()
+            .map_err(|source| FetchError::Network {
+                request: RequestContext::new(config.url().as_str()),
+                attempts: 0,
+                source,
+            })?;
+
+        Ok(HttpClient {
+            config: config.clone(),
+            client,
+            endpoints: Arc::new(EndpointPool::new(config.endpoints(), config.failover().clone())),
+            tx_query: Arc::new(config.tx_query().map(OnceLock::from).unwrap_or_default()),
+        })
+    }
+
+    /// Builds the client and, when several endpoints are configured, health-checks them first.
+    pub async fn connect(config: &Config) -> Result<Self, FetchError> {
+        let client = HttpClient::new(config)?;
+        if client.endpoints.len() > 1 && config.failover().health_check {
+            client.health_check().await;
+        }
+        Ok(client)
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// How the LCD takes tx search conditions, once configured or detected.
+    pub fn tx_query_style(&self) -> Option<TxQueryStyle> {
+        self.tx_query.get().copied()
+    }
+
+    /// Records the detected tx search style; the first style recorded is kept.
+    pub fn set_tx_query_style(&self, style: TxQueryStyle) {
+        let _ = self.tx_query.set(style);
+    }
+
+    /// Probes every endpoint once, recording its latency or taking it out of rotation.
+    ///
+    /// An endpoint that is unreachable, answers with an error or is still catching up is cooled down.
+    pub async fn health_check(&self) {
+        let backend = self.config.backend();
+        let path = match backend {
+            Backend::Lcd => HEALTH_CHECK_PATH,
+            Backend::Rpc => RPC_HEALTH_CHECK_PATH,
+        };
+        let probes = self.endpoints.urls().into_iter().map(|endpoint| async move {
+            let started = Instant::now();
+            let result = self.client.get(format!("{}{}", endpoint, path)).send().await;
+            let healthy = match result {
+                Ok(res) if res.status().is_success() => match backend {
+                    Backend::Lcd => !matches!(res.json::<SyncingResponse>().await, Ok(SyncingResponse { syncing: true })),
+                    Backend::Rpc => !matches!(
+                        res.json::<StatusResponse>().await,
+                        Ok(StatusResponse { result: StatusResult { sync_info: SyncInfo { catching_up: true } } })
+                    ),
+                },
+                _ => false,
+            };
+            if healthy {
+                self.endpoints.record_success(&endpoint, started.elapsed());
+            } else {
+                self.endpoints.cool_down(&endpoint, "failed its health check");
+            }
+        });
+        join_all(probes).await;
+    }
+
+    /// Fetches `path` and deserializes the JSON body, reporting the URL and body on parse failures.
+    pub async fn get_json<T: DeserializeOwned>(&self, path: &str, params: &[(&str, String)]) -> Result<T, FetchError> {
+        let fetched = self.get_text(path, params).await?;
+        serde_json::from_str(&fetched.body)
+            .map_err(|source| FetchError::parse(RequestContext::new(fetched.url.as_str()), &fetched.body, source))
+    }
+
+    /// The endpoint the failover strategy would send the next request to.
+    pub fn select_endpoint(&self) -> String {
+        self.endpoints.select(&[])
+    }
+
+    /// Fetches `path` from the given endpoint only and deserializes the JSON body, leaving the
+    /// health of the endpoint pool alone.
+    ///
+    /// Meant for probes whose error answers are expected, such as heights a pruned node no longer
+    /// serves: these are returned as they are instead of taking the endpoint out of rotation.
+    /// Network errors and retryable statuses are retried on the same endpoint with backoff.
+    pub async fn probe_json<T: DeserializeOwned>(&self, endpoint: &str, path: &str, params: &[(&str, String)]) -> Result<T, FetchError> {
+        let policy = self.config.http();
+        let url = build_url(endpoint, path, params)?;
+        let mut attempt: u32 = 0;
+
+        loop {
+            let request = RequestContext::new(url.as_str());
+            let (delay, error) = match self.client.get(url.clone()).send().await {
+                Ok(res) if res.status().is_success() => match res.text().await {
+                    Ok(body) => return serde_json::from_str(&body).map_err(|source| FetchError::parse(request, &body, source)),
+                    Err(source) => (backoff(policy, attempt), FetchError::Network { request, attempts: attempt + 1, source }),
+                },
+                Ok(res) => {
+                    let server_delay = retry_after(&res, policy);
+                    let status = res.status();
+                    let body = res.text().await.unwrap_or_default();
+                    let error = FetchError::status(request, status, &body);
+                    if !error.is_transient() {
+                        return Err(error);
+                    }
+                    (server_delay.unwrap_or_else(|| backoff(policy, attempt)), error)
+                }
+                Err(source) => (backoff(policy, attempt), FetchError::Network { request, attempts: attempt + 1, source }),
+            };
+
+            if attempt >= policy.max_retries {
+                return Err(error);
+            }
+            attempt += 1;
+            eprintln!("{}; retry {}/{} in {:?}", error, attempt, policy.max_retries, delay);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Sends a GET request for `path` and returns the body once a successful status is received.
+    ///
+    /// Each attempt goes to the endpoint chosen by the failover strategy, preferring endpoints not
+    /// yet tried for this request. Connection failures, timeouts, bodies cut short, `429` and `5xx`
+    /// responses are retried with exponential backoff and full jitter, honouring `Retry-After` when
+    /// the server sends one; moving to a different endpoint happens without waiting. An endpoint
+    /// reporting the height as pruned is cooled down and the next one is tried; a height above the
+    /// latest block is returned at once. Any other status, or running out of retries, is returned
+    /// as a `FetchError` carrying the URL, status and the start of the body.
+    pub async fn get_text(&self, path: &str, params: &[(&str, String)]) -> Result<Fetched, FetchError> {
+        let policy = self.config.http();
+        let mut attempt: u32 = 0;
+        let mut tried: Vec<String> = Vec::new();
+
+        loop {
+            let endpoint = self.endpoints.select(&tried);
+            let url = build_url(&endpoint, path, params)?;
+            let request = RequestContext::new(url.as_str());
+            let started = Instant::now();
+
+            let (delay, error) = match self.client.get(url.clone()).send().await {
+                Ok(res) if res.status().is_success() => match res.text().await {
+                    Ok(body) => {
+                        self.endpoints.record_success(&endpoint, started.elapsed());
+                        return Ok(Fetched { url, body });
+                    }
+                    // A body cut short by a read timeout or a dropped connection is as transient
+                    // as a request that never got through.
+                    Err(source) => {
+                        let error = FetchError::Network {
+                            request,
+                            attempts: attempt + 1,
+                            source,
+                        };
+                        (backoff(policy, attempt), error)
+                    }
+                },
+                Ok(res) => {
+                    let status = res.status();
+                    let server_delay = retry_after(&res, policy);
+                    let body = res.text().await.unwrap_or_default();
+                    let error = FetchError::status(request, status, &body);
+                    if error.is_future_height() {
+                        return Err(error);
+                    }
+                    if error.is_height_unavailable() {
+                        self.endpoints.cool_down(&endpoint, "reported the height as unavailable");
+                        tried.push(endpoint);
+                        if self.endpoints.has_untried(&tried) {
+                            continue;
+                        }
+                        return Err(error);
+                    }
+                    if !is_retryable_status(status) {
+                        return Err(error);
+                    }
+                    (server_delay.unwrap_or_else(|| backoff(policy, attempt)), error)
+                }
+                Err(source) => {
+                    let retryable = source.is_connect() || source.is_timeout() || source.is_request();
+                    let error = FetchError::Network {
+                        request,
+                        attempts: attempt + 1,
+                        source,
+                    };
+                    if !retryable {
+                        return Err(error);
+                    }
+                    (backoff(policy, attempt), error)
+                }
+            };
+
+            self.endpoints.record_failure(&endpoint);
+            tried.push(endpoint);
+
+            if attempt >= policy.max_retries {
+                return Err(error);
+            }
+            attempt += 1;
+
+            if self.endpoints.has_untried(&tried) {
+                eprintln!("{}; retry {}/{} on the next endpoint", error, attempt, policy.max_retries);
+            } else {
+                eprintln!("{}; retry {}/{} in {:?}", error, attempt, policy.max_retries, delay);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// Joins an endpoint, a path and query parameters, encoding the parameters.
+fn build_url(endpoint: &str, path: &str, params: &[(&str, String)]) -> Result<Url, FetchError> {
+    let base = format!("{}{}", endpoint, path);
+    let url = if params.is_empty() { Url::parse(&base) } else { Url::parse_with_params(&base, params) };
+    url.map_err(|source| FetchError::InvalidUrl { url: base, source })
+}
+
+/// Statuses worth another attempt: rate limiting and transient server or gateway failures.
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status.is_server_error()
+}
+
+/// Exponential backoff with full jitter: a random delay up to `initial * 2^attempt`, capped.
+fn backoff(policy: &HttpConfig, attempt: u32) -> Duration {
+    let ceiling = policy.initial_backoff_ms
+        .saturating_mul(1u64 << attempt.min(32))
+        .min(policy.max_backoff_ms);
+    let millis = if ceiling == 0 { 0 } else { rand::thread_rng().gen_range(0..=ceiling) };
+    Duration::from_millis(millis)
+}
+
+/// Reads `Retry-After` as either delta seconds or an HTTP date, capped at the maximum backoff.
+fn retry_after(res: &Response, policy: &HttpConfig) -> Option<Duration> {
+    let value = res.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    let delay = parse_retry_after(value, Utc::now())?;
+    Some(delay.min(Duration::from_millis(policy.max_backoff_ms)))
+}
+
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    if let Ok(secs) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value.trim()).ok()?.with_timezone(&Utc);
+    Some((at - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn mock_config(url: String, max_retries: u32) -> Config {
+        Config {
+            url,
+            http: HttpConfig {
+                max_retries,
+                initial_backoff_ms: 1,
+                max_backoff_ms: 5,
+                ..HttpConfig::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy = HttpConfig { initial_backoff_ms: 100, max_backoff_ms: 1_000, ..HttpConfig::default() };
+        for attempt in 0..64 {
+            assert!(backoff(&policy, attempt) <= Duration::from_millis(1_000));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_text_retries_transient_failures() {
+        let mut server = mockito::Server::new_async().await;
+        let failing = server.mock("GET", "/status")
+            .with_status(503)
+            .with_header("retry-after", "0")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = HttpClient::new(&mock_config(server.url(), 1)).unwrap();
+
+        let error = client.get_text("/status", &[]).await.unwrap_err();
+        assert_eq!(error.http_status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+        failing.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_text_retries_truncated_body() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut server = mockito::Server::new_async().await;
+        let calls = AtomicUsize::new(0);
+        // The first response drops the connection halfway through the body.
+        let mock = server.mock("GET", "/status")
+            .with_chunked_body(move |writer| {
+                if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                    writer.write_all(br#"{"height":"#)?;
+                    return Err(std::io::Error::new(std::io::ErrorKind::ConnectionReset, "connection dropped"));
+                }
+                writer.write_all(br#"{"height":"5"}"#)
+            })
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = HttpClient::new(&mock_config(server.url(), 1)).unwrap();
+
+        let fetched = client.get_text("/status", &[]).await.unwrap();
+        assert_eq!(fetched.body, r#"{"height":"5"}"#);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_text_returns_future_height_at_once() {
+        let mut server = mockito::Server::new_async().await;
+        let future = server.mock("GET", "/block")
+            .match_query(mockito::Matcher::Any)
+            .with_status(500)
+            .with_body(r#"{"jsonrpc":"2.0","id":-1,"error":{"code":-32603,"message":"Internal error","data":"height 100 must be less than or equal to the current blockchain height 50"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = HttpClient::new(&mock_config(server.url(), 3)).unwrap();
+
+        let error = client.get_text("/block", &[("height", "100".to_string())]).await.unwrap_err();
+        assert!(error.is_future_height());
+        future.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_text_does_not_retry_client_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let bad_request = server.mock("GET", "/status")
+            .with_status(400)
+            .with_body(r#"{"code":3,"message":"invalid request"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = HttpClient::new(&mock_config(server.url(), 3)).unwrap();
+
+        match client.get_text("/status", &[]).await {
+            Err(FetchError::Status { request, status, body }) => {
+                assert_eq!(request.url, format!("{}/status", server.url()));
+                assert_eq!(status, StatusCode::BAD_REQUEST);
+                assert!(body.contains("invalid request"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        bad_request.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_text_fails_over_to_next_endpoint() {
+        let mut primary = mockito::Server::new_async().await;
+        let mut fallback = mockito::Server::new_async().await;
+        let pruned = primary.mock("GET", "/status")
+            .with_status(500)
+            .with_body(r#"{"code":2,"message":"height 5 is not available, lowest height is 100"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let served = fallback.mock("GET", "/status")
+            .with_body("ok")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let config = Config {
+            endpoints: vec![fallback.url()],
+            ..mock_config(primary.url(), 3)
+        };
+        let client = HttpClient::new(&config).unwrap();
+
+        let fetched = client.get_text("/status", &[]).await.unwrap();
+        assert_eq!(fetched.body, "ok");
+        assert!(fetched.url.as_str().starts_with(&fallback.url()));
+
+        // The pruned primary stays out of rotation for the following request.
+        client.get_text("/status", &[]).await.unwrap();
+        pruned.assert_async().await;
+        served.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_health_check_cools_down_syncing_endpoint() {
+        let mut syncing = mockito::Server::new_async().await;
+        let mut synced = mockito::Server::new_async().await;
+        syncing.mock("GET", HEALTH_CHECK_PATH).with_body(r#"{"syncing":true}"#).create_async().await;
+        synced.mock("GET", HEALTH_CHECK_PATH).with_body(r#"{"syncing":false}"#).create_async().await;
+
+        let config = Config {
+            endpoints: vec![synced.url()],
+            ..mock_config(syncing.url(), 0)
+        };
+        let client = HttpClient::connect(&config).await.unwrap();
+
+        assert_eq!(client.endpoints.select(&[]), synced.url());
+    }
+}
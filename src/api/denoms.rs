@@ -0,0 +1,222 @@
+use std::collections::{BTreeSet, HashMap};
+
+use futures::stream::{self, StreamExt};
+use serde::Deserialize;
+
+use crate::api::cache::ChainCache;
+use crate::api::client::HttpClient;
+use crate::api::error::FetchError;
+use crate::config::config::Backend;
+use crate::models::coin::{BankDenomMetadata, DenomMetadata, DenomResolver};
+use crate::models::ibc::{DenomTrace, IBC_DENOM_PREFIX};
+use crate::models::pagination::Pagination;
+
+/// LCD path listing the bank module's denom metadata.
+const DENOMS_METADATA_PATH: &str = "/cosmos/bank/v1beta1/denoms_metadata";
+
+/// LCD path of the ICS-20 denom trace of an `ibc/{hash}` denom.
+const DENOM_TRACES_PATH: &str = "/ibc/apps/transfer/v1/denom_traces";
+
+#[derive(Deserialize)]
+struct DenomTraceResponse {
+    denom_trace: DenomTrace,
+}
+
+/// Resolved IBC denom traces kept on disk, per chain then per `ibc/{hash}` denom.
+///
+/// Traces never change once a voucher exists, so entries are never refreshed.
+pub type DenomTraceCache = ChainCache<String, DenomTrace>;
+
+/// The bank denom metadata of each chain kept on disk, per chain then per base denom.
+///
+/// A chain's metadata is fetched once, when the cache has none for it; delete the file to pick
+/// up denoms registered since.
+pub type DenomMetadataCache = ChainCache<String, DenomMetadata>;
+
+#[derive(Deserialize)]
+struct DenomsMetadataResponse {
+    #[serde(default)]
+    metadatas: Vec<BankDenomMetadata>,
+    pagination: Option<Pagination>,
+}
+
+/// Loads the denom metadata of the chain, from the on-disk cache or else from the LCD, so a
+/// command can convert every amount it reports to display units without further requests.
+///
+/// Metadata is only served by the LCD; with the RPC backend amounts stay in base units.
+pub async fn fetch_denom_resolver(client: &HttpClient) -> Result<DenomResolver, FetchError> {
+    let config = client.config();
+    let chain = &config.cache_key();
+    let path = config.denom_metadata_cache();
+    let mut cache = DenomMetadataCache::load_or_default(&path, "denom metadata");
+    if let Some(metadata) = cache.chain(chain) {
+        return Ok(DenomResolver::new(metadata.values().cloned()));
+    }
+    if config.backend() == Backend::Rpc {
+        return Ok(DenomResolver::default());
+    }
+
+    let metadata = fetch_denoms_metadata(client).await?;
+    for entry in &metadata {
+        cache.insert(chain, entry.base.clone(), entry.clone());
+    }
+    if let Err(error) = cache.save(&path) {
+        eprintln!("Could not write the denom metadata cache {}: {}", path.display(), error);
+    }
+    Ok(DenomResolver::new(metadata))
+}
+
+/// Fetches every entry of the bank module's denom metadata, following `pagination.key`.
+async fn fetch_denoms_metadata(client: &HttpClient) -> Result<Vec<DenomMetadata>, FetchError> {
+    let mut metadata: Vec<DenomMetadata> = Vec::new();
+    let mut next_key: Option<String> = None;
+    loop {
+        let mut params = vec![("pagination.limit", client.config().page_size().to_string())];
+        if let Some(key) = &next_key {
+            params.push(("pagination.key", key.clone()));
+        }
+        let response: DenomsMetadataResponse = client.get_json(DENOMS_METADATA_PATH, &params).await?;
+        metadata.extend(response.metadatas.into_iter().map(DenomMetadata::from));
+
+        match response.pagination.and_then(|pagination| pagination.next_key).filter(|key| !key.is_empty()) {
+            Some(key) if next_key.as_deref() == Some(key.as_str()) => {
+                return Err(FetchError::TaskFailure("pagination key repeated for denom metadata".to_string()));
+            }
+            Some(key) => next_key = Some(key),
+            None => break,
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Resolves `ibc/{hash}` denoms to their traces, from the on-disk cache or else from the LCD.
+///
+/// Newly resolved traces are written back to the cache. Denoms that cannot be resolved, because
+/// the node does not know them, cannot be reached or only serves the RPC, are reported on stderr
+/// and left out, so the output keeps the bare hash.
+pub async fn resolve_denom_traces(client: &HttpClient, denoms: &BTreeSet<String>) -> HashMap<String, DenomTrace> {
+    let config = client.config();
+    // Hashes depend on the channels of the chain, so the cache is kept per chain.
+    let chain = &config.cache_key();
+    let path = config.denom_trace_cache();
+    let mut cache = DenomTraceCache::load_or_default(&path, "denom trace");
+
+    let missing: Vec<&String> = denoms.iter()
+        .filter(|denom| denom.starts_with(IBC_DENOM_PREFIX) && cache.get(chain, *denom).is_none())
+        .collect();
+    if !missing.is_empty() && config.backend() == Backend::Rpc {
+        eprintln!("Denom traces can only be looked up on the LCD; {} IBC denoms left unresolved", missing.len());
+    } else if !missing.is_empty() {
+        let fetched: Vec<(&String, Result<DenomTrace, FetchError>)> = stream::iter(missing)
+            .map(|denom| async move { (denom, fetch_denom_trace(client, denom).await) })
+            .buffer_unordered(config.concurrency())
+            .collect()
+            .await;
+        for (denom, result) in fetched {
+            match result {
+                Ok(trace) => cache.insert(chain, denom.clone(), trace),
+                Err(error) => eprintln!("Could not resolve {}: {}", denom, error),
+            }
+        }
+        if let Err(error) = cache.save(&path) {
+            eprintln!("Could not write the denom trace cache {}: {}", path.display(), error);
+        }
+    }
+
+    denoms.iter()
+        .filter_map(|denom| cache.get(chain, denom).map(|trace| (denom.clone(), trace.clone())))
+        .collect()
+}
+
+async fn fetch_denom_trace(client: &HttpClient, denom: &str) -> Result<DenomTrace, FetchError> {
+    let hash = &denom[IBC_DENOM_PREFIX.len()..];
+    let response: DenomTraceResponse = client.get_json(&format!("{}/{}", DENOM_TRACES_PATH, hash), &[]).await?;
+    let trace = response.denom_trace;
+    if !trace.ibc_denom().eq_ignore_ascii_case(denom) {
+        return Err(FetchError::TaskFailure(format!("the node returned {} whose hash is not {}", trace.full_path(), denom)));
+    }
+    Ok(trace)
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::Matcher;
+    use serde_json::json;
+
+    use super::*;
+    use crate::config::config::Config;
+    use crate::models::coin::Coin;
+
+    #[tokio::test]
+    async fn test_fetch_denom_resolver_follows_pages() {
+        let mut server = mockito::Server::new_async().await;
+        let atom = json!({ "denom_units": [{ "denom": "uatom", "exponent": 0 }, { "denom": "atom", "exponent": 6 }], "base": "uatom", "display": "atom", "symbol": "ATOM" });
+        let osmo = json!({ "denom_units": [{ "denom": "uosmo", "exponent": 0 }, { "denom": "osmo", "exponent": 6 }], "base": "uosmo", "display": "osmo", "symbol": "" });
+        let first = server.mock("GET", DENOMS_METADATA_PATH)
+            .match_query(Matcher::Regex("^pagination.limit=100$".to_string()))
+            .with_body(json!({ "metadatas": [atom], "pagination": { "next_key": "b3Ntbw==", "total": "2" } }).to_string())
+            .expect(1)
+            .create_async().await;
+        let second = server.mock("GET", DENOMS_METADATA_PATH)
+            .match_query(Matcher::Regex("pagination.key=b3Ntbw".to_string()))
+            .with_body(json!({ "metadatas": [osmo], "pagination": { "next_key": null, "total": "0" } }).to_string())
+            .expect(1)
+            .create_async().await;
+
+        let cache = std::env::temp_dir().join(format!("tx_dump_denom_metadata_{}.json", std::process::id()));
+        let config = Config {
+            url: server.url(),
+            denom_metadata_cache: Some(cache.to_string_lossy().to_string()),
+            ..Config::default()
+        };
+        let client = HttpClient::new(&config).unwrap();
+        let resolver = fetch_denom_resolver(&client).await.unwrap();
+
+        assert_eq!(resolver.to_display(&Coin::new("uatom", 2_500_000)).to_string(), "2.5 ATOM");
+        assert_eq!(resolver.to_display(&Coin::new("uosmo", 1_000_000)).to_string(), "1 osmo");
+
+        // Served from the file this time: each page was requested once.
+        let resolver = fetch_denom_resolver(&client).await.unwrap();
+        assert_eq!(resolver.to_display(&Coin::new("uatom", 2_500_000)).to_string(), "2.5 ATOM");
+        first.assert_async().await;
+        second.assert_async().await;
+        std::fs::remove_file(cache).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resolve_denom_traces_uses_the_cache() {
+        let mut server = mockito::Server::new_async().await;
+        let atom = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2".to_string();
+        let unknown = "ibc/0000000000000000000000000000000000000000000000000000000000000000".to_string();
+        let trace = server.mock("GET", format!("{}/{}", DENOM_TRACES_PATH, &atom[4..]).as_str())
+            .with_body(json!({ "denom_trace": { "path": "transfer/channel-0", "base_denom": "uatom" } }).to_string())
+            .expect(1)
+            .create_async().await;
+        server.mock("GET", format!("{}/{}", DENOM_TRACES_PATH, &unknown[4..]).as_str())
+            .with_status(404)
+            .with_body(r#"{"code":5,"message":"denomination trace not found"}"#)
+            .create_async().await;
+
+        let cache = std::env::temp_dir().join(format!("tx_dump_denom_traces_{}.json", std::process::id()));
+        let config = Config {
+            url: server.url(),
+            chain_id: "osmosis-1".to_string(),
+            denom_trace_cache: Some(cache.to_string_lossy().to_string()),
+            ..Config::default()
+        };
+        let client = HttpClient::new(&config).unwrap();
+        let denoms = BTreeSet::from([atom.clone(), unknown.clone(), "uosmo".to_string()]);
+
+        let traces = resolve_denom_traces(&client, &denoms).await;
+        assert_eq!(traces[&atom].full_path(), "transfer/channel-0/uatom");
+        assert!(!traces.contains_key(&unknown));
+
+        // Served from the file this time.
+        let traces = resolve_denom_traces(&client, &BTreeSet::from([atom.clone()])).await;
+        assert_eq!(traces.len(), 1);
+        assert_eq!(DenomTraceCache::load(&cache).unwrap().get("osmosis-1", &atom), Some(&traces[&atom]));
+        trace.assert_async().await;
+        std::fs::remove_file(cache).unwrap();
+    }
+}
@@ -5,7 +5,7 @@ use prettytable::{Cell, Row, Table};
 use serde::Serialize;
 
 /// Dumps the given data to a CSV file.
-pub fn dump_to_csv<T: Serialize>(data: &Vec<T>, filename: String) -> Result<(), Box<dyn Error>> {
+pub fn dump_to_csv<T: Serialize>(data: &[T], filename: String) -> Result<(), Box<dyn Error>> {
     let mut writer = Writer::from_path(filename)?;
     for item in data {
         writer.serialize(item)?;
@@ -16,7 +16,7 @@ pub fn dump_to_csv<T: Serialize>(data: &Vec<T>, filename: String) -> Result<(),
 }
 
 /// display_pretty Dumps the given data to a Display using table
-pub fn display_pretty<T: Display>(data: &Vec<T>) {
+pub fn display_pretty<T: Display>(data: &[T]) {
     let mut table = Table::new();
     // Add a row per item
     for item in data.iter() {
@@ -0,0 +1,197 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::config::{EndpointStrategy, FailoverConfig};
+
+/// Weight of the newest sample in the moving average of response times.
+const LATENCY_SMOOTHING: f64 = 0.3;
+
+/// Health bookkeeping for a single endpoint.
+#[derive(Debug, Clone)]
+struct EndpointState {
+    url: String,
+    consecutive_failures: u32,
+    cooldown_until: Option<Instant>,
+    latency: Option<Duration>,
+}
+
+impl EndpointState {
+    fn is_available(&self, now: Instant) -> bool {
+        self.cooldown_until.is_none_or(|until| until <= now)
+    }
+}
+
+/// The set of endpoints of one chain, with the selection strategy and cooldown rules from `FailoverConfig`.
+///
+/// The pool is shared by all in-flight requests of an `HttpClient`; its lock is never held across an await.
+#[derive(Debug)]
+pub struct EndpointPool {
+    endpoints: Mutex<Vec<EndpointState>>,
+    next: AtomicUsize,
+    settings: FailoverConfig,
+}
+
+impl EndpointPool {
+    pub fn new(urls: Vec<String>, settings: FailoverConfig) -> Self {
+        let endpoints = urls.into_iter().map(|url| EndpointState {
+            url,
+            consecutive_failures: 0,
+            cooldown_until: None,
+            latency: None,
+        }).collect();
+
+        EndpointPool {
+            endpoints: Mutex::new(endpoints),
+            next: AtomicUsize::new(0),
+            settings,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn urls(&self) -> Vec<String> {
+        self.lock().iter().map(|endpoint| endpoint.url.clone()).collect()
+    }
+
+    /// Picks the endpoint for the next attempt according to the configured strategy.
+    ///
+    /// Endpoints in `tried` are only reused when every available endpoint has been tried already.
+    /// When all endpoints are cooling down, the one that comes back first is returned.
+    pub fn select(&self, tried: &[String]) -> String {
+        let now = Instant::now();
+        let endpoints = self.lock();
+
+        let available: Vec<&EndpointState> = endpoints.iter().filter(|e| e.is_available(now)).collect();
+        if available.is_empty() {
+            return endpoints.iter()
+                .min_by_key(|e| e.cooldown_until)
+                .map(|e| e.url.clone())
+                .unwrap_or_default();
+        }
+
+        let untried: Vec<&EndpointState> = available.iter().copied().filter(|e| !tried.contains(&e.url)).collect();
+        let candidates = if untried.is_empty() { available } else { untried };
+
+        let chosen = match self.settings.strategy {
+            EndpointStrategy::PrimaryWithFallback => candidates[0],
+            EndpointStrategy::RoundRobin => {
+                let turn = self.next.fetch_add(1, Ordering::Relaxed);
+                candidates[turn % candidates.len()]
+            }
+            EndpointStrategy::LowestLatency => candidates.iter()
+                .min_by_key(|e| e.latency.unwrap_or(Duration::MAX))
+                .copied()
+                .unwrap_or(candidates[0]),
+        };
+        chosen.url.clone()
+    }
+
+    /// Whether any endpoint other than those in `tried` can currently take a request.
+    pub fn has_untried(&self, tried: &[String]) -> bool {
+        let now = Instant::now();
+        self.lock().iter().any(|e| e.is_available(now) && !tried.contains(&e.url))
+    }
+
+    pub fn record_success(&self, url: &str, latency: Duration) {
+        self.update(url, |endpoint| {
+            endpoint.consecutive_failures = 0;
+            endpoint.cooldown_until = None;
+            endpoint.latency = Some(match endpoint.latency {
+                Some(previous) => previous.mul_f64(1.0 - LATENCY_SMOOTHING) + latency.mul_f64(LATENCY_SMOOTHING),
+                None => latency,
+            });
+        });
+    }
+
+    /// Counts a failure, taking the endpoint out of rotation once `max_failures` is reached.
+    pub fn record_failure(&self, url: &str) {
+        let max_failures = self.settings.max_failures.max(1);
+        let cooldown = self.cooldown();
+        self.update(url, |endpoint| {
+            endpoint.consecutive_failures += 1;
+            if endpoint.consecutive_failures >= max_failures {
+                eprintln!("Endpoint {} failed {} times in a row, out of rotation for {:?}", endpoint.url, endpoint.consecutive_failures, cooldown);
+                endpoint.cooldown_until = Some(Instant::now() + cooldown);
+            }
+        });
+    }
+
+    /// Takes the endpoint out of rotation straight away, e.g. when it reports a pruned height.
+    pub fn cool_down(&self, url: &str, reason: &str) {
+        let cooldown = self.cooldown();
+        self.update(url, |endpoint| {
+            eprintln!("Endpoint {} {}, out of rotation for {:?}", endpoint.url, reason, cooldown);
+            endpoint.cooldown_until = Some(Instant::now() + cooldown);
+        });
+    }
+
+    fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.settings.cooldown_secs)
+    }
+
+    fn update(&self, url: &str, f: impl FnOnce(&mut EndpointState)) {
+        if let Some(endpoint) = self.lock().iter_mut().find(|e| e.url == url) {
+            f(endpoint);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<EndpointState>> {
+        // A panic while holding the lock leaves plain bookkeeping behind, which is still usable.
+        self.endpoints.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(strategy: EndpointStrategy) -> EndpointPool {
+        let urls = vec!["http://a".to_string(), "http://b".to_string(), "http://c".to_string()];
+        EndpointPool::new(urls, FailoverConfig { strategy, max_failures: 2, cooldown_secs: 60, health_check: false })
+    }
+
+    #[test]
+    fn test_primary_with_fallback_skips_cooled_down_endpoint() {
+        let pool = pool(EndpointStrategy::PrimaryWithFallback);
+        assert_eq!(pool.select(&[]), "http://a");
+
+        pool.record_failure("http://a");
+        assert_eq!(pool.select(&[]), "http://a");
+        pool.record_failure("http://a");
+        assert_eq!(pool.select(&[]), "http://b");
+
+        pool.cool_down("http://b", "reported a pruned height");
+        assert_eq!(pool.select(&[]), "http://c");
+    }
+
+    #[test]
+    fn test_round_robin_cycles_through_endpoints() {
+        let pool = pool(EndpointStrategy::RoundRobin);
+        let picks: Vec<String> = (0..6).map(|_| pool.select(&[])).collect();
+        assert_eq!(picks, vec!["http://a", "http://b", "http://c", "http://a", "http://b", "http://c"]);
+    }
+
+    #[test]
+    fn test_lowest_latency_prefers_fastest_endpoint() {
+        let pool = pool(EndpointStrategy::LowestLatency);
+        pool.record_success("http://a", Duration::from_millis(300));
+        pool.record_success("http://b", Duration::from_millis(50));
+        pool.record_success("http://c", Duration::from_millis(120));
+
+        assert_eq!(pool.select(&[]), "http://b");
+        assert_eq!(pool.select(&["http://b".to_string()]), "http://c");
+    }
+
+    #[test]
+    fn test_select_when_all_endpoints_cool_down() {
+        let pool = pool(EndpointStrategy::PrimaryWithFallback);
+        for url in pool.urls() {
+            pool.cool_down(&url, "failed");
+        }
+        assert!(!pool.has_untried(&[]));
+        assert_eq!(pool.select(&[]), "http://a");
+    }
+}
@@ -0,0 +1,330 @@
+use std::error::Error;
+use std::fmt;
+
+use reqwest::StatusCode;
+
+use crate::api::client::is_retryable_status;
+use crate::models::transaction::TranslationError;
+
+/// Number of bytes of a response body kept on an error for diagnostics.
+const BODY_SNIPPET_LEN: usize = 512;
+
+/// What a failed request was fetching, so errors can point at the height or hash involved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FetchTarget {
+    Height(u64),
+    Hash(String),
+    /// An event query such as `recv_packet.packet_sequence='7'`.
+    Events(String),
+}
+
+impl fmt::Display for FetchTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchTarget::Height(height) => write!(f, "height {}", height),
+            FetchTarget::Hash(hash) => write!(f, "tx {}", hash),
+            FetchTarget::Events(query) => write!(f, "txs matching {}", query),
+        }
+    }
+}
+
+/// The request an error belongs to: the URL that was called and what it was fetching.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestContext {
+    pub url: String,
+    pub target: Option<FetchTarget>,
+}
+
+impl RequestContext {
+    pub fn new(url: impl Into<String>) -> Self {
+        RequestContext {
+            url: url.into(),
+            target: None,
+        }
+    }
+}
+
+impl fmt::Display for RequestContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.target {
+            Some(target) => write!(f, "{} ({})", self.url, target),
+            None => write!(f, "{}", self.url),
+        }
+    }
+}
+
+// Enum for custom error types
+#[derive(Debug)]
+pub enum FetchError {
+    /// The request could not be sent or the response could not be read, after all retries.
+    Network {
+        request: RequestContext,
+        attempts: u32,
+        source: reqwest::Error,
+    },
+    /// The node answered with a non-success status.
+    Status {
+        request: RequestContext,
+        status: StatusCode,
+        body: String,
+    },
+    /// The response body is not the JSON we expected.
+    Parse {
+        request: RequestContext,
+        body: String,
+        source: serde_json::Error,
+    },
+    /// The fetched data could not be translated into our transaction types.
+    Translate {
+        target: Option<FetchTarget>,
+        source: TranslationError,
+    },
+    /// The pages collected for a height do not add up to the total reported by the node.
+    IncompleteData { height: u64, expected: u64, received: u64 },
+    /// A request URL could not be built from the configured endpoint.
+    InvalidUrl { url: String, source: url::ParseError },
+    TaskFailure(String),
+}
+
+impl FetchError {
+    #![allow(dead_code)]
+    fn new(msg: &str) -> Self {
+        FetchError::TaskFailure(msg.to_string())
+    }
+
+    /// Builds a `Status` error, keeping only the first bytes of the body.
+    pub fn status(request: RequestContext, status: StatusCode, body: &str) -> Self {
+        FetchError::Status {
+            request,
+            status,
+            body: snippet(body),
+        }
+    }
+
+    /// Builds a `Parse` error, keeping only the first bytes of the body.
+    pub fn parse(request: RequestContext, body: &str, source: serde_json::Error) -> Self {
+        FetchError::Parse {
+            request,
+            body: snippet(body),
+            source,
+        }
+    }
+
+    /// Records the height or hash being fetched, unless the error already names one.
+    pub fn with_target(mut self, target: FetchTarget) -> Self {
+        let slot = match &mut self {
+            FetchError::Network { request, .. }
+            | FetchError::Status { request, .. }
+            | FetchError::Parse { request, .. } => &mut request.target,
+            FetchError::Translate { target, .. } => target,
+            _ => return self,
+        };
+        if slot.is_none() {
+            *slot = Some(target);
+        }
+        self
+    }
+
+    /// The HTTP status returned by the node, if the request got that far.
+    pub fn http_status(&self) -> Option<StatusCode> {
+        match self {
+            FetchError::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Whether the node refused the request because it pruned the height.
+    ///
+    /// Pruned nodes answer with a 4xx/5xx and a message such as
+    /// `height 100 is not available, lowest height is 2000`; other "not available" errors, such
+    /// as a `503 service not available`, are not about the height.
+    pub fn is_height_unavailable(&self) -> bool {
+        match self {
+            FetchError::Status { status, body, .. } if status.is_client_error() || status.is_server_error() => {
+                let body = body.to_lowercase();
+                body.contains("lowest height is") || names_unavailable_height(&body)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the node refused the request because the height is above its latest block
+    /// (`height 100 must be less than or equal to the current blockchain height 50`).
+    ///
+    /// Every healthy node answers so until the block is produced, so this is neither retried
+    /// nor held against the endpoint.
+    pub fn is_future_height(&self) -> bool {
+        match self {
+            FetchError::Status { body, .. } => body.to_lowercase().contains("must be less than or equal to the current blockchain height"),
+            _ => false,
+        }
+    }
+
+    /// Whether trying again later may succeed: the node could not be reached, was overloaded, or
+    /// has not produced or indexed the data yet. Pruned heights, rejected requests and responses
+    /// that do not parse never clear.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            FetchError::Network { .. } | FetchError::IncompleteData { .. } => true,
+            FetchError::Status { status, .. } => {
+                self.is_future_height() || (is_retryable_status(*status) && !self.is_height_unavailable())
+            }
+            _ => false,
+        }
+    }
+
+    /// The lowest height a pruned node still has, when its error names it
+    /// (`height 1 is not available, lowest height is 2000`).
+    pub fn lowest_available_height(&self) -> Option<u64> {
+        match self {
+            FetchError::Status { body, .. } => {
+                let body = body.to_lowercase();
+                let (_, rest) = body.split_once("lowest height is")?;
+                let digits: String = rest.trim_start().chars().take_while(char::is_ascii_digit).collect();
+                digits.parse().ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the LCD rejected `events` parameters because it takes a `query` string instead,
+    /// as Cosmos SDK v0.50 and later do (`query cannot be empty`).
+    pub fn is_query_required(&self) -> bool {
+        match self {
+            FetchError::Status { status, body, .. } if status.is_client_error() => {
+                body.to_lowercase().contains("query cannot be empty")
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Error for FetchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FetchError::Network { source, .. } => Some(source),
+            FetchError::Parse { source, .. } => Some(source),
+            FetchError::Translate { source, .. } => Some(source),
+            FetchError::InvalidUrl { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// Implementing the Display trait for FetchError
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchError::Network { request, attempts, source } => {
+                write!(f, "Network error for {} after {} attempt(s): {}", request, attempts, source)
+            }
+            FetchError::Status { request, status, body } => {
+                write!(f, "Request to {} returned {}: {}", request, status, body)
+            }
+            FetchError::Parse { request, body, source } => {
+                write!(f, "Failed to parse response from {}: {} (body: {})", request, source, body)
+            }
+            FetchError::Translate { target: Some(target), source } => {
+                write!(f, "Failed to translate data for {}: {}", target, source)
+            }
+            FetchError::Translate { target: None, source } => {
+                write!(f, "Failed to translate the fetched data: {}", source)
+            }
+            FetchError::IncompleteData { height, expected, received } => write!(
+                f,
+                "Incomplete data at height {}: node reported {} txs but {} were fetched",
+                height, expected, received
+            ),
+            FetchError::InvalidUrl { url, source } => write!(f, "Invalid url {}: {}", url, source),
+            FetchError::TaskFailure(msg) => write!(f, "Task failure: {}", msg),
+        }
+    }
+}
+
+impl From<TranslationError> for FetchError {
+    fn from(source: TranslationError) -> Self {
+        FetchError::Translate { target: None, source }
+    }
+}
+
+/// Whether the body contains `height N is not available`.
+fn names_unavailable_height(body: &str) -> bool {
+    body.match_indices("height ").any(|(index, matched)| {
+        let rest = &body[index + matched.len()..];
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        digits > 0 && rest[digits..].starts_with(" is not available")
+    })
+}
+
+/// Truncates a response body on a character boundary for inclusion in an error.
+fn snippet(body: &str) -> String {
+    if body.len() <= BODY_SNIPPET_LEN {
+        return body.to_string();
+    }
+    let mut end = BODY_SNIPPET_LEN;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &body[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_truncates_on_char_boundary() {
+        let body = "é".repeat(BODY_SNIPPET_LEN);
+        let truncated = snippet(&body);
+        assert!(truncated.len() <= BODY_SNIPPET_LEN + 3);
+        assert!(truncated.ends_with("..."));
+        assert_eq!(snippet("short"), "short");
+    }
+
+    #[test]
+    fn test_is_height_unavailable() {
+        let request = RequestContext::new("http://localhost/cosmos/tx/v1beta1/txs");
+        let pruned = FetchError::status(
+            request.clone(),
+            StatusCode::BAD_REQUEST,
+            r#"{"code":3,"message":"height 5 is not available, lowest height is 100"}"#,
+        );
+        let other = FetchError::status(request, StatusCode::BAD_REQUEST, r#"{"code":3,"message":"invalid hash"}"#);
+
+        assert!(pruned.is_height_unavailable());
+        assert!(!other.is_height_unavailable());
+        assert!(!pruned.is_future_height());
+        assert_eq!(pruned.lowest_available_height(), Some(100));
+        assert_eq!(other.lowest_available_height(), None);
+    }
+
+    #[test]
+    fn test_unavailable_service_and_future_height_are_not_pruned_heights() {
+        let request = RequestContext::new("http://localhost/cosmos/tx/v1beta1/txs");
+        let unavailable = FetchError::status(request.clone(), StatusCode::SERVICE_UNAVAILABLE, "service not available");
+        let future = FetchError::status(
+            request.clone(),
+            StatusCode::INTERNAL_SERVER_ERROR,
+            r#"{"jsonrpc":"2.0","id":-1,"error":{"code":-32603,"message":"Internal error","data":"height 100 must be less than or equal to the current blockchain height 50"}}"#,
+        );
+        let pruned = FetchError::status(request, StatusCode::INTERNAL_SERVER_ERROR, r#"{"error":{"data":"height 5 is not available"}}"#);
+
+        assert!(!unavailable.is_height_unavailable());
+        assert!(!unavailable.is_future_height());
+        assert!(!future.is_height_unavailable());
+        assert!(future.is_future_height());
+        assert!(pruned.is_height_unavailable());
+    }
+
+    #[test]
+    fn test_with_target_keeps_first_target() {
+        let error = FetchError::status(RequestContext::new("http://localhost"), StatusCode::BAD_GATEWAY, "")
+            .with_target(FetchTarget::Height(7))
+            .with_target(FetchTarget::Height(8));
+
+        match error {
+            FetchError::Status { request, .. } => assert_eq!(request.target, Some(FetchTarget::Height(7))),
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+}
@@ -1,44 +1,35 @@
-use std::fmt;
-use std::fmt::Display;
-use std::sync::Arc;
-
-use tokio::sync::Semaphore;
-use tokio::task;
-
-use crate::config::config::Config;
-use crate::models::transaction::{ComprehensiveTx, ResponseData, ResponseDataForHashQuery, Translate,IndividualMsgTx};
-
-
-
-// Enum for custom error types
-#[derive(Debug)]
-pub enum FetchError {
-    NetworkError,
-    ParseError,
-    TranslateError,
-    TaskFailure(String)
+use std::collections::HashSet;
+
+use futures::future::try_join_all;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::Deserialize;
+
+use crate::api::client::HttpClient;
+use crate::api::error::{FetchError, FetchTarget};
+use crate::api::rpc;
+use crate::config::config::{Backend, Config, TxQueryStyle};
+use crate::models::pagination::Pagination;
+use crate::models::transaction::{ComprehensiveTx, ResponseData, ResponseDataForHashQuery, Translate, IndividualMsgTx, Tx, TxResponse};
+
+/// LCD path of the `GetTxsEvent` and `GetTx` endpoints.
+const TXS_PATH: &str = "/cosmos/tx/v1beta1/txs";
+
+/// A `GetTxsEvent` page from a node taking a `query` string, which reports the total next to the
+/// txs instead of in `pagination`.
+#[derive(Deserialize)]
+struct QueryTxsPage {
+    #[serde(default)]
+    txs: Vec<Tx>,
+    #[serde(default)]
+    tx_responses: Vec<TxResponse>,
+    #[serde(default)]
+    total: String,
 }
 
-impl FetchError {
-    #![allow(dead_code)]
-    fn new(msg: &str) -> Self {
-        FetchError::TaskFailure(msg.to_string())
-    }
-}
-impl std::error::Error for FetchError {}
+/// Event attributes naming the accounts a transaction involves; an address query matches any of them.
+const ADDRESS_EVENT_KEYS: [&str; 3] = ["message.sender", "transfer.recipient", "transfer.sender"];
 
 
-// Implementing the Display trait for FetchError
-impl fmt::Display for FetchError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            FetchError::NetworkError => write!(f, "Network error occurred during fetch"),
-            FetchError::ParseError => write!(f, "Failed to parse the fetched data"),
-            FetchError::TranslateError => write!(f, "Failed to translate the fetched data"),
-            FetchError::TaskFailure(msg) => write!(f, "Task failure: {}", msg),
-        }
-    }
-}
 
 /// Fetches transaction data for a specific block height from the Cosmos SDK REST endpoint.
 ///
@@ -51,7 +42,7 @@ impl fmt::Display for FetchError {
 ///
 /// # Arguments
 ///
-/// * `config` - A reference to `Config` struct containing the URL of the Cosmos API.
+/// * `client` - The shared `HttpClient`, carrying the `Config` with the URL of the Cosmos API.
 ///
 /// * `height` - A `u64` value representing the block height of interest.
 ///
@@ -86,10 +77,16 @@ impl fmt::Display for FetchError {
 /// }
 /// ```
 ///
+/// # Backends
+///
+/// With `backend = "rpc"` the block is read from the CometBFT RPC instead, see
+/// `rpc::fetch_transactions_for_height`; the result has the same shape.
+///
 /// # Errors
 ///
-/// This function can return `FetchError::NetworkError` if there's a problem with the network
-/// request, and `FetchError::ParseError` if there's an issue parsing the JSON response.
+/// This function can return `FetchError::Network` or `FetchError::Status` if there's a problem
+/// with the network request, and `FetchError::Parse` if there's an issue parsing the JSON
+/// response. Each carries the request URL and the height or hash involved.
 ///
 /// # Panics
 ///
@@ -100,43 +97,154 @@ impl fmt::Display for FetchError {
 ///
 /// This function doesn't perform unsafe operations. Ensure that the returned `Result`
 /// is managed properly in the calling context to address potential errors.
-pub fn fetch_transactions_for_height(config: &Config, height: u64) -> Result<Vec<ResponseData>, FetchError> {
+pub async fn fetch_transactions_for_height(client: &HttpClient, height: u64) -> Result<Vec<ResponseData>, FetchError> {
+    if client.config().backend() == Backend::Rpc {
+        return rpc::fetch_transactions_for_height(client, height).await;
+    }
+
+    let events = [format!("tx.height={}", height)];
+    let (all_data, expected_total, received) = fetch_event_pages(client, &events, &[], FetchTarget::Height(height)).await?;
+
+    // Some nodes ignore `count_total` and report zero, so only a non-zero total is checked.
+    match expected_total {
+        Some(expected) if expected > 0 && expected != received => {
+            Err(FetchError::IncompleteData { height, expected, received })
+        }
+        _ => Ok(all_data),
+    }
+}
+
+/// Fetches every transaction matching all of the given event conditions, such as
+/// `recv_packet.packet_sequence='7'`.
+///
+/// The LCD is queried with `GetTxsEvent` and the RPC with `/tx_search`; either way the result
+/// has the same shape as a height query.
+pub async fn fetch_transactions_by_events(client: &HttpClient, events: &[String]) -> Result<Vec<ResponseData>, FetchError> {
+    if client.config().backend() == Backend::Rpc {
+        return rpc::search_transactions(client, events).await;
+    }
+
+    let target = FetchTarget::Events(events.join(" AND "));
+    let (all_data, _, _) = fetch_event_pages(client, events, &[], target).await?;
+    Ok(all_data)
+}
+
+/// Pages through `GetTxsEvent` for a set of conditions, returning the pages together with the
+/// total reported on the first page and the number of transactions received.
+///
+/// Unless the config names the node's style, conditions are sent as `events` first; a node that
+/// asks for a `query` string instead (SDK v0.50+) is queried that way from then on. `query_only`
+/// conditions, such as height bounds, are added to a `query` string and dropped from `events`,
+/// which only accept `key=value`.
+async fn fetch_event_pages(client: &HttpClient, events: &[String], query_only: &[String], target: FetchTarget) -> Result<(Vec<ResponseData>, Option<u64>, u64), FetchError> {
+    match client.tx_query_style() {
+        Some(TxQueryStyle::Events) => fetch_events_param_pages(client, events, target).await,
+        Some(TxQueryStyle::Query) => fetch_query_param_pages(client, &[events, query_only].concat(), target).await,
+        None => match fetch_events_param_pages(client, events, target.clone()).await {
+            Err(error) if error.is_query_required() => {
+                client.set_tx_query_style(TxQueryStyle::Query);
+                fetch_query_param_pages(client, &[events, query_only].concat(), target).await
+            }
+            result => {
+                if result.is_ok() {
+                    client.set_tx_query_style(TxQueryStyle::Events);
+                }
+                result
+            }
+        },
+    }
+}
+
+/// Follows `pagination.key` through `GetTxsEvent` with one `events` parameter per condition.
+async fn fetch_events_param_pages(client: &HttpClient, events: &[String], target: FetchTarget) -> Result<(Vec<ResponseData>, Option<u64>, u64), FetchError> {
     let mut all_data = Vec::new();
-    let next_key: Option<String> = None;
+    let mut next_key: Option<String> = None;
+    let mut expected_total: Option<u64> = None;
+    let mut received: u64 = 0;
 
     loop {
-        let url = if let Some(ref nk) = next_key {
-            format!("{}/cosmos/tx/v1beta1/txs?events=tx.height={}&pagination_key={}", config.url(), height, nk)
-        } else {
-            format!("{}/cosmos/tx/v1beta1/txs?events=tx.height={}", config.url(), height)
-        };
+        let params = txs_by_events_params(client.config(), events, next_key.as_deref());
 
-        println!("{}", url);
+        let data: ResponseData = client.get_json(TXS_PATH, &params).await
+            .map_err(|e| e.with_target(target.clone()))?;
 
-        let res = reqwest::blocking::get(&url).map_err(|_| FetchError::NetworkError)?;
-        let res_text = res.text().map_err(|_| FetchError::ParseError)?;
+        // The total is only meaningful on the first page; later pages may report zero.
+        if expected_total.is_none() {
+            expected_total = data.pagination.total.parse::<u64>().ok();
+        }
+        received += data.tx_responses.len() as u64;
 
-        let data: ResponseData = serde_json::from_str(&res_text).map_err(|_| FetchError::ParseError)?;
-        // Check if there's a next page
-        let next_key = data.pagination.next_key.clone();
+        let page_key = data.pagination.next_key.clone().filter(|key| !key.is_empty());
 
         // Now, push the data to all_data
         all_data.push(data);
 
-        // Check if there's a next page
-        if next_key.is_none() {
+        match page_key {
+            // A node handing back the key we just sent would otherwise keep us here forever.
+            Some(key) if next_key.as_deref() == Some(key.as_str()) => {
+                return Err(FetchError::TaskFailure(format!("pagination key repeated for {}", target)));
+            }
+            Some(key) => next_key = Some(key),
+            None => break,
+        }
+    }
+
+    Ok((all_data, expected_total, received))
+}
+
+/// Builds the `GetTxsEvent` query for a set of event conditions, passing the pagination parameters on every page.
+fn txs_by_events_params(config: &Config, events: &[String], key: Option<&str>) -> Vec<(&'static str, String)> {
+    let mut params: Vec<(&'static str, String)> = events.iter().map(|event| ("events", event.clone())).collect();
+    params.push(("pagination.limit", config.page_size().to_string()));
+    params.push(("pagination.count_total", "true".to_string()));
+    if let Some(key) = key {
+        params.push(("pagination.key", key.to_string()));
+    }
+    params
+}
+
+/// Pages through `GetTxsEvent` with the conditions ANDed into a `query` string, using `page` and
+/// `limit` since these nodes no longer hand out a pagination key.
+async fn fetch_query_param_pages(client: &HttpClient, events: &[String], target: FetchTarget) -> Result<(Vec<ResponseData>, Option<u64>, u64), FetchError> {
+    let query = events.join(" AND ");
+    let limit = client.config().page_size();
+    let mut all_data = Vec::new();
+    let mut expected_total: Option<u64> = None;
+    let mut received: u64 = 0;
+
+    for page in 1.. {
+        let params = [
+            ("query", query.clone()),
+            ("page", page.to_string()),
+            ("limit", limit.to_string()),
+        ];
+        let data: QueryTxsPage = client.get_json(TXS_PATH, &params).await
+            .map_err(|e| e.with_target(target.clone()))?;
+
+        if expected_total.is_none() {
+            expected_total = data.total.parse::<u64>().ok();
+        }
+        let fetched = data.tx_responses.len() as u64;
+        received += fetched;
+        all_data.push(ResponseData {
+            txs: data.txs,
+            tx_responses: data.tx_responses,
+            pagination: Pagination { next_key: None, total: data.total },
+        });
+
+        if fetched < limit || expected_total.is_some_and(|total| received >= total) {
             break;
         }
     }
 
-    Ok(all_data)
+    Ok((all_data, expected_total, received))
 }
 
 /// Fetches transaction data for a given block height from the Cosmos API.
 ///
 /// # Arguments
 ///
-/// * `config` - A reference to `Config` struct containing the URL of the Cosmos API.
+/// * `client` - The shared `HttpClient`, carrying the `Config` with the URL of the Cosmos API.
 /// * `height` - The block height for which we want to retrieve transaction data.
 ///
 /// # Returns
@@ -171,31 +279,15 @@ pub fn fetch_transactions_for_height(config: &Config, height: u64) -> Result<Vec
 ///     }
 /// }
 /// ```
-pub async fn fetch_transactions_for_height_range(config: Arc<Config>, start_height: u64, end_height: u64) -> Result<Vec<ResponseData>, FetchError> {
-    let mut all_data = Vec::new();
-
-    // Create a semaphore with 10 permits
-    let semaphore = Arc::new(Semaphore::new(10));
-
-    let handles: Vec<_> = (start_height..=end_height).map(|height| {
-        let config = config.clone(); // This clones the Arc, not the Config itself
-        let sem_clone = semaphore.clone();
-        task::spawn_blocking(move || {
-            // Acquire a permit
-            let _permit = sem_clone.acquire();
-            // Once we have a permit, fetch the data
-            fetch_transactions_for_height(&config, height)
-        })
-    }).collect();
-
-    for handle in handles {
-        match handle.await {
-            Ok(data) => all_data.extend(data?),
-            Err(_) => return Err(FetchError::TaskFailure(String::from("Task failed"))),
-        };
-    }
-
-    Ok(all_data)
+pub async fn fetch_transactions_for_height_range(client: &HttpClient, start_height: u64, end_height: u64) -> Result<Vec<ResponseData>, FetchError> {
+    let pages: Vec<Vec<ResponseData>> = stream::iter(start_height..=end_height)
+        .map(|height| fetch_transactions_for_height(client, height))
+        // At most `concurrency` heights are in flight; `buffered` still yields them in height order.
+        .buffered(client.config().concurrency())
+        .try_collect()
+        .await?;
+
+    Ok(pages.into_iter().flatten().collect())
 }
 /// Fetches transaction data from the Cosmos SDK REST endpoint based on a given transaction hash.
 ///
@@ -205,12 +297,12 @@ pub async fn fetch_transactions_for_height_range(config: Arc<Config>, start_heig
 /// into a `ResponseDataForHashQuery` structure.
 ///
 /// Any encountered network issues, such as connection failures or timeouts, will result
-/// in a `FetchError::NetworkError`. Similarly, any problems during the JSON parsing phase
-/// will yield a `FetchError::ParseError`.
+/// in a `FetchError::Network` or `FetchError::Status`. Similarly, any problems during the
+/// JSON parsing phase will yield a `FetchError::Parse`.
 ///
 /// # Arguments
 ///
-/// * `config` - A reference to `Config` struct containing the URL of the Cosmos API.
+/// * `client` - The shared `HttpClient`, carrying the `Config` with the URL of the Cosmos API.
 /// * `tx_hash` - A string slice representing the transaction hash of interest.
 ///
 /// # Returns
@@ -245,8 +337,9 @@ pub async fn fetch_transactions_for_height_range(config: Arc<Config>, start_heig
 ///
 /// # Errors
 ///
-/// This function can return `FetchError::NetworkError` if there's a problem with the network
-/// request, and `FetchError::ParseError` if there's an issue parsing the JSON response.
+/// This function can return `FetchError::Network` or `FetchError::Status` if there's a problem
+/// with the network request, and `FetchError::Parse` if there's an issue parsing the JSON
+/// response. Each carries the request URL and the height or hash involved.
 ///
 /// # Panics
 ///
@@ -257,112 +350,385 @@ pub async fn fetch_transactions_for_height_range(config: Arc<Config>, start_heig
 ///
 /// This function doesn't perform unsafe operations. Ensure you handle the returned
 /// `Result` appropriately in the calling context to manage any potential errors.
-pub fn fetch_by_tx_hash(config: &Config, tx_hash: &str) -> Result<ResponseDataForHashQuery, FetchError> {
-    let url = format!("{}/cosmos/tx/v1beta1/txs/{}", config.url(), tx_hash);
-
-    // Try making the HTTP request
-    let res = reqwest::blocking::get(&url).map_err(|e| {
-        eprintln!("Network error: {}", e);  // Log the actual error for more context
-        FetchError::NetworkError
-    })?;
-
-    // Ensure we received a successful status code before trying to parse
-    if !res.status().is_success() {
-        return Err(FetchError::NetworkError);
+pub async fn fetch_by_tx_hash(client: &HttpClient, tx_hash: &str) -> Result<ResponseDataForHashQuery, FetchError> {
+    if client.config().backend() == Backend::Rpc {
+        return rpc::fetch_by_tx_hash(client, tx_hash).await;
     }
 
-    // Attempt to get the response text
-    let res_text = res.text().map_err(|e| {
-        eprintln!("Error retrieving text from response: {}", e);
-        FetchError::ParseError
-    })?;
+    let path = format!("{}/{}", TXS_PATH, tx_hash);
 
-    // Try parsing the JSON response
-    let data: ResponseDataForHashQuery = serde_json::from_str(&res_text).map_err(|e| {
-        eprintln!("JSON parsing error: {}", e);
-        FetchError::ParseError
-    })?;
+    // Retries, failover and status handling are done by the shared client
+    let data: ResponseDataForHashQuery = client.get_json(&path, &[]).await
+        .map_err(|e| e.with_target(FetchTarget::Hash(tx_hash.to_string())))?;
 
     Ok(data)
 }
 
 /// Fetches and translates a transaction's data into its comprehensive format using its hash.
 ///
-/// Given a `Config` instance and a transaction hash, this function first fetches the transaction data
+/// Given an `HttpClient` and a transaction hash, this function first fetches the transaction data
 /// using `fetch_by_tx_hash` method. Post fetching, it translates the raw data into a `ComprehensiveTx` format.
 ///
 /// # Parameters
 ///
-/// * `config`: A reference to the configuration used to access necessary endpoints or services.
+/// * `client`: The shared `HttpClient` used to access the configured endpoint.
 /// * `tx_hash`: A string slice that represents the transaction hash.
 ///
 /// # Returns
 ///
 /// * `Ok(Vec<ComprehensiveTx>)`: A successful result containing a vector of `ComprehensiveTx` objects.
 /// * `Err(FetchError)`: An error result indicating a problem encountered during the fetch or translation process.
-///                      The error might be due to network issues, parsing problems, or translation failures.
+///   The error might be due to network issues, parsing problems, or translation failures.
 ///
 /// # Errors
 ///
-/// This function can return `FetchError::TranslateError` if there's a problem translating the raw data into `ComprehensiveTx` format.
+/// This function can return `FetchError::Translate` if there's a problem translating the raw data into `ComprehensiveTx` format.
 ///
 /// # Example
 ///
 /// ```rust
-/// # use your_crate_name::Config;
-/// let config = Config::new();
+/// # use your_crate_name::{Config, HttpClient};
+/// let client = HttpClient::new(&Config::default())?;
 /// let tx_hash = "some_hash_string";
-/// let result = get_comprehensive_tx_data_for_hash(&config, tx_hash);
+/// let result = get_comprehensive_tx_data_for_hash(&client, tx_hash).await;
 /// match result {
 ///     Ok(data) => println!("Transaction data: {:?}", data),
 ///     Err(e) => eprintln!("Failed to fetch transaction data: {}", e),
 /// }
-/// #![allow(dead_code)]
-pub fn get_comprehensive_tx_data_for_hash(config: &Config, tx_hash: &str) -> Result<Vec<ComprehensiveTx>, FetchError> {
+/// ```
+#[allow(dead_code)]
+pub async fn get_comprehensive_tx_data_for_hash(client: &HttpClient, tx_hash: &str) -> Result<Vec<ComprehensiveTx>, FetchError> {
     // translate to comprehensive and handle the error
-    let data = fetch_by_tx_hash(config, tx_hash)?;
-    data.translate().map_err(|_e| {
-        FetchError::TranslateError
+    let data = fetch_by_tx_hash(client, tx_hash).await?;
+    data.translate().map_err(|e| {
+        FetchError::from(e).with_target(FetchTarget::Hash(tx_hash.to_string()))
     })
 }
 
 /// Fetches and translates a transaction's data into its comprehensive format using its height.
-pub fn get_comprehensive_tx_data_for_height(config: &Config, height: u64) -> Result<Vec<ComprehensiveTx>, FetchError> {
-    let data = fetch_transactions_for_height(config, height)?;
+pub async fn get_comprehensive_tx_data_for_height(client: &HttpClient, height: u64) -> Result<Vec<ComprehensiveTx>, FetchError> {
+    let data = fetch_transactions_for_height(client, height).await?;
+    let mut comprehensive_txs = Vec::new();
+    for response_data in data {
+        let mut txs = response_data.translate().map_err(|e| {
+            FetchError::from(e).with_target(FetchTarget::Height(height))
+        })?;
+        comprehensive_txs.append(&mut txs);
+    }
+    Ok(comprehensive_txs)
+}
+
+/// Fetches and translates every transaction in an inclusive height range, in height order.
+///
+/// Heights are fetched concurrently, bounded by `Config::concurrency`.
+pub async fn get_comprehensive_tx_data_for_height_range(client: &HttpClient, start_height: u64, end_height: u64) -> Result<Vec<ComprehensiveTx>, FetchError> {
+    let per_height: Vec<Vec<ComprehensiveTx>> = stream::iter(start_height..=end_height)
+        .map(|height| get_comprehensive_tx_data_for_height(client, height))
+        .buffered(client.config().concurrency())
+        .try_collect()
+        .await?;
+
+    Ok(per_height.into_iter().flatten().collect())
+}
+
+/// Fetches and translates every transaction matching all of the given event conditions.
+pub async fn get_comprehensive_tx_data_for_events(client: &HttpClient, events: &[String]) -> Result<Vec<ComprehensiveTx>, FetchError> {
+    let data = fetch_transactions_by_events(client, events).await?;
     let mut comprehensive_txs = Vec::new();
     for response_data in data {
-        let mut txs = response_data.translate().map_err(|_e| {
-            FetchError::TranslateError
+        let mut txs = response_data.translate().map_err(|e| {
+            FetchError::from(e).with_target(FetchTarget::Events(events.join(" AND ")))
         })?;
         comprehensive_txs.append(&mut txs);
     }
     Ok(comprehensive_txs)
 }
 
-pub fn get_comprehensive_tx_data_for_height_range(config: &Config, start_height: u64, end_height: u64) -> Result<Vec<ComprehensiveTx>, FetchError> {
+/// Fetches and translates every transaction involving `address` as message sender, transfer
+/// recipient or transfer sender, within the optional inclusive height bounds.
+///
+/// Nodes AND the conditions of a query, so each attribute is queried on its own and the results
+/// are merged, deduplicated by tx hash and returned in height order.
+pub async fn get_comprehensive_tx_data_for_address(client: &HttpClient, address: &str, from_height: Option<u64>, to_height: Option<u64>) -> Result<Vec<ComprehensiveTx>, FetchError> {
+    let mut bounds = Vec::new();
+    bounds.extend(from_height.map(|height| format!("tx.height>={}", height)));
+    bounds.extend(to_height.map(|height| format!("tx.height<={}", height)));
+    let queries: Vec<Vec<String>> = ADDRESS_EVENT_KEYS.iter().map(|key| vec![format!("{}='{}'", key, address)]).collect();
+    let results = try_join_all(queries.iter().map(|events| get_comprehensive_tx_data_within(client, events, &bounds))).await?;
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut comprehensive_txs: Vec<ComprehensiveTx> = results.into_iter().flatten()
+        .filter(|tx| seen.insert(tx.tx_hash.clone()))
+        .collect();
+    // The LCD's `events` style cannot take the height bounds, so there they are applied here.
+    if client.config().backend() == Backend::Lcd && client.tx_query_style() == Some(TxQueryStyle::Events) {
+        comprehensive_txs.retain(|tx| {
+            from_height.is_none_or(|height| tx.height >= height) && to_height.is_none_or(|height| tx.height <= height)
+        });
+    }
+    // The sort is stable, so transactions of one block keep the order the node returned them in.
+    comprehensive_txs.sort_by_key(|tx| tx.height);
+    Ok(comprehensive_txs)
+}
+
+/// Fetches and translates every transaction matching all of `events` and, where the node can
+/// search by them, the `tx.height` `bounds`.
+async fn get_comprehensive_tx_data_within(client: &HttpClient, events: &[String], bounds: &[String]) -> Result<Vec<ComprehensiveTx>, FetchError> {
+    let conditions = [events, bounds].concat();
+    let target = FetchTarget::Events(conditions.join(" AND "));
+    let data = match client.config().backend() {
+        Backend::Rpc => rpc::search_transactions(client, &conditions).await?,
+        Backend::Lcd => fetch_event_pages(client, events, bounds, target.clone()).await?.0,
+    };
     let mut comprehensive_txs = Vec::new();
-    for height in start_height..=end_height {
-        let data = fetch_transactions_for_height(config, height)?;
-        for response_data in data {
-            let mut txs = response_data.translate().map_err(|_e| {
-                FetchError::TranslateError
-            })?;
-            comprehensive_txs.append(&mut txs);
-        }
+    for response_data in data {
+        let mut txs = response_data.translate().map_err(|e| FetchError::from(e).with_target(target.clone()))?;
+        comprehensive_txs.append(&mut txs);
     }
     Ok(comprehensive_txs)
 }
 
-pub fn get_individual_txs_from_comprehensive_txs(comprehensive_txs: &Vec<ComprehensiveTx>) -> Result<Vec<IndividualMsgTx>, FetchError> {
+/// Splits transactions into one row per message; with `expand_authz`, messages wrapped in an
+/// authz `MsgExec` get rows of their own as well.
+pub fn get_individual_txs_from_comprehensive_txs(comprehensive_txs: &[ComprehensiveTx], expand_authz: bool) -> Result<Vec<IndividualMsgTx>, FetchError> {
+    if !expand_authz {
+        return Ok(comprehensive_txs.translate()?);
+    }
+
     let mut individual_msg_txs = Vec::new();
-    individual_msg_txs = comprehensive_txs.translate().map_err(|_e| {
-        FetchError::TranslateError
-    })?;
+    for comprehensive_tx in comprehensive_txs {
+        individual_msg_txs.extend(comprehensive_tx.to_expanded_individual_transactions()?);
+    }
     Ok(individual_msg_txs)
 }
 
+#[cfg(test)]
+mod tests {
+    use mockito::Matcher;
+    use serde_json::{json, Value};
 
+    use super::*;
+    use crate::models::testing::{tx_json, tx_response_json};
 
+    fn mock_client(url: String, page_size: u64) -> HttpClient {
+        let config = Config {
+            url,
+            page_size,
+            ..Config::default()
+        };
+        HttpClient::new(&config).unwrap()
+    }
+
+    fn mock_page(height: u64, hashes: &[&str], next_key: Option<&str>, total: u64) -> String {
+        json!({
+            "txs": hashes.iter().map(|_| tx_json(json!([]))).collect::<Vec<_>>(),
+            "tx_responses": hashes.iter().map(|hash| tx_response_json(height, hash)).collect::<Vec<_>>(),
+            "pagination": { "next_key": next_key, "total": total.to_string() }
+        }).to_string()
+    }
 
+    #[tokio::test]
+    async fn test_fetch_transactions_for_height_follows_next_key() {
+        let mut server = mockito::Server::new_async().await;
+        let first = server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("events".into(), "tx.height=10".into()),
+                Matcher::UrlEncoded("pagination.limit".into(), "2".into()),
+                Matcher::UrlEncoded("pagination.count_total".into(), "true".into()),
+            ]))
+            .with_body(mock_page(10, &["A", "B"], Some("a2V5+/=="), 3))
+            .create_async()
+            .await;
+        let second = server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("pagination.key".into(), "a2V5+/==".into()),
+                Matcher::UrlEncoded("pagination.limit".into(), "2".into()),
+                Matcher::UrlEncoded("pagination.count_total".into(), "true".into()),
+            ]))
+            .with_body(mock_page(10, &["C"], None, 0))
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url(), 2);
+        let pages = fetch_transactions_for_height(&client, 10).await.expect("pagination should succeed");
+
+        first.assert_async().await;
+        second.assert_async().await;
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages.iter().map(|page| page.tx_responses.len()).sum::<usize>(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_transactions_for_height_detects_missing_txs() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::Any)
+            .with_body(mock_page(10, &["A"], None, 5))
+            .create_async()
+            .await;
 
+        let client = mock_client(server.url(), 100);
+        let result = fetch_transactions_for_height(&client, 10).await;
 
+        assert!(matches!(result, Err(FetchError::IncompleteData { height: 10, expected: 5, received: 1 })));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_transactions_for_height_stops_on_repeated_key() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::Any)
+            .with_body(mock_page(10, &["A"], Some("c2FtZQ=="), 0))
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url(), 1);
+        let result = fetch_transactions_for_height(&client, 10).await;
+
+        assert!(matches!(result, Err(FetchError::TaskFailure(_))));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_transactions_for_height_range_keeps_height_order() {
+        let mut server = mockito::Server::new_async().await;
+        for height in 1..=5u64 {
+            let hash = format!("HASH{}", height);
+            server.mock("GET", "/cosmos/tx/v1beta1/txs")
+                .match_query(Matcher::UrlEncoded("events".into(), format!("tx.height={}", height)))
+                .with_body(mock_page(height, &[hash.as_str()], None, 1))
+                .create_async()
+                .await;
+        }
+
+        let client = mock_client(server.url(), 100);
+        let txs = get_comprehensive_tx_data_for_height_range(&client, 1, 5).await.unwrap();
+
+        let hashes: Vec<Value> = txs.iter().map(|tx| serde_json::to_value(tx).unwrap()["tx_hash"].clone()).collect();
+        assert_eq!(hashes, vec!["HASH1", "HASH2", "HASH3", "HASH4", "HASH5"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_comprehensive_tx_data_for_address_merges_queries() {
+        let mut server = mockito::Server::new_async().await;
+        let pages = [
+            ("message.sender='cosmos1abc'", mock_page(12, &["SENT"], None, 1)),
+            ("transfer.recipient='cosmos1abc'", mock_page(5, &["RECEIVED"], None, 1)),
+            ("transfer.sender='cosmos1abc'", mock_page(12, &["SENT"], None, 1)),
+        ];
+        for (event, page) in pages {
+            server.mock("GET", "/cosmos/tx/v1beta1/txs")
+                .match_query(Matcher::UrlEncoded("events".into(), event.into()))
+                .with_body(page)
+                .create_async()
+                .await;
+        }
+
+        let client = mock_client(server.url(), 100);
+        let txs = get_comprehensive_tx_data_for_address(&client, "cosmos1abc", None, None).await.unwrap();
+        let hashes: Vec<&str> = txs.iter().map(|tx| tx.tx_hash.as_str()).collect();
+        assert_eq!(hashes, vec!["RECEIVED", "SENT"]);
+
+        let txs = get_comprehensive_tx_data_for_address(&client, "cosmos1abc", Some(6), Some(20)).await.unwrap();
+        let hashes: Vec<&str> = txs.iter().map(|tx| tx.tx_hash.as_str()).collect();
+        assert_eq!(hashes, vec!["SENT"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_comprehensive_tx_data_for_address_queries_height_bounds() {
+        let mut server = mockito::Server::new_async().await;
+        let mut mocks = Vec::new();
+        for (key, hash) in ADDRESS_EVENT_KEYS.iter().zip(["SENT", "RECEIVED", "SENT"]) {
+            let query = format!("{}='cosmos1abc' AND tx.height>=6 AND tx.height<=20", key);
+            mocks.push(server.mock("GET", "/cosmos/tx/v1beta1/txs")
+                .match_query(Matcher::UrlEncoded("query".into(), query))
+                .with_body(json!({
+                    "txs": [tx_json(json!([]))],
+                    "tx_responses": [tx_response_json(12, hash)],
+                    "pagination": null,
+                    "total": "1"
+                }).to_string())
+                .expect(1)
+                .create_async()
+                .await);
+        }
+
+        let config = Config { url: server.url(), tx_query: Some(TxQueryStyle::Query), ..Config::default() };
+        let client = HttpClient::new(&config).unwrap();
+        let txs = get_comprehensive_tx_data_for_address(&client, "cosmos1abc", Some(6), Some(20)).await.unwrap();
+        let hashes: Vec<&str> = txs.iter().map(|tx| tx.tx_hash.as_str()).collect();
+        assert_eq!(hashes, vec!["SENT", "RECEIVED"]);
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_transactions_by_events_switches_to_query_param() {
+        let mut server = mockito::Server::new_async().await;
+        let rejected = server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::Regex("^events=".into()))
+            .with_status(400)
+            .with_body(r#"{"code":3,"message":"query cannot be empty","details":[]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let query = "wasm._contract_address='juno1x' AND wasm.action='swap'";
+        let first = server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("query".into(), query.into()),
+                Matcher::UrlEncoded("page".into(), "1".into()),
+                Matcher::UrlEncoded("limit".into(), "2".into()),
+            ]))
+            .with_body(json!({
+                "txs": [tx_json(json!([])), tx_json(json!([]))],
+                "tx_responses": [tx_response_json(3, "A"), tx_response_json(4, "B")],
+                "pagination": null,
+                "total": "3"
+            }).to_string())
+            .expect(2)
+            .create_async()
+            .await;
+        let second = server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("query".into(), query.into()),
+                Matcher::UrlEncoded("page".into(), "2".into()),
+            ]))
+            .with_body(json!({ "txs": [tx_json(json!([]))], "tx_responses": [tx_response_json(5, "C")], "total": "3" }).to_string())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url(), 2);
+        let events = vec!["wasm._contract_address='juno1x'".to_string(), "wasm.action='swap'".to_string()];
+        let txs = get_comprehensive_tx_data_for_events(&client, &events).await.unwrap();
+        assert_eq!(txs.iter().map(|tx| tx.tx_hash.as_str()).collect::<Vec<_>>(), vec!["A", "B", "C"]);
+        assert_eq!(client.tx_query_style(), Some(TxQueryStyle::Query));
+
+        // The detected style is kept, so the node is not asked for `events` again.
+        get_comprehensive_tx_data_for_events(&client, &events).await.unwrap();
+        rejected.assert_async().await;
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fetch_transactions_for_height_reports_parse_context() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::Any)
+            .with_body(r#"{"txs": "unexpected"}"#)
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url(), 100);
+        match fetch_transactions_for_height(&client, 42).await {
+            Err(FetchError::Parse { request, body, .. }) => {
+                assert_eq!(request.target, Some(FetchTarget::Height(42)));
+                assert!(request.url.contains("tx.height%3D42"));
+                assert!(body.contains("unexpected"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
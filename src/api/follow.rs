@@ -0,0 +1,314 @@
+use std::error::Error;
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+use crate::api::client::HttpClient;
+use crate::api::fetcher::get_comprehensive_tx_data_for_height;
+use crate::api::error::FetchError;
+use crate::api::heights::{fetch_block_header, fetch_block_tx_count};
+use crate::config::config::Backend;
+use crate::models::rpc::Header;
+use crate::models::transaction::ComprehensiveTx;
+
+/// CometBFT event query announcing every new block header.
+const NEW_BLOCK_HEADER_QUERY: &str = "tm.event='NewBlockHeader'";
+
+/// How long a subscription may stay silent before it is given up for polling.
+const SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(60);
+
+type Subscription = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// A message pushed on a CometBFT websocket: the subscription ack, an event, or an error.
+#[derive(Deserialize)]
+struct SubscriptionMessage {
+    #[serde(default)]
+    result: Option<SubscriptionResult>,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct SubscriptionResult {
+    #[serde(default)]
+    data: Option<SubscriptionData>,
+}
+
+#[derive(Deserialize)]
+struct SubscriptionData {
+    value: NewBlockHeader,
+}
+
+#[derive(Deserialize)]
+struct NewBlockHeader {
+    header: Header,
+}
+
+/// Hands out the transactions of every block in height order as the chain produces them.
+///
+/// New blocks are announced by a CometBFT websocket subscription when the RPC backend offers one,
+/// and otherwise found by polling the latest block. Neither a failed fetch nor a dropped
+/// subscription skips a height: the height is retried until it is fetched in full, and a broken
+/// subscription falls back to polling.
+pub struct BlockFollower<'a> {
+    client: &'a HttpClient,
+    poll_interval: Duration,
+    subscription: Option<Subscription>,
+    next_height: u64,
+    to_height: Option<u64>,
+    latest: u64,
+}
+
+impl<'a> BlockFollower<'a> {
+    /// Starts following at `from_height`, or at the latest block, up to `to_height` if given.
+    pub async fn start(client: &'a HttpClient, from_height: Option<u64>, to_height: Option<u64>, poll_interval: Duration) -> BlockFollower<'a> {
+        let subscription = match client.config().backend() {
+            Backend::Rpc => match subscribe(client).await {
+                Ok(subscription) => Some(subscription),
+                Err(error) => {
+                    eprintln!("Could not subscribe to new blocks, polling instead: {}", error);
+                    None
+                }
+            },
+            Backend::Lcd => None,
+        };
+        let mut follower = BlockFollower { client, poll_interval, subscription, next_height: 0, to_height, latest: 0 };
+        follower.latest = follower.poll_beyond(0).await;
+        follower.next_height = from_height.unwrap_or(follower.latest);
+        follower
+    }
+
+    /// The next height and its transactions, once the block exists; `None` past `to_height`.
+    ///
+    /// Only network errors and retryable statuses are retried; errors that never clear, such as a
+    /// pruned height or a response that does not parse, are returned.
+    pub async fn next_block(&mut self) -> Result<Option<(u64, Vec<ComprehensiveTx>)>, FetchError> {
+        let height = self.next_height;
+        if self.to_height.is_some_and(|to_height| height > to_height) {
+            return Ok(None);
+        }
+        while height > self.latest {
+            self.latest = self.wait_beyond(self.latest).await;
+        }
+        loop {
+            match self.fetch_height(height).await {
+                Ok(Some(txs)) => {
+                    self.next_height += 1;
+                    return Ok(Some((height, txs)));
+                }
+                Ok(None) => {}
+                Err(error) if error.is_transient() => {
+                    eprintln!("Could not fetch height {}, retrying in {:?}: {}", height, self.poll_interval, error);
+                }
+                Err(error) => {
+                    eprintln!("Could not fetch height {}: {}", height, error);
+                    return Err(error);
+                }
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    /// The transactions of `height`, or `None` while the node has indexed fewer than the block holds.
+    ///
+    /// The LCD tx search lags behind `/blocks/latest` until the indexer has caught up, and then
+    /// answers with fewer txs, often none, instead of an error.
+    async fn fetch_height(&self, height: u64) -> Result<Option<Vec<ComprehensiveTx>>, FetchError> {
+        let expected = match self.client.config().backend() {
+            Backend::Lcd => fetch_block_tx_count(self.client, height).await?,
+            Backend::Rpc => 0,
+        };
+        let txs = get_comprehensive_tx_data_for_height(self.client, height).await?;
+        if txs.len() < expected {
+            eprintln!("Height {} holds {} txs but {} are indexed so far, retrying in {:?}", height, expected, txs.len(), self.poll_interval);
+            return Ok(None);
+        }
+        Ok(Some(txs))
+    }
+
+    /// Waits for a block above `known` and returns the new latest height.
+    async fn wait_beyond(&mut self, known: u64) -> u64 {
+        while let Some(subscription) = &mut self.subscription {
+            match next_header_height(subscription).await {
+                Ok(height) if height > known => return height,
+                Ok(_) => {}
+                Err(error) => {
+                    eprintln!("Block subscription lost, polling instead: {}", error);
+                    self.subscription = None;
+                }
+            }
+        }
+        self.poll_beyond(known).await
+    }
+
+    /// Polls the latest block until it is above `known`.
+    async fn poll_beyond(&self, known: u64) -> u64 {
+        loop {
+            match fetch_block_header(self.client, None).await {
+                Ok((height, _)) if height > known => return height,
+                Ok(_) => {}
+                Err(error) => eprintln!("Could not fetch the latest block: {}", error),
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+/// Opens the `/websocket` of the primary RPC endpoint and subscribes to new block headers.
+async fn subscribe(client: &HttpClient) -> Result<Subscription, Box<dyn Error>> {
+    let endpoint = client.config().endpoints().into_iter().next().ok_or("no endpoint configured")?;
+    let (mut subscription, _) = connect_async(websocket_url(&endpoint)?).await?;
+    let request = json!({ "jsonrpc": "2.0", "method": "subscribe", "id": 0, "params": { "query": NEW_BLOCK_HEADER_QUERY } });
+    subscription.send(Message::Text(request.to_string())).await?;
+    Ok(subscription)
+}
+
+/// The websocket URL of an RPC endpoint: `https://host/path` becomes `wss://host/path/websocket`.
+fn websocket_url(endpoint: &str) -> Result<Url, Box<dyn Error>> {
+    let mut url = Url::parse(endpoint)?;
+    let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+    url.set_scheme(scheme).map_err(|_| format!("cannot open a websocket to {}", endpoint))?;
+    let path = format!("{}/websocket", url.path().trim_end_matches('/'));
+    url.set_path(&path);
+    Ok(url)
+}
+
+/// Reads the subscription until the next block header arrives and returns its height.
+async fn next_header_height(subscription: &mut Subscription) -> Result<u64, Box<dyn Error>> {
+    loop {
+        let message = tokio::time::timeout(SUBSCRIPTION_TIMEOUT, subscription.next()).await
+            .map_err(|_| format!("no new block for {:?}", SUBSCRIPTION_TIMEOUT))?
+            .ok_or("closed by the node")??;
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => return Err("closed by the node".into()),
+            _ => continue,
+        };
+        let message: SubscriptionMessage = serde_json::from_str(&text)?;
+        if let Some(error) = message.error {
+            return Err(format!("the node refused the subscription: {}", error).into());
+        }
+        // The subscription ack carries an empty result.
+        if let Some(data) = message.result.and_then(|result| result.data) {
+            return Ok(data.value.header.height.parse()?);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use mockito::Matcher;
+
+    use super::*;
+    use crate::config::config::{Config, HttpConfig};
+    use crate::models::testing::{block_json, tx_response_json, txs_page};
+
+    fn mock_block(height: u64) -> String {
+        block_json(height, "2024-01-01T00:00:00Z", 1)
+    }
+
+    fn mock_page(height: u64) -> String {
+        txs_page(vec![tx_response_json(height, &format!("HASH{}", height))], json!([]))
+    }
+
+    #[tokio::test]
+    async fn test_follows_new_blocks_without_skipping() {
+        let mut server = mockito::Server::new_async().await;
+        // The chain is at 5, then moves on to 6.
+        let polls = Arc::new(AtomicUsize::new(0));
+        let counter = polls.clone();
+        server.mock("GET", "/cosmos/base/tendermint/v1beta1/blocks/latest")
+            .with_body_from_request(move |_| mock_block(if counter.fetch_add(1, Ordering::SeqCst) == 0 { 5 } else { 6 }).into())
+            .create_async().await;
+        for height in [5, 6] {
+            server.mock("GET", format!("/cosmos/base/tendermint/v1beta1/blocks/{}", height).as_str())
+                .with_body(mock_block(height))
+                .create_async().await;
+        }
+        server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::UrlEncoded("events".into(), "tx.height=5".into()))
+            .with_body(mock_page(5))
+            .create_async().await;
+        // The indexer has not caught up with height 6 the first time it is asked.
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::UrlEncoded("events".into(), "tx.height=6".into()))
+            .with_body_from_request(move |_| if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                txs_page(vec![], json!([])).into()
+            } else {
+                mock_page(6).into()
+            })
+            .create_async().await;
+
+        let config = Config { url: server.url(), http: HttpConfig { max_retries: 0, ..HttpConfig::default() }, ..Config::default() };
+        let client = HttpClient::new(&config).unwrap();
+        let mut follower = BlockFollower::start(&client, None, Some(6), Duration::from_millis(1)).await;
+
+        let mut blocks = Vec::new();
+        while let Some((height, txs)) = follower.next_block().await.unwrap() {
+            blocks.push((height, txs.into_iter().map(|tx| tx.tx_hash).collect::<Vec<_>>()));
+        }
+        assert_eq!(blocks, vec![(5, vec!["HASH5".to_string()]), (6, vec!["HASH6".to_string()])]);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_returns_errors_that_never_clear() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/cosmos/base/tendermint/v1beta1/blocks/latest")
+            .with_body(mock_block(5))
+            .create_async().await;
+        server.mock("GET", "/cosmos/base/tendermint/v1beta1/blocks/5")
+            .with_body(mock_block(5))
+            .create_async().await;
+        let txs = server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::Any)
+            .with_body("oops")
+            .expect(1)
+            .create_async().await;
+
+        let config = Config { url: server.url(), http: HttpConfig { max_retries: 0, ..HttpConfig::default() }, ..Config::default() };
+        let client = HttpClient::new(&config).unwrap();
+        let mut follower = BlockFollower::start(&client, None, None, Duration::from_millis(1)).await;
+
+        assert!(matches!(follower.next_block().await, Err(FetchError::Parse { .. })));
+        txs.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_returns_pruned_heights() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/cosmos/base/tendermint/v1beta1/blocks/latest")
+            .with_body(mock_block(5))
+            .create_async().await;
+        let pruned = server.mock("GET", "/cosmos/base/tendermint/v1beta1/blocks/3")
+            .with_status(400)
+            .with_body(r#"{"code":3,"message":"height 3 is not available, lowest height is 4"}"#)
+            .expect(1)
+            .create_async().await;
+
+        let config = Config { url: server.url(), http: HttpConfig { max_retries: 0, ..HttpConfig::default() }, ..Config::default() };
+        let client = HttpClient::new(&config).unwrap();
+        let mut follower = BlockFollower::start(&client, Some(3), None, Duration::from_millis(1)).await;
+
+        let result = follower.next_block().await;
+        assert!(matches!(result, Err(ref error) if error.is_height_unavailable()));
+        pruned.assert_async().await;
+    }
+
+    #[test]
+    fn test_websocket_url() {
+        assert_eq!(websocket_url("https://rpc.cosmos.network:443").unwrap().as_str(), "wss://rpc.cosmos.network/websocket");
+        assert_eq!(websocket_url("http://localhost:26657/rpc/").unwrap().as_str(), "ws://localhost:26657/rpc/websocket");
+    }
+}
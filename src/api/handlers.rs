@@ -1,3 +1,4 @@
+use std::collections::{BTreeSet, HashMap};
 use std::error::Error;
 use std::fmt::Display;
 
@@ -5,28 +6,42 @@ use chrono::{DateTime, Utc};
 use csv::Writer;
 use serde::Serialize;
 
+use crate::api::client::HttpClient;
+use crate::api::denoms::{fetch_denom_resolver, resolve_denom_traces};
 use crate::api::dumper::{display_pretty, dump_to_csv};
-use crate::api::fetcher::{fetch_by_tx_hash, fetch_transactions_for_height, fetch_transactions_for_height_range, FetchError, get_comprehensive_tx_data_for_height, get_comprehensive_tx_data_for_height_range, get_individual_txs_from_comprehensive_txs};
+use crate::api::error::FetchError;
+use crate::api::follow::BlockFollower;
+use crate::api::fetcher::{fetch_by_tx_hash, fetch_transactions_by_events, fetch_transactions_for_height, fetch_transactions_for_height_range, get_comprehensive_tx_data_for_address, get_comprehensive_tx_data_for_events, get_comprehensive_tx_data_for_height, get_individual_txs_from_comprehensive_txs};
 use crate::cmd::args;
-use crate::cmd::args::{BundledMsgsRangeOpts, QueryTxAtHeightOpts, QueryTxForRangeHeightOpts};
+use crate::api::heights::HeightResolver;
+use crate::api::index::{get_comprehensive_tx_data_for_height_range_indexed, TxIndex};
+use crate::api::tracker;
+use crate::cmd::args::{AddressMsgOpts, AddressTxOpts, BundledMsgsRangeOpts, EventsMsgOpts, EventsTxOpts, FailureReportOpts, FeeReportOpts, FollowOpts, GovReportOpts, HeightRangeFlags, MsgFilterFlags, QueryTxAtHeightOpts, QueryTxByAddressOpts, QueryTxByEventsOpts, QueryTxForRangeHeightOpts, SyncOpts, TrackTransferOpts, TxFilterFlags};
 use crate::config::config::Config;
+use crate::models::coin::DenomResolver;
+use crate::models::failure::FailureCount;
+use crate::models::fee::FeeSpend;
+use crate::models::gov::GovVote;
+use crate::models::ibc::TransferLifecycle;
+use crate::models::message::MessageType;
+use crate::models::registry::MessageRegistry;
 use crate::models::transaction::{ComprehensiveTx, IndividualMsgTx, ResponseData, Translate};
 
 /// handle_query_tx_hash Handles the query_tx_hash subcommand
 pub async fn handle_query_tx_hash(config: &Config, opts: args::QueryTxHashOpts) {
     println!("Querying transaction with hash: {}", opts.hash);
-    let config_clone = config.clone();
-    let join_handle = tokio::task::spawn_blocking(move || fetch_by_tx_hash(&config_clone, &opts.hash));
-    let result = join_handle.await;
-
-    let data = match result {
-        Ok(Ok(d)) => d,
-        Ok(Err(error)) => {
-            eprintln!("Failed to fetch data: {}", error);
+    let client = match HttpClient::connect(config).await {
+        Ok(client) => client,
+        Err(error) => {
+            eprintln!("Failed to create http client: {}", error);
             return;
         }
-        Err(join_error) => {
-            eprintln!("Task panicked: {}", join_error);
+    };
+
+    let data = match fetch_by_tx_hash(&client, &opts.hash).await {
+        Ok(d) => d,
+        Err(error) => {
+            eprintln!("Failed to fetch data: {}", error);
             return;
         }
     };
@@ -42,6 +57,44 @@ pub async fn handle_query_tx_hash(config: &Config, opts: args::QueryTxHashOpts)
     println!("{:?}", comprehensive_txs);
 }
 
+/// handle_track_transfer Follows the IBC packets sent by a transfer to the destination chain and back
+pub async fn handle_track_transfer(config: &Config, opts: TrackTransferOpts) {
+    println!("Tracking transfer with hash: {}", opts.hash);
+    match track_transfer(config, &opts).await {
+        Ok(transfers) => display_pretty(&transfers),
+        Err(error) => eprintln!("Failed to track transfer: {}", error),
+    }
+}
+
+async fn track_transfer(config: &Config, opts: &TrackTransferOpts) -> Result<Vec<TransferLifecycle>, Box<dyn Error>> {
+    let source = HttpClient::connect(config).await?;
+    let mut transfers = tracker::sent_packets(&source, &opts.hash).await?;
+
+    // Packets of one transfer usually share a channel, so each destination is connected once.
+    let mut destinations: HashMap<String, HttpClient> = HashMap::new();
+    for transfer in transfers.iter_mut() {
+        let (name, chain) = match &opts.dest_chain {
+            Some(name) => {
+                let chain = config.chains().get(name).ok_or_else(|| format!("no chain named '{}' in the config", name))?;
+                (name.clone(), chain)
+            }
+            None => {
+                let chain_id = tracker::counterparty_chain_id(&source, &transfer.source_port, &transfer.source_channel).await?;
+                let (name, chain) = config.chain_by_id(&chain_id)
+                    .ok_or_else(|| format!("{}/{} leads to {}, which is not configured under [chains]", transfer.source_port, transfer.source_channel, chain_id))?;
+                (name.clone(), chain)
+            }
+        };
+        transfer.destination_chain = if chain.chain_id().is_empty() { name.clone() } else { chain.chain_id().to_string() };
+
+        if !destinations.contains_key(&name) {
+            destinations.insert(name.clone(), HttpClient::connect(chain).await?);
+        }
+        tracker::follow_packet(&source, &destinations[&name], transfer).await?;
+    }
+    Ok(transfers)
+}
+
 /// handle_query_tx_at_height Handles the query_tx_at_height subcommand
 pub async fn handle_query_tx_at_height(config: &Config, opts: QueryTxAtHeightOpts) {
     println!("Querying transaction at height", );
@@ -63,22 +116,16 @@ enum TransactionResult {
 
 /// handle_tx_details_at_height Handles the tx_details subcommand
  async fn handle_tx_details_at_height(config: &Config, opts: args::BundledMsgsOpts) -> Result<(), Box<dyn std::error::Error>> {
-    // Start by defining the return type for the spawn_blocking
-    type BlockingResult = Result<TransactionResult, FetchError>;
+    let client = HttpClient::connect(config).await?;
 
-    // Then perform the blocking operation
-    let result: BlockingResult = match (opts.common_flags.simplified, opts.common_flags.raw) {
+    let result: Result<TransactionResult, FetchError> = match (opts.common_flags.simplified, opts.common_flags.raw) {
         (Some(true), _) => {
-            let config_clone = config.clone(); // Make sure Config implements Clone, or use Arc if necessary
-            let height = opts.height;
-            tokio::task::spawn_blocking(move || fetch_transactions_for_height(&config_clone, height)
-                .map(TransactionResult::SimpleData)).await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?
+            fetch_transactions_for_height(&client, opts.height).await
+                .map(TransactionResult::SimpleData)
         }
         (_, Some(true)) => {
-            let config_clone = config.clone(); // Make sure Config implements Clone, or use Arc if necessary
-            let height = opts.height;
-            tokio::task::spawn_blocking(move || get_comprehensive_tx_data_for_height(&config_clone, height)
-                .map(TransactionResult::ComprehensiveData)).await.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?
+            get_comprehensive_tx_data_for_height(&client, opts.height).await
+                .map(TransactionResult::ComprehensiveData)
         }
         _ => {
             eprintln!("Invalid option combination. Please check the provided flags.");
@@ -92,7 +139,8 @@ enum TransactionResult {
             Ok(())
         }
         Ok(TransactionResult::ComprehensiveData(data)) => {
-            handle_data_dump_and_display(&data, opts.dump_csv, format!("tx_dump_at_{}.csv", opts.height));
+            let data = select_txs(&client, data, &opts.filters).await;
+            handle_tx_dump_and_display(&data, opts.dump_csv, format!("tx_dump_at_{}.csv", opts.height));
             Ok(())
         }
         Err(error) => {
@@ -103,9 +151,112 @@ enum TransactionResult {
 }
 
 
+/// message_registry Builds the message registry for the config, checking that a filter by name
+/// refers to a registered type
+fn message_registry(config: &Config, filter: Option<&MessageType>) -> Result<MessageRegistry, Box<dyn Error>> {
+    let registry = MessageRegistry::from_config(config)?;
+    if let Some(MessageType::Name(name)) = filter {
+        if !registry.knows_name(name) {
+            return Err(format!("unknown message type '{}'; register it in the message_schema file or filter by type URL", name).into());
+        }
+    }
+    Ok(registry)
+}
+
+/// success_filter Tells whether only successful (true) or only failed (false) transactions are wanted
+fn success_filter(only_success: Option<bool>, only_failed: Option<bool>) -> Option<bool> {
+    match (only_success, only_failed) {
+        (Some(true), _) => Some(true),
+        (_, Some(true)) => Some(false),
+        _ => None,
+    }
+}
+
+/// denom_resolver Loads the denom metadata of the chain and resolves the `ibc/{hash}` denoms among the given ones to their traces
+///
+/// Amounts stay in base units when the metadata cannot be loaded; the query itself goes on.
+async fn denom_resolver(client: &HttpClient, denoms: impl Iterator<Item = String>) -> DenomResolver {
+    let denoms: BTreeSet<String> = denoms.collect();
+    let resolver = fetch_denom_resolver(client).await.unwrap_or_else(|error| {
+        eprintln!("Could not load the denom metadata, amounts stay in base units: {}", error);
+        DenomResolver::default()
+    });
+    resolver.with_traces(resolve_denom_traces(client, &denoms).await)
+}
+
+/// height_range Resolves the heights of a range query, looking up the blocks of --from-time and --to-time
+async fn height_range(client: &HttpClient, range: &HeightRangeFlags) -> Result<(u64, u64), Box<dyn Error>> {
+    if let (Some(from_height), Some(to_height)) = (range.from_height, range.to_height) {
+        return Ok((from_height, to_height));
+    }
+
+    let mut resolver = HeightResolver::new(client);
+    let from_height = match &range.from_time {
+        Some(time) => resolver.first_height_at_or_after(time.start()).await,
+        None => resolver.earliest_height().await,
+    };
+    let to_height = match &range.to_time {
+        Some(time) => resolver.last_height_at_or_before(time.end()).await,
+        None => resolver.latest_height().await,
+    };
+    // Whatever was read is worth keeping, even if one of the bounds could not be resolved.
+    resolver.save();
+    let (from_height, to_height) = (from_height?, to_height?);
+    if from_height > to_height {
+        return Err(format!("no blocks in the time range: the first block in it would be {} but the last {}", from_height, to_height).into());
+    }
+    println!("Resolved the time range to heights {} to {}", from_height, to_height);
+    Ok((from_height, to_height))
+}
+
+/// select_txs Applies the tx-details filters and annotates the remaining transactions with their fee in display units and IBC denom traces
+async fn select_txs(client: &HttpClient, data: Vec<ComprehensiveTx>, filters: &TxFilterFlags) -> Vec<ComprehensiveTx> {
+    let data = match success_filter(filters.only_success, filters.only_failed) {
+        Some(success) => ComprehensiveTx::filter_by_success(data, success),
+        None => data,
+    };
+    let mut data = match &filters.filter_by_event {
+        Some(filter) => ComprehensiveTx::filter_by_event(data, filter),
+        None => data,
+    };
+    let resolver = denom_resolver(client, data.iter().flat_map(ComprehensiveTx::ibc_denoms)).await;
+    ComprehensiveTx::annotate_denoms(&mut data, &resolver);
+    data
+}
+
+/// select_msgs Splits transactions into message rows, then applies the msg-details filters, annotations and sorting
+async fn select_msgs(client: &HttpClient, comptxs: &[ComprehensiveTx], filters: &MsgFilterFlags, registry: &MessageRegistry) -> Result<Vec<IndividualMsgTx>, FetchError> {
+    let data = get_individual_txs_from_comprehensive_txs(comptxs, filters.expand_authz.unwrap_or(false))?;
+
+    let mut filtered_data = match &filters.filter_by_msgtype {
+        Some(msg_type) => IndividualMsgTx::filter_by_type(&data, msg_type.clone(), registry),
+        None => data,
+    };
+    IndividualMsgTx::annotate(&mut filtered_data, registry);
+    if let Some(contract) = &filters.contract {
+        filtered_data = IndividualMsgTx::filter_by_contract(&filtered_data, contract);
+    }
+    if let Some(method) = &filters.execute_method {
+        filtered_data = IndividualMsgTx::filter_by_execute_method(&filtered_data, method);
+    }
+    if let Some(filter) = &filters.filter_by_event {
+        filtered_data = IndividualMsgTx::filter_by_event(&filtered_data, filter);
+    }
+    if let Some(success) = success_filter(filters.only_success, filters.only_failed) {
+        filtered_data = IndividualMsgTx::filter_by_success(&filtered_data, success);
+    }
+    let resolver = denom_resolver(client, filtered_data.iter().flat_map(IndividualMsgTx::ibc_denoms)).await;
+    IndividualMsgTx::annotate_denoms(&mut filtered_data, &resolver);
+
+    if filters.sort_by_timestamp.is_some() {
+        IndividualMsgTx::sort_by::<DateTime<Utc>>(&mut filtered_data, false);
+    }
+    Ok(filtered_data)
+}
+
 /// handle_data_dump_and_display Handles the data dump and display for the given data
 fn handle_data_dump_and_display<T: Display + Serialize>(
-    data: &Vec<T>,
+    data: &[T],
     dump_csv_option: Option<bool>,
     filename: String,
 ) {
@@ -118,25 +269,31 @@ fn handle_data_dump_and_display<T: Display + Serialize>(
     display_pretty(data);
 }
 
+/// handle_tx_dump_and_display Handles the data dump and display for transactions, written to CSV as flat rows
+fn handle_tx_dump_and_display(data: &[ComprehensiveTx], dump_csv_option: Option<bool>, filename: String) {
+    if let Some(true) = dump_csv_option {
+        let rows: Vec<_> = data.iter().map(ComprehensiveTx::csv_row).collect();
+        if let Err(e) = dump_to_csv(&rows, filename) {
+            eprintln!("Error while dumping to CSV: {}", e);
+            return;
+        }
+    }
+    display_pretty(data);
+}
+
 /// handle_msg_details_at_height Handles the msg_details subcommand
  async fn handle_msg_details_at_height(config: &Config, opts: args::IndividualMsgOpts) -> Result<(), Box<dyn std::error::Error>> {
-    type BlockingResult = Result<Vec<ComprehensiveTx>, FetchError>;
+    let client = HttpClient::connect(config).await?;
+    let registry = message_registry(config, opts.filters.filter_by_msgtype.as_ref())?;
 
-    // Perform the blocking operation
-    let comptxs: BlockingResult = {
-        let config_clone = config.clone(); // Make sure Config implements Clone, or use Arc if necessary
-        let height = opts.height;
-        tokio::task::spawn_blocking(move || get_comprehensive_tx_data_for_height(&config_clone, height)).await?
-    };
-
-    let comptxs = match comptxs {
+    let comptxs = match get_comprehensive_tx_data_for_height(&client, opts.height).await {
         Ok(data) => data,
         Err(error) => {
             eprintln!("Failed to fetch data: {}", error);
             return Err(Box::new(error));
         }
     };
-    let data = match get_individual_txs_from_comprehensive_txs(&comptxs) {
+    let data = match select_msgs(&client, &comptxs, &opts.filters, &registry).await {
         Ok(data) => data,
         Err(error) => {
             eprintln!("Failed to fetch data: {}", error);
@@ -144,35 +301,22 @@ fn handle_data_dump_and_display<T: Display + Serialize>(
         }
     };
 
-    let mut filtered_data = match opts.filter_by_msgtype {
-        Some(msg_type) => IndividualMsgTx::filter_by_type(&data, msg_type),
-        None => data,
-    };
-
-    let sorted_data_by_timestamp = match opts.sort_by_timestamp {
-        Some(_) => {
-            IndividualMsgTx::sort_by::<DateTime<Utc>>(&mut filtered_data, false);
-            filtered_data
-        }
-        None => filtered_data,
-    };
-
     if opts.dump_csv.unwrap_or(false) {
-        if let Err(e) = dump_to_csv_try(&sorted_data_by_timestamp, format!("msg_dump_at_{}.csv", opts.height)) {
+        if let Err(e) = dump_to_csv_try(&data, format!("msg_dump_at_{}.csv", opts.height)) {
             eprintln!("Error while dumping to CSV: {}", e);
-            return Err(e.into());
+            return Err(e);
         }
     }
 
-    display_pretty(&sorted_data_by_timestamp);
+    display_pretty(&data);
     Ok(())
 }
 
 ///dEBUGfUNCTION
- fn dump_to_csv_try(data: &Vec<IndividualMsgTx>, filename: String) -> Result<(), Box<dyn Error>> {
+ fn dump_to_csv_try(data: &[IndividualMsgTx], filename: String) -> Result<(), Box<dyn Error>> {
     let mut writer = Writer::from_path(filename)?;
     for item in data {
-        writer.serialize(item)?;
+        writer.serialize(item.csv_row())?;
     }
 
     writer.flush()?;
@@ -190,23 +334,31 @@ pub async fn handle_query_tx_for_range_height(config: Config, opts: QueryTxForRa
         args::QueryTxForRangeHeightSubCommand::MsgDetails(msg_details_opts) => {
             handle_msg_details_for_range(config, msg_details_opts).await.expect("Panic: Unable to handle msg details for range");
         }
+        args::QueryTxForRangeHeightSubCommand::Gov(gov_opts) => {
+            handle_gov_report_for_range(config, gov_opts).await.expect("Panic: Unable to handle gov report for range");
+        }
+        args::QueryTxForRangeHeightSubCommand::Fees(fee_opts) => {
+            handle_fee_report_for_range(config, fee_opts).await.expect("Panic: Unable to handle fee report for range");
+        }
+        args::QueryTxForRangeHeightSubCommand::Failures(failure_opts) => {
+            handle_failure_report_for_range(config, failure_opts).await.expect("Panic: Unable to handle failure report for range");
+        }
     }
 }
 
 
 /// handle_tx_details_for_range Handles the tx_details subcommand
 async fn handle_tx_details_for_range(config: Config, opts: BundledMsgsRangeOpts) -> Result<(), Box<dyn Error>> {
-    let arc_config = std::sync::Arc::new(config.clone());
+    let client = HttpClient::connect(&config).await?;
+    let (from_height, to_height) = height_range(&client, &opts.range).await?;
 
     let result: Result<TransactionResult, Box<dyn Error>> = match (opts.common_flags.simplified, opts.common_flags.raw) {
         (Some(true), _) => {
-            let res = fetch_transactions_for_height_range(arc_config, opts.from_height, opts.to_height).await?;
+            let res = fetch_transactions_for_height_range(&client, from_height, to_height).await?;
             Ok(TransactionResult::SimpleData(res))
         }
         (_, Some(true)) => {
-            let res = tokio::task::spawn_blocking(move || {
-                get_comprehensive_tx_data_for_height_range(&config, opts.from_height, opts.to_height)
-            }).await??; // Double `?`: One for JoinError and one for the result of the function
+            let res = get_comprehensive_tx_data_for_height_range_indexed(&client, from_height, to_height).await?;
             Ok(TransactionResult::ComprehensiveData(res))
         }
         _ => {
@@ -217,14 +369,15 @@ async fn handle_tx_details_for_range(config: Config, opts: BundledMsgsRangeOpts)
 
     match result {
         Ok(TransactionResult::SimpleData(data)) => {
-            handle_data_dump_and_display(&data, opts.dump_csv, format!("tx_dump_at_{}_to_{}.csv", opts.from_height, opts.to_height));
+            handle_data_dump_and_display(&data, opts.dump_csv, format!("tx_dump_at_{}_to_{}.csv", from_height, to_height));
         }
         Ok(TransactionResult::ComprehensiveData(data)) => {
-            handle_data_dump_and_display(&data, opts.dump_csv, format!("tx_dump_at_{}_to_{}.csv", opts.from_height, opts.to_height));
+            let data = select_txs(&client, data, &opts.filters).await;
+            handle_tx_dump_and_display(&data, opts.dump_csv, format!("tx_dump_at_{}_to_{}.csv", from_height, to_height));
         }
         Err(error) => {
             eprintln!("Failed to fetch data: {}", error);
-            return Err(error.into());
+            return Err(error);
         }
     }
 
@@ -232,14 +385,19 @@ async fn handle_tx_details_for_range(config: Config, opts: BundledMsgsRangeOpts)
 }
 
 async fn handle_msg_details_for_range(config: Config, opts: args::IndividualMsgRangeOpts) -> Result<(), Box<dyn std::error::Error>> {
-    type BlockingResult = Result<Vec<ComprehensiveTx>, FetchError>;
-    // Perform the blocking operation
-    let comptxs: BlockingResult = {
-        let config_clone = config.clone(); // Make sure Config implements Clone, or use Arc if necessary
-        tokio::task::spawn_blocking(move || get_comprehensive_tx_data_for_height_range(&config_clone, opts.from_height, opts.to_height)).await?
+    let client = HttpClient::connect(&config).await?;
+    let (from_height, to_height) = height_range(&client, &opts.range).await?;
+    let registry = message_registry(&config, opts.filters.filter_by_msgtype.as_ref())?;
+
+    let comptxs = match get_comprehensive_tx_data_for_height_range_indexed(&client, from_height, to_height).await {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("Failed to fetch data: {}", error);
+            return Err(Box::new(error));
+        }
     };
 
-    let comptxs = match comptxs {
+    let data = match select_msgs(&client, &comptxs, &opts.filters, &registry).await {
         Ok(data) => data,
         Err(error) => {
             eprintln!("Failed to fetch data: {}", error);
@@ -247,7 +405,23 @@ async fn handle_msg_details_for_range(config: Config, opts: args::IndividualMsgR
         }
     };
 
-    let data = match get_individual_txs_from_comprehensive_txs(&comptxs) {
+    if opts.dump_csv.unwrap_or(false) {
+        if let Err(e) = dump_to_csv_try(&data, format!("msg_dump_from_{}_to_{}.csv", from_height, to_height)) {
+            eprintln!("Error while dumping to CSV: {}", e);
+            return Err(e);
+        }
+    }
+
+    display_pretty(&data);
+    Ok(())
+}
+
+/// handle_fee_report_for_range Sums the fees paid in a height range, per payer or per block
+async fn handle_fee_report_for_range(config: Config, opts: FeeReportOpts) -> Result<(), Box<dyn Error>> {
+    let client = HttpClient::connect(&config).await?;
+    let (from_height, to_height) = height_range(&client, &opts.range).await?;
+
+    let comptxs = match get_comprehensive_tx_data_for_height_range_indexed(&client, from_height, to_height).await {
         Ok(data) => data,
         Err(error) => {
             eprintln!("Failed to fetch data: {}", error);
@@ -255,28 +429,231 @@ async fn handle_msg_details_for_range(config: Config, opts: args::IndividualMsgR
         }
     };
 
-    let mut filtered_data = match opts.filter_by_msgtype {
-        Some(msg_type) => IndividualMsgTx::filter_by_type(&data, msg_type),
-        None => data,
+    let fee_denoms: BTreeSet<String> = comptxs.iter().flat_map(|tx| tx.fee.iter().map(|coin| coin.denom.clone())).collect();
+    let resolver = fetch_denom_resolver(&client).await?.with_traces(resolve_denom_traces(&client, &fee_denoms).await);
+    let spend = FeeSpend::from_txs(&comptxs, opts.by, &resolver)?;
+    handle_data_dump_and_display(&spend, opts.dump_csv, format!("fees_from_{}_to_{}.csv", from_height, to_height));
+    Ok(())
+}
+
+/// handle_failure_report_for_range Counts the failed transactions in a height range per error
+async fn handle_failure_report_for_range(config: Config, opts: FailureReportOpts) -> Result<(), Box<dyn Error>> {
+    let client = HttpClient::connect(&config).await?;
+    let (from_height, to_height) = height_range(&client, &opts.range).await?;
+
+    let comptxs = match get_comprehensive_tx_data_for_height_range_indexed(&client, from_height, to_height).await {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("Failed to fetch data: {}", error);
+            return Err(Box::new(error));
+        }
     };
 
+    let failures = FailureCount::from_txs(&comptxs);
+    handle_data_dump_and_display(&failures, opts.dump_csv, format!("failures_from_{}_to_{}.csv", from_height, to_height));
+    Ok(())
+}
 
-    let sorted_data_by_timestamp = match opts.sort_by_timestamp {
-        Some(_) => {
-            IndividualMsgTx::sort_by::<DateTime<Utc>>(&mut filtered_data, false);
-            filtered_data
+/// handle_gov_report_for_range Lists the gov votes cast in a height range, per proposal
+async fn handle_gov_report_for_range(config: Config, opts: GovReportOpts) -> Result<(), Box<dyn Error>> {
+    let client = HttpClient::connect(&config).await?;
+    let (from_height, to_height) = height_range(&client, &opts.range).await?;
+
+    let comptxs = match get_comprehensive_tx_data_for_height_range_indexed(&client, from_height, to_height).await {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("Failed to fetch data: {}", error);
+            return Err(Box::new(error));
+        }
+    };
+
+    // Votes of failed transactions were never counted.
+    let comptxs = ComprehensiveTx::filter_by_success(comptxs, true);
+    let data = match get_individual_txs_from_comprehensive_txs(&comptxs, true) {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("Failed to fetch data: {}", error);
+            return Err(Box::new(error));
         }
-        None => filtered_data,
     };
 
+    let votes: Vec<GovVote> = GovVote::from_msg_txs(&data).into_iter()
+        .filter(|vote| opts.proposal_id.is_none_or(|id| vote.proposal_id == id))
+        .collect();
+
+    handle_data_dump_and_display(&votes, opts.dump_csv, format!("gov_votes_from_{}_to_{}.csv", from_height, to_height));
+    Ok(())
+}
+
+/// handle_query_tx_by_address Handles the query_tx_by_address subcommand
+pub async fn handle_query_tx_by_address(config: &Config, opts: QueryTxByAddressOpts) {
+    match opts.cmd {
+        args::QueryTxByAddressSubCommand::TxDetails(tx_details_opts) => {
+            println!("Querying transactions involving {}", tx_details_opts.query.address);
+            if let Err(error) = handle_tx_details_for_address(config, tx_details_opts).await {
+                eprintln!("Failed to query transactions by address: {}", error);
+            }
+        }
+        args::QueryTxByAddressSubCommand::MsgDetails(msg_details_opts) => {
+            println!("Querying transactions involving {}", msg_details_opts.query.address);
+            if let Err(error) = handle_msg_details_for_address(config, msg_details_opts).await {
+                eprintln!("Failed to query messages by address: {}", error);
+            }
+        }
+    }
+}
+
+/// address_dump_name Names the CSV file of an address query after the address and its height bounds
+fn address_dump_name(prefix: &str, query: &args::AddressQueryFlags) -> String {
+    let mut name = format!("{}_{}", prefix, query.address);
+    if let Some(height) = query.from_height {
+        name.push_str(&format!("_from_{}", height));
+    }
+    if let Some(height) = query.to_height {
+        name.push_str(&format!("_to_{}", height));
+    }
+    format!("{}.csv", name)
+}
+
+/// handle_tx_details_for_address Lists the transactions involving an address
+async fn handle_tx_details_for_address(config: &Config, opts: AddressTxOpts) -> Result<(), Box<dyn Error>> {
+    let client = HttpClient::connect(config).await?;
+
+    let data = get_comprehensive_tx_data_for_address(&client, &opts.query.address, opts.query.from_height, opts.query.to_height).await?;
+    let data = select_txs(&client, data, &opts.filters).await;
+    handle_tx_dump_and_display(&data, opts.dump_csv, address_dump_name("tx_dump", &opts.query));
+    Ok(())
+}
+
+/// handle_msg_details_for_address Lists the messages of the transactions involving an address
+async fn handle_msg_details_for_address(config: &Config, opts: AddressMsgOpts) -> Result<(), Box<dyn Error>> {
+    let client = HttpClient::connect(config).await?;
+    let registry = message_registry(config, opts.filters.filter_by_msgtype.as_ref())?;
+
+    let comptxs = get_comprehensive_tx_data_for_address(&client, &opts.query.address, opts.query.from_height, opts.query.to_height).await?;
+    let data = select_msgs(&client, &comptxs, &opts.filters, &registry).await?;
+
     if opts.dump_csv.unwrap_or(false) {
-        if let Err(e) = dump_to_csv_try(&sorted_data_by_timestamp, format!("msg_dump_from_{}_to_{}.csv", opts.from_height, opts.to_height)) {
-            eprintln!("Error while dumping to CSV: {}", e);
-            return Err(e.into());
+        dump_to_csv_try(&data, address_dump_name("msg_dump", &opts.query))?;
+    }
+    display_pretty(&data);
+    Ok(())
+}
+
+/// handle_query_tx_by_events Handles the query_tx_by_events subcommand
+pub async fn handle_query_tx_by_events(config: &Config, opts: QueryTxByEventsOpts) {
+    match opts.cmd {
+        args::QueryTxByEventsSubCommand::TxDetails(tx_details_opts) => {
+            println!("Querying transactions matching {}", tx_details_opts.query.conditions().join(" AND "));
+            if let Err(error) = handle_tx_details_for_events(config, tx_details_opts).await {
+                eprintln!("Failed to query transactions by events: {}", error);
+            }
+        }
+        args::QueryTxByEventsSubCommand::MsgDetails(msg_details_opts) => {
+            println!("Querying transactions matching {}", msg_details_opts.query.conditions().join(" AND "));
+            if let Err(error) = handle_msg_details_for_events(config, msg_details_opts).await {
+                eprintln!("Failed to query messages by events: {}", error);
+            }
         }
     }
+}
+
+/// handle_tx_details_for_events Lists the transactions matching every event condition
+async fn handle_tx_details_for_events(config: &Config, opts: EventsTxOpts) -> Result<(), Box<dyn Error>> {
+    let client = HttpClient::connect(config).await?;
+    let events = opts.query.conditions();
+
+    match (opts.common_flags.simplified, opts.common_flags.raw) {
+        (Some(true), _) => {
+            let data = fetch_transactions_by_events(&client, &events).await?;
+            handle_data_dump_and_display(&data, opts.dump_csv, "tx_dump_by_events.csv".to_string());
+        }
+        (_, Some(true)) => {
+            let data = get_comprehensive_tx_data_for_events(&client, &events).await?;
+            let data = select_txs(&client, data, &opts.filters).await;
+            handle_tx_dump_and_display(&data, opts.dump_csv, "tx_dump_by_events.csv".to_string());
+        }
+        _ => {
+            eprintln!("Invalid option combination. Please check the provided flags.");
+            return Err("Invalid option combination.".into());
+        }
+    }
+    Ok(())
+}
+
+/// handle_msg_details_for_events Lists the messages of the transactions matching every event condition
+async fn handle_msg_details_for_events(config: &Config, opts: EventsMsgOpts) -> Result<(), Box<dyn Error>> {
+    let client = HttpClient::connect(config).await?;
+    let registry = message_registry(config, opts.filters.filter_by_msgtype.as_ref())?;
+
+    let comptxs = get_comprehensive_tx_data_for_events(&client, &opts.query.conditions()).await?;
+    let data = select_msgs(&client, &comptxs, &opts.filters, &registry).await?;
+
+    if opts.dump_csv.unwrap_or(false) {
+        dump_to_csv_try(&data, "msg_dump_by_events.csv".to_string())?;
+    }
+    display_pretty(&data);
+    Ok(())
+}
+
+/// handle_query_tx_follow Handles the query_tx_follow subcommand
+pub async fn handle_query_tx_follow(config: &Config, opts: FollowOpts) {
+    println!("Following new blocks");
+    if let Err(error) = follow_msgs(config, opts).await {
+        eprintln!("Failed to follow new blocks: {}", error);
+    }
+}
+
+/// follow_msgs Prints the messages of every new block as it arrives, appending them to the CSV as it goes
+async fn follow_msgs(config: &Config, opts: FollowOpts) -> Result<(), Box<dyn Error>> {
+    let client = HttpClient::connect(config).await?;
+    let registry = message_registry(config, opts.filters.filter_by_msgtype.as_ref())?;
+    let mut writer = match opts.dump_csv {
+        Some(true) => Some(Writer::from_path(format!("msg_follow_from_{}.csv", opts.from_height.map_or("latest".to_string(), |height| height.to_string())))?),
+        _ => None,
+    };
 
-    display_pretty(&sorted_data_by_timestamp);
+    let poll_interval = std::time::Duration::from_secs(opts.poll_interval_secs);
+    let mut follower = BlockFollower::start(&client, opts.from_height, opts.to_height, poll_interval).await;
+    while let Some((_, comptxs)) = follower.next_block().await? {
+        let data = select_msgs(&client, &comptxs, &opts.filters, &registry).await?;
+        if data.is_empty() {
+            continue;
+        }
+        if let Some(writer) = &mut writer {
+            for item in &data {
+                writer.serialize(item.csv_row())?;
+            }
+            writer.flush()?;
+        }
+        display_pretty(&data);
+    }
     Ok(())
 }
 
+/// handle_sync Handles the sync subcommand
+pub async fn handle_sync(config: &Config, opts: SyncOpts) {
+    if let Err(error) = sync_height_range(config, opts).await {
+        eprintln!("Failed to sync: {}", error);
+    }
+}
+
+/// sync_height_range Stores the heights of the range missing from the local index
+async fn sync_height_range(config: &Config, opts: SyncOpts) -> Result<(), Box<dyn Error>> {
+    let client = HttpClient::connect(config).await?;
+    let (from_height, to_height) = height_range(&client, &opts.range).await?;
+    let registry = MessageRegistry::from_config(config)?;
+    let path = config.tx_index();
+    let mut index = TxIndex::open(&path, &config.cache_key())?;
+
+    println!("Syncing heights {} to {} into {}", from_height, to_height, path.display());
+    let report = index.sync(&client, &registry, from_height, to_height).await?;
+    println!("Stored {} heights, {} were already synced", report.stored, report.already_synced);
+    if let Some(checkpoint) = report.checkpoint {
+        println!("Heights {} to {} are synced", from_height, checkpoint);
+    }
+    if !report.failed.is_empty() {
+        return Err(format!("{} heights could not be synced, first {}; run sync again to fill them", report.failed.len(), report.failed[0]).into());
+    }
+    Ok(())
+}
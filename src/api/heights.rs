@@ -0,0 +1,317 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::api::cache::ChainCache;
+use crate::api::client::HttpClient;
+use crate::api::error::{FetchError, FetchTarget};
+use crate::config::config::Backend;
+use crate::models::rpc::{BlockResponse, Header, RpcResponse};
+
+/// LCD path of a block by height, or of the `latest` one.
+const BLOCKS_PATH: &str = "/cosmos/base/tendermint/v1beta1/blocks";
+
+/// Block times kept on disk, per chain then per height.
+///
+/// A block's time never changes, so entries are never refreshed.
+pub type BlockTimeCache = ChainCache<u64, DateTime<Utc>>;
+
+#[derive(Deserialize)]
+struct StatusResult {
+    sync_info: SyncInfo,
+}
+
+#[derive(Deserialize)]
+struct SyncInfo {
+    earliest_block_height: String,
+}
+
+/// Resolves times to block heights by binary search over block header times.
+///
+/// Block times grow with the height, so the first block at or after a time is found in about
+/// log2(heights) header fetches. The heights searched run from the earliest block the node still
+/// has to the latest one, both discovered on first use. Every block time read is cached on disk
+/// by `save`, so later searches over the same chain mostly stay local.
+pub struct HeightResolver<'a> {
+    client: &'a HttpClient,
+    cache: BlockTimeCache,
+    earliest: Option<u64>,
+    latest: Option<u64>,
+}
+
+impl<'a> HeightResolver<'a> {
+    pub fn new(client: &'a HttpClient) -> Self {
+        let cache = BlockTimeCache::load_or_default(&client.config().block_time_cache(), "block time");
+        HeightResolver { client, cache, earliest: None, latest: None }
+    }
+
+    /// Writes the block times read so far to the cache file.
+    pub fn save(&self) {
+        let path = self.client.config().block_time_cache();
+        if let Err(error) = self.cache.save(&path) {
+            eprintln!("Could not write the block time cache {}: {}", path.display(), error);
+        }
+    }
+
+    /// The height of the latest block.
+    pub async fn latest_height(&mut self) -> Result<u64, FetchError> {
+        if let Some(latest) = self.latest {
+            return Ok(latest);
+        }
+        let (height, time) = fetch_block_header(self.client, None).await?;
+        self.cache.insert(&self.client.config().cache_key(), height, time);
+        self.latest = Some(height);
+        Ok(height)
+    }
+
+    /// The height of the earliest block the node still has.
+    ///
+    /// The CometBFT RPC reports it in `/status`. The LCD does not, so height 1 is asked for: a
+    /// pruned node refuses it, usually naming its lowest height, and otherwise the lowest height
+    /// it serves is searched for.
+    pub async fn earliest_height(&mut self) -> Result<u64, FetchError> {
+        if let Some(earliest) = self.earliest {
+            return Ok(earliest);
+        }
+        let earliest = match self.client.config().backend() {
+            Backend::Rpc => {
+                let status: RpcResponse<StatusResult> = self.client.get_json("/status", &[]).await?;
+                status.result.sync_info.earliest_block_height.parse::<u64>()
+                    .map_err(|e| FetchError::TaskFailure(format!("invalid earliest block height in /status: {}", e)))?
+                    .max(1)
+            }
+            Backend::Lcd => self.search_earliest_height().await?,
+        };
+        self.earliest = Some(earliest);
+        Ok(earliest)
+    }
+
+    /// Pruned heights are the expected answer here, so the probes go around the endpoint health
+    /// tracking, and all go to one endpoint so that they describe the same node.
+    async fn search_earliest_height(&mut self) -> Result<u64, FetchError> {
+        let endpoint = self.client.select_endpoint();
+        let error = match probe_lcd_block(self.client, &endpoint, Some(1)).await {
+            Ok(_) => return Ok(1),
+            Err(error) if error.is_height_unavailable() => error,
+            Err(error) => return Err(error),
+        };
+        if let Some(lowest) = error.lowest_available_height() {
+            return Ok(lowest);
+        }
+
+        // Height `unavailable` is known to be pruned and `available` to be served.
+        let (mut unavailable, mut available) = (1, probe_lcd_block(self.client, &endpoint, None).await?);
+        while available - unavailable > 1 {
+            let mid = unavailable + (available - unavailable) / 2;
+            match probe_lcd_block(self.client, &endpoint, Some(mid)).await {
+                Ok(_) => available = mid,
+                Err(error) if error.is_height_unavailable() => unavailable = mid,
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(available)
+    }
+
+    /// The first height whose block time is at or after `time`.
+    pub async fn first_height_at_or_after(&mut self, time: DateTime<Utc>) -> Result<u64, FetchError> {
+        match self.first_height_where(|block_time| block_time >= time).await? {
+            Some(height) => Ok(height),
+            None => {
+                let latest = self.latest_height().await?;
+                Err(FetchError::TaskFailure(format!(
+                    "no block at or after {}; the latest block {} is from {}",
+                    time, latest, self.block_time(latest).await?
+                )))
+            }
+        }
+    }
+
+    /// The last height whose block time is at or before `time`.
+    pub async fn last_height_at_or_before(&mut self, time: DateTime<Utc>) -> Result<u64, FetchError> {
+        let earliest = self.earliest_height().await?;
+        match self.first_height_where(|block_time| block_time > time).await? {
+            Some(height) if height == earliest => Err(FetchError::TaskFailure(format!(
+                "no block at or before {}; the earliest available block {} is from {}",
+                time, earliest, self.block_time(earliest).await?
+            ))),
+            Some(height) => Ok(height - 1),
+            None => self.latest_height().await,
+        }
+    }
+
+    /// Binary search for the first height from the earliest to the latest whose block time
+    /// satisfies `predicate`, which must hold for every later block once it holds for one.
+    async fn first_height_where(&mut self, predicate: impl Fn(DateTime<Utc>) -> bool) -> Result<Option<u64>, FetchError> {
+        let mut low = self.earliest_height().await?;
+        let mut high = self.latest_height().await?;
+        if !predicate(self.block_time(high).await?) {
+            return Ok(None);
+        }
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if predicate(self.block_time(mid).await?) {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Ok(Some(low))
+    }
+
+    /// The time of the block at `height`, from the cache or else from the node.
+    async fn block_time(&mut self, height: u64) -> Result<DateTime<Utc>, FetchError> {
+        let chain = &self.client.config().cache_key();
+        if let Some(time) = self.cache.get(chain, &height) {
+            return Ok(*time);
+        }
+        let (_, time) = fetch_block_header(self.client, Some(height)).await?;
+        self.cache.insert(chain, height, time);
+        Ok(time)
+    }
+}
+
+/// Fetches the height and time of the block at `height`, or of the latest block.
+pub async fn fetch_block_header(client: &HttpClient, height: Option<u64>) -> Result<(u64, DateTime<Utc>), FetchError> {
+    parse_header(fetch_block(client, height).await?.block.header)
+}
+
+/// Fetches the height of the LCD block at `height`, or of the latest block, from one endpoint
+/// with `HttpClient::probe_json`.
+async fn probe_lcd_block(client: &HttpClient, endpoint: &str, height: Option<u64>) -> Result<u64, FetchError> {
+    let path = match height {
+        Some(height) => format!("{}/{}", BLOCKS_PATH, height),
+        None => format!("{}/latest", BLOCKS_PATH),
+    };
+    let response: BlockResponse = client.probe_json(endpoint, &path, &[]).await.map_err(|e| match height {
+        Some(height) => e.with_target(FetchTarget::Height(height)),
+        None => e,
+    })?;
+    parse_header(response.block.header).map(|(height, _)| height)
+}
+
+fn parse_header(header: Header) -> Result<(u64, DateTime<Utc>), FetchError> {
+    let parsed_height = header.height.parse::<u64>()
+        .map_err(|e| FetchError::TaskFailure(format!("invalid block height '{}': {}", header.height, e)))?;
+    let time = header.time.parse::<DateTime<Utc>>()
+        .map_err(|e| FetchError::TaskFailure(format!("invalid time '{}' of block {}: {}", header.time, parsed_height, e)))?;
+    Ok((parsed_height, time))
+}
+
+/// Fetches the number of transactions the block at `height` holds.
+pub async fn fetch_block_tx_count(client: &HttpClient, height: u64) -> Result<usize, FetchError> {
+    let block = fetch_block(client, Some(height)).await?.block;
+    Ok(block.data.txs.map_or(0, |txs| txs.len()))
+}
+
+/// Fetches the block at `height`, or the latest block.
+async fn fetch_block(client: &HttpClient, height: Option<u64>) -> Result<BlockResponse, FetchError> {
+    match client.config().backend() {
+        Backend::Lcd => {
+            let path = match height {
+                Some(height) => format!("{}/{}", BLOCKS_PATH, height),
+                None => format!("{}/latest", BLOCKS_PATH),
+            };
+            client.get_json(&path, &[]).await
+        }
+        Backend::Rpc => {
+            let params: Vec<(&str, String)> = height.iter().map(|height| ("height", height.to_string())).collect();
+            client.get_json::<RpcResponse<BlockResponse>>("/block", &params).await.map(|response| response.result)
+        }
+    }.map_err(|e| match height {
+        Some(height) => e.with_target(FetchTarget::Height(height)),
+        None => e,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::Matcher;
+
+    use super::*;
+    use crate::config::config::Config;
+    use crate::models::testing::block_json;
+
+    fn mock_block(height: u64) -> String {
+        // One block every ten minutes from midnight.
+        let time = format!("2024-01-01T{:02}:{}0:00.5Z", height / 6, height % 6);
+        block_json(height, &time, 0)
+    }
+
+    #[tokio::test]
+    async fn test_resolves_times_to_heights() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", format!("{}/1", BLOCKS_PATH).as_str())
+            .with_status(500)
+            .with_body(r#"{"code":2,"message":"height 1 is not available, lowest height is 10"}"#)
+            .create_async().await;
+        server.mock("GET", format!("{}/latest", BLOCKS_PATH).as_str())
+            .with_body(mock_block(100))
+            .create_async().await;
+        for height in 10..=100 {
+            server.mock("GET", format!("{}/{}", BLOCKS_PATH, height).as_str())
+                .match_query(Matcher::Any)
+                .with_body(mock_block(height))
+                .create_async().await;
+        }
+
+        let cache = std::env::temp_dir().join(format!("tx_dump_block_times_{}.json", std::process::id()));
+        let config = Config {
+            url: server.url(),
+            block_time_cache: Some(cache.to_string_lossy().to_string()),
+            http: crate::config::config::HttpConfig { max_retries: 0, ..Default::default() },
+            ..Config::default()
+        };
+        let client = HttpClient::new(&config).unwrap();
+        let mut resolver = HeightResolver::new(&client);
+
+        assert_eq!(resolver.earliest_height().await.unwrap(), 10);
+        let time = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        // Block 42 is at 07:00:00.5.
+        assert_eq!(resolver.first_height_at_or_after(time("2024-01-01T07:00:00Z")).await.unwrap(), 42);
+        assert_eq!(resolver.last_height_at_or_before(time("2024-01-01T07:00:00Z")).await.unwrap(), 41);
+        assert_eq!(resolver.first_height_at_or_after(time("2023-12-31T00:00:00Z")).await.unwrap(), 10);
+        assert_eq!(resolver.last_height_at_or_before(time("2024-02-01T00:00:00Z")).await.unwrap(), 100);
+        assert!(resolver.first_height_at_or_after(time("2024-02-01T00:00:00Z")).await.is_err());
+        assert!(resolver.last_height_at_or_before(time("2024-01-01T00:00:00Z")).await.is_err());
+
+        resolver.save();
+        assert_eq!(BlockTimeCache::load(&cache).unwrap().get(&server.url(), &42), Some(&time("2024-01-01T07:00:00.5Z")));
+        std::fs::remove_file(cache).unwrap();
+    }
+    #[tokio::test]
+    async fn test_searches_earliest_height_on_one_endpoint() {
+        // The primary endpoint pruned everything below 40 without saying so; the other one is an archive.
+        let mut pruned = mockito::Server::new_async().await;
+        pruned.mock("GET", format!("{}/latest", BLOCKS_PATH).as_str())
+            .with_body(mock_block(100))
+            .expect_at_least(2)
+            .create_async().await;
+        pruned.mock("GET", Matcher::Regex(format!(r"^{}/([1-9]|[1-3][0-9])$", BLOCKS_PATH)))
+            .with_status(400)
+            .with_body_from_request(|request| {
+                let height = request.path().rsplit('/').next().unwrap_or_default();
+                format!(r#"{{"code":3,"message":"height {} is not available"}}"#, height).into()
+            })
+            .create_async().await;
+        for height in 40..=100 {
+            pruned.mock("GET", format!("{}/{}", BLOCKS_PATH, height).as_str())
+                .with_body(mock_block(height))
+                .create_async().await;
+        }
+        let mut archive = mockito::Server::new_async().await;
+        let untouched = archive.mock("GET", Matcher::Any).expect(0).create_async().await;
+
+        let config = Config {
+            url: pruned.url(),
+            endpoints: vec![archive.url()],
+            http: crate::config::config::HttpConfig { max_retries: 0, ..Default::default() },
+            ..Config::default()
+        };
+        let client = HttpClient::new(&config).unwrap();
+        let mut resolver = HeightResolver::new(&client);
+
+        assert_eq!(resolver.earliest_height().await.unwrap(), 40);
+        // The pruned answers did not take the primary out of rotation.
+        assert_eq!(resolver.latest_height().await.unwrap(), 100);
+        untouched.assert_async().await;
+    }
+}
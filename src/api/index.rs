@@ -0,0 +1,340 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use rusqlite::{params, Connection};
+
+use crate::api::client::HttpClient;
+use crate::api::error::FetchError;
+use crate::api::fetcher::{fetch_transactions_for_height, get_comprehensive_tx_data_for_height, get_comprehensive_tx_data_for_height_range};
+use crate::models::registry::MessageRegistry;
+use crate::models::transaction::{ComprehensiveTx, IndividualMsgTx, ResponseData, ResponseDataForHashQuery, TranslationError, Translate};
+
+/// Tables of the index. Every statement is idempotent, so opening an existing file is harmless.
+///
+/// `txs.raw` keeps each transaction as the node served it, `{"tx": ..., "tx_response": ...}`, and
+/// is what queries read back; the other columns, and the `msgs` table, are there for SQL.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS heights (
+    chain TEXT NOT NULL,
+    height INTEGER NOT NULL,
+    tx_count INTEGER NOT NULL,
+    PRIMARY KEY (chain, height)
+);
+CREATE TABLE IF NOT EXISTS txs (
+    chain TEXT NOT NULL,
+    height INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    code INTEGER NOT NULL,
+    timestamp TEXT NOT NULL,
+    fee_payer TEXT NOT NULL,
+    raw TEXT NOT NULL,
+    PRIMARY KEY (chain, height, position)
+);
+CREATE INDEX IF NOT EXISTS txs_by_hash ON txs (chain, tx_hash);
+CREATE TABLE IF NOT EXISTS msgs (
+    chain TEXT NOT NULL,
+    height INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    msg_path TEXT NOT NULL,
+    type_url TEXT NOT NULL,
+    msg_name TEXT NOT NULL,
+    sender TEXT,
+    receiver TEXT,
+    grantee TEXT,
+    code INTEGER NOT NULL,
+    timestamp TEXT NOT NULL,
+    message TEXT NOT NULL,
+    PRIMARY KEY (chain, height, tx_hash, msg_path)
+);
+CREATE INDEX IF NOT EXISTS msgs_by_type ON msgs (chain, type_url, height);
+";
+
+/// Transactions in height order, grouped by height.
+type TxsByHeight = BTreeMap<u64, Vec<ComprehensiveTx>>;
+
+/// Why the index could not be read or written.
+#[derive(Debug)]
+pub enum IndexError {
+    Sqlite(rusqlite::Error),
+    /// A stored transaction is not the JSON we wrote.
+    Json(serde_json::Error),
+    Translate(TranslationError),
+    Fetch(FetchError),
+    Io(std::io::Error),
+}
+
+impl Error for IndexError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            IndexError::Sqlite(source) => Some(source),
+            IndexError::Json(source) => Some(source),
+            IndexError::Translate(source) => Some(source),
+            IndexError::Fetch(source) => Some(source),
+            IndexError::Io(source) => Some(source),
+        }
+    }
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndexError::Sqlite(source) => write!(f, "Index database error: {}", source),
+            IndexError::Json(source) => write!(f, "Unreadable transaction in the index: {}", source),
+            IndexError::Translate(source) => write!(f, "Failed to translate a transaction for the index: {}", source),
+            IndexError::Fetch(source) => write!(f, "{}", source),
+            IndexError::Io(source) => write!(f, "Could not create the index: {}", source),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for IndexError {
+    fn from(source: rusqlite::Error) -> Self {
+        IndexError::Sqlite(source)
+    }
+}
+
+impl From<serde_json::Error> for IndexError {
+    fn from(source: serde_json::Error) -> Self {
+        IndexError::Json(source)
+    }
+}
+
+impl From<TranslationError> for IndexError {
+    fn from(source: TranslationError) -> Self {
+        IndexError::Translate(source)
+    }
+}
+
+impl From<FetchError> for IndexError {
+    fn from(source: FetchError) -> Self {
+        IndexError::Fetch(source)
+    }
+}
+
+/// What a sync did: the heights it found already stored, stored itself, or could not fetch.
+#[derive(Debug, Default, PartialEq)]
+pub struct SyncReport {
+    pub already_synced: u64,
+    pub stored: u64,
+    /// Heights left as gaps for the next sync, in height order.
+    pub failed: Vec<u64>,
+    /// The last height up to which the whole range is stored, if its first height is.
+    pub checkpoint: Option<u64>,
+}
+
+/// Transactions of whole heights of one chain, stored in an SQLite file.
+///
+/// A height is recorded as done in the same database transaction as its txs, so the index never
+/// holds part of a block, and blocks are final, so stored heights are never fetched again.
+pub struct TxIndex {
+    conn: Connection,
+    chain: String,
+}
+
+impl TxIndex {
+    /// Opens the index file of `chain`, creating it if needed.
+    pub fn open(path: &Path, chain: &str) -> Result<TxIndex, IndexError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(IndexError::Io)?;
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(TxIndex { conn, chain: chain.to_string() })
+    }
+
+    /// Opens the index file of `chain` if a sync created one.
+    pub fn open_existing(path: &Path, chain: &str) -> Result<Option<TxIndex>, IndexError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        TxIndex::open(path, chain).map(Some)
+    }
+
+    /// The heights of the inclusive range that are stored.
+    pub fn synced_heights(&self, from_height: u64, to_height: u64) -> Result<BTreeSet<u64>, IndexError> {
+        let mut statement = self.conn.prepare("SELECT height FROM heights WHERE chain = ?1 AND height BETWEEN ?2 AND ?3")?;
+        let heights = statement.query_map(params![self.chain, from_height, to_height], |row| row.get(0))?
+            .collect::<Result<BTreeSet<u64>, _>>()?;
+        Ok(heights)
+    }
+
+    /// Stores the transactions fetched for a height, with a row per message (authz included, as
+    /// named by `registry`), and records the height as done.
+    pub fn store_height(&mut self, height: u64, pages: Vec<ResponseData>, registry: &MessageRegistry) -> Result<(), IndexError> {
+        let db = self.conn.transaction()?;
+        let mut position: u64 = 0;
+        for page in pages {
+            for (tx, tx_response) in page.txs.into_iter().zip(page.tx_responses) {
+                let raw = ResponseDataForHashQuery { tx, tx_response };
+                for comprehensive_tx in raw.translate()? {
+                    db.execute(
+                        "INSERT OR REPLACE INTO txs (chain, height, position, tx_hash, code, timestamp, fee_payer, raw) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                        params![self.chain, height, position, comprehensive_tx.tx_hash, comprehensive_tx.code, comprehensive_tx.timestamp.to_rfc3339(), comprehensive_tx.fee_payer, serde_json::to_string(&raw)?],
+                    )?;
+                    let mut msgs = comprehensive_tx.to_expanded_individual_transactions()?;
+                    IndividualMsgTx::annotate(&mut msgs, registry);
+                    for msg in &msgs {
+                        db.execute(
+                            "INSERT OR REPLACE INTO msgs (chain, height, tx_hash, msg_path, type_url, msg_name, sender, receiver, grantee, code, timestamp, message) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                            params![self.chain, height, msg.tx_hash, msg.msg_path, msg.message.type_url(), msg.msg_name, msg.sender, msg.receiver, msg.grantee, msg.code, msg.timestamp.to_rfc3339(), serde_json::to_string(&msg.message)?],
+                        )?;
+                    }
+                    position += 1;
+                }
+            }
+        }
+        db.execute(
+            "INSERT OR REPLACE INTO heights (chain, height, tx_count) VALUES (?1, ?2, ?3)",
+            params![self.chain, height, position],
+        )?;
+        db.commit()?;
+        Ok(())
+    }
+
+    /// The stored transactions of the inclusive range by height, and the heights that are not stored.
+    pub fn read_range(&self, from_height: u64, to_height: u64) -> Result<(TxsByHeight, Vec<u64>), IndexError> {
+        let synced = self.synced_heights(from_height, to_height)?;
+        let mut per_height = TxsByHeight::new();
+        let mut statement = self.conn.prepare("SELECT height, raw FROM txs WHERE chain = ?1 AND height BETWEEN ?2 AND ?3 ORDER BY height, position")?;
+        let mut rows = statement.query(params![self.chain, from_height, to_height])?;
+        while let Some(row) = rows.next()? {
+            let height: u64 = row.get(0)?;
+            let raw: ResponseDataForHashQuery = serde_json::from_str(&row.get::<_, String>(1)?)?;
+            per_height.entry(height).or_default().extend(raw.translate()?);
+        }
+        let missing = (from_height..=to_height).filter(|height| !synced.contains(height)).collect();
+        Ok((per_height, missing))
+    }
+
+    /// Fetches and stores every height of the inclusive range that is not stored yet, so running
+    /// it again resumes an interrupted sync and fills the gaps of a failed one.
+    ///
+    /// Each height is committed as soon as it arrives. A height that cannot be fetched or
+    /// translated is reported on stderr and left for the next sync; only database errors abort.
+    pub async fn sync(&mut self, client: &HttpClient, registry: &MessageRegistry, from_height: u64, to_height: u64) -> Result<SyncReport, IndexError> {
+        let synced = self.synced_heights(from_height, to_height)?;
+        let missing: Vec<u64> = (from_height..=to_height).filter(|height| !synced.contains(height)).collect();
+        let mut report = SyncReport { already_synced: synced.len() as u64, ..SyncReport::default() };
+        let total = missing.len();
+
+        // Every height is stored on its own, so they need not arrive in order.
+        let mut fetches = stream::iter(missing)
+            .map(|height| async move { (height, fetch_transactions_for_height(client, height).await) })
+            .buffer_unordered(client.config().concurrency());
+        while let Some((height, result)) = fetches.next().await {
+            match result.map_err(IndexError::from).and_then(|pages| self.store_height(height, pages, registry)) {
+                Ok(()) => report.stored += 1,
+                Err(IndexError::Sqlite(error)) => return Err(IndexError::Sqlite(error)),
+                Err(error) => {
+                    eprintln!("Could not sync height {}, leaving it for the next sync: {}", height, error);
+                    report.failed.push(height);
+                }
+            }
+            let done = report.stored as usize + report.failed.len();
+            if done.is_multiple_of(1000) {
+                println!("Synced {} of {} heights", done, total);
+            }
+        }
+
+        report.failed.sort_unstable();
+        let synced = self.synced_heights(from_height, to_height)?;
+        report.checkpoint = (from_height..=to_height).take_while(|height| synced.contains(height)).last();
+        Ok(report)
+    }
+}
+
+/// Fetches and translates every transaction in an inclusive height range, in height order, like
+/// `get_comprehensive_tx_data_for_height_range`, but reads the heights already synced to the
+/// index from it and only fetches the others.
+pub async fn get_comprehensive_tx_data_for_height_range_indexed(client: &HttpClient, from_height: u64, to_height: u64) -> Result<Vec<ComprehensiveTx>, IndexError> {
+    let config = client.config();
+    let (mut per_height, missing) = match TxIndex::open_existing(&config.tx_index(), &config.cache_key())? {
+        Some(index) => index.read_range(from_height, to_height)?,
+        None => return Ok(get_comprehensive_tx_data_for_height_range(client, from_height, to_height).await?),
+    };
+
+    let fetched: Vec<(u64, Vec<ComprehensiveTx>)> = stream::iter(missing)
+        .map(|height| async move { get_comprehensive_tx_data_for_height(client, height).await.map(|txs| (height, txs)) })
+        .buffered(config.concurrency())
+        .try_collect()
+        .await?;
+    per_height.extend(fetched);
+
+    Ok(per_height.into_values().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::Matcher;
+    use serde_json::json;
+
+    use super::*;
+    use crate::config::config::{Config, HttpConfig};
+    use crate::models::testing::{tx_response_json, txs_page};
+
+    fn mock_page(height: u64, hashes: &[&str]) -> String {
+        let send = json!({ "@type": "/cosmos.bank.v1beta1.MsgSend", "from_address": "cosmos1from", "to_address": "cosmos1to", "amount": [{ "denom": "uatom", "amount": "1" }] });
+        txs_page(hashes.iter().map(|hash| tx_response_json(height, hash)).collect(), json!([send]))
+    }
+
+    fn mock_height(server: &mut mockito::Server, height: u64, hashes: &[&str]) -> mockito::Mock {
+        server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::UrlEncoded("events".into(), format!("tx.height={}", height)))
+            .with_body(mock_page(height, hashes))
+    }
+
+    #[tokio::test]
+    async fn test_sync_fills_gaps_and_serves_range_queries() {
+        let mut server = mockito::Server::new_async().await;
+        let path = std::env::temp_dir().join(format!("tx_dump_index_{}.sqlite", std::process::id()));
+        let config = Config {
+            url: server.url(),
+            chain_id: "cosmoshub-4".to_string(),
+            tx_index: Some(path.to_string_lossy().to_string()),
+            http: HttpConfig { max_retries: 0, ..HttpConfig::default() },
+            ..Config::default()
+        };
+        let client = HttpClient::new(&config).unwrap();
+        let registry = MessageRegistry::builtin();
+        let mut index = TxIndex::open(&path, &config.cache_key()).unwrap();
+
+        // Height 2 fails on the first sync and is the only height fetched by the second.
+        let one = mock_height(&mut server, 1, &["A", "B"]).expect(1).create_async().await;
+        let failing = server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            .match_query(Matcher::UrlEncoded("events".into(), "tx.height=2".into()))
+            .with_status(500)
+            .create_async().await;
+        let three = mock_height(&mut server, 3, &[]).expect(1).create_async().await;
+
+        let report = index.sync(&client, &registry, 1, 3).await.unwrap();
+        assert_eq!(report, SyncReport { already_synced: 0, stored: 2, failed: vec![2], checkpoint: Some(1) });
+
+        failing.remove_async().await;
+        let two = mock_height(&mut server, 2, &["C"]).expect(1).create_async().await;
+        let report = index.sync(&client, &registry, 1, 3).await.unwrap();
+        assert_eq!(report, SyncReport { already_synced: 2, stored: 1, failed: vec![], checkpoint: Some(3) });
+
+        let msgs: u64 = index.conn.query_row("SELECT COUNT(*) FROM msgs WHERE chain = 'cosmoshub-4' AND msg_name = 'MsgSend'", [], |row| row.get(0)).unwrap();
+        assert_eq!(msgs, 3);
+
+        // Heights 1 to 3 come from the index, only 4 from the node.
+        let four = mock_height(&mut server, 4, &["D"]).expect(1).create_async().await;
+        let txs = get_comprehensive_tx_data_for_height_range_indexed(&client, 1, 4).await.unwrap();
+        let hashes: Vec<&str> = txs.iter().map(|tx| tx.tx_hash.as_str()).collect();
+        assert_eq!(hashes, vec!["A", "B", "C", "D"]);
+
+        for mock in [one, two, three, four] {
+            mock.assert_async().await;
+        }
+        drop(index);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+}
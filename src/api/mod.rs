@@ -1,3 +1,13 @@
+pub mod cache;
+pub mod client;
+pub mod endpoints;
+pub mod error;
 pub mod fetcher;
+pub mod follow;
 pub mod handlers;
+pub mod heights;
+pub mod index;
+pub mod rpc;
+pub mod tracker;
+pub mod denoms;
 pub mod dumper;
\ No newline at end of file
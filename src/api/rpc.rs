@@ -0,0 +1,307 @@
+use crate::api::client::HttpClient;
+use crate::api::error::{FetchError, FetchTarget};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::models::pagination::Pagination;
+use crate::models::rpc::{BlockResponse, BlockResults, BlockchainInfo, RpcResponse, TxResult, TxSearchResult};
+use crate::models::transaction::{ResponseData, ResponseDataForHashQuery};
+
+/// Largest page `/tx_search` accepts.
+const MAX_SEARCH_PAGE_SIZE: u64 = 100;
+
+/// Most block metas `/blockchain` returns at once.
+const MAX_BLOCKCHAIN_SPAN: u64 = 20;
+
+/// Fetches the transactions of a block from the CometBFT RPC.
+///
+/// The raw txs come from `/block` and their execution results from `/block_results`. The txs are
+/// decoded and returned as a single `ResponseData` page, the same shape the LCD fetcher produces.
+///
+/// # Errors
+///
+/// Returns `FetchError::IncompleteData` when `/block_results` has fewer results than the block has
+/// txs, and `FetchError::Translate` when a tx cannot be decoded.
+pub async fn fetch_transactions_for_height(client: &HttpClient, height: u64) -> Result<Vec<ResponseData>, FetchError> {
+    let params = [("height", height.to_string())];
+    let (block, results) = futures::try_join!(
+        client.get_json::<RpcResponse<BlockResponse>>("/block", &params),
+        client.get_json::<RpcResponse<BlockResults>>("/block_results", &params),
+    ).map_err(|e| e.with_target(FetchTarget::Height(height)))?;
+
+    let data = block.result.block.to_response_data(&results.result)
+        .map_err(|e| FetchError::from(e).with_target(FetchTarget::Height(height)))?;
+
+    let expected = data.pagination.total.parse::<u64>().unwrap_or_default();
+    let received = data.tx_responses.len() as u64;
+    if expected != received {
+        return Err(FetchError::IncompleteData { height, expected, received });
+    }
+
+    Ok(vec![data])
+}
+
+/// Fetches a single transaction by hash from the CometBFT RPC (`/tx`), with the time of its block.
+pub async fn fetch_by_tx_hash(client: &HttpClient, tx_hash: &str) -> Result<ResponseDataForHashQuery, FetchError> {
+    let target = FetchTarget::Hash(tx_hash.to_string());
+    let hash = format!("0x{}", tx_hash.trim_start_matches("0x"));
+
+    let tx: RpcResponse<TxResult> = client.get_json("/tx", &[("hash", hash)]).await
+        .map_err(|e| e.with_target(target.clone()))?;
+    let height = parse_height(&tx.result.height).map_err(|e| e.with_target(target.clone()))?;
+    let times = fetch_block_times(client, &[height]).await
+        .map_err(|e| e.with_target(target.clone()))?;
+
+    tx.result.to_hash_query(&times[&height])
+        .map_err(|e| FetchError::from(e).with_target(target))
+}
+
+/// Fetches every transaction matching all of the given event conditions with `/tx_search`,
+/// oldest first, as a single `ResponseData`.
+pub async fn search_transactions(client: &HttpClient, events: &[String]) -> Result<Vec<ResponseData>, FetchError> {
+    let query = events.join(" AND ");
+    let target = FetchTarget::Events(query.clone());
+    let per_page = client.config().page_size().min(MAX_SEARCH_PAGE_SIZE);
+
+    let mut results: Vec<TxResult> = Vec::new();
+    let mut page = 1;
+    loop {
+        let params = [
+            ("query", format!("\"{}\"", query)),
+            ("page", page.to_string()),
+            ("per_page", per_page.to_string()),
+            ("order_by", "\"asc\"".to_string()),
+        ];
+        let response: RpcResponse<TxSearchResult> = client.get_json("/tx_search", &params).await
+            .map_err(|e| e.with_target(target.clone()))?;
+
+        let total = response.result.total_count.parse::<usize>().unwrap_or_default();
+        let fetched = response.result.txs.len();
+        results.extend(response.result.txs);
+        if fetched == 0 || results.len() >= total {
+            break;
+        }
+        page += 1;
+    }
+
+    // `/tx_search` does not report block times, so they are looked up for the distinct heights.
+    let heights: Vec<u64> = results.iter()
+        .map(|result| parse_height(&result.height))
+        .collect::<Result<BTreeSet<u64>, FetchError>>()
+        .map_err(|e| e.with_target(target.clone()))?
+        .into_iter()
+        .collect();
+    let times = fetch_block_times(client, &heights).await
+        .map_err(|e| e.with_target(target.clone()))?;
+
+    let mut data = ResponseData {
+        txs: Vec::with_capacity(results.len()),
+        tx_responses: Vec::with_capacity(results.len()),
+        pagination: Pagination { next_key: None, total: results.len().to_string() },
+    };
+    for result in results {
+        let time = &times[&parse_height(&result.height)?];
+        let tx = result.to_hash_query(time)
+            .map_err(|e| FetchError::from(e).with_target(target.clone()))?;
+        data.txs.push(tx.tx);
+        data.tx_responses.push(tx.tx_response);
+    }
+
+    Ok(vec![data])
+}
+
+/// Fetches the block times of ascending `heights` from `/blockchain`, which serves only block
+/// metadata and up to 20 blocks per request. Heights close together share a request, and the
+/// requests run concurrently.
+async fn fetch_block_times(client: &HttpClient, heights: &[u64]) -> Result<HashMap<u64, String>, FetchError> {
+    let mut spans: Vec<(u64, u64)> = Vec::new();
+    for &height in heights {
+        match spans.last_mut() {
+            Some((min, max)) if height < *min + MAX_BLOCKCHAIN_SPAN => *max = height,
+            _ => spans.push((height, height)),
+        }
+    }
+
+    let batches: Vec<BlockchainInfo> = stream::iter(spans)
+        .map(|(min, max)| async move {
+            let params = [("minHeight", min.to_string()), ("maxHeight", max.to_string())];
+            client.get_json::<RpcResponse<BlockchainInfo>>("/blockchain", &params).await
+                .map(|response| response.result)
+        })
+        .buffered(client.config().concurrency())
+        .try_collect()
+        .await?;
+
+    let mut times = HashMap::new();
+    for meta in batches.into_iter().flat_map(|batch| batch.block_metas) {
+        times.insert(parse_height(&meta.header.height)?, meta.header.time);
+    }
+    match heights.iter().find(|height| !times.contains_key(height)) {
+        Some(height) => Err(FetchError::TaskFailure(format!("/blockchain did not return block {}", height))),
+        None => Ok(times),
+    }
+}
+
+fn parse_height(height: &str) -> Result<u64, FetchError> {
+    height.parse().map_err(|e| FetchError::TaskFailure(format!("invalid block height '{}': {}", height, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use mockito::Matcher;
+    use prost::Message as _;
+    use serde_json::json;
+
+    use crate::config::config::{Backend, Config};
+    use crate::models::proto::{tx_hash, Any, TxBody, TxRaw};
+
+    use super::*;
+
+    fn mock_client(url: String) -> HttpClient {
+        let config = Config {
+            url,
+            backend: Backend::Rpc,
+            ..Config::default()
+        };
+        HttpClient::new(&config).unwrap()
+    }
+
+    fn mock_raw_tx() -> Vec<u8> {
+        let body = TxBody {
+            messages: vec![Any { type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(), value: vec![] }],
+            memo: "rpc".to_string(),
+            timeout_height: 0,
+        };
+        TxRaw { body_bytes: body.encode_to_vec(), auth_info_bytes: vec![], signatures: vec![] }.encode_to_vec()
+    }
+
+    fn mock_block(height: u64, txs: &[Vec<u8>]) -> String {
+        json!({
+            "jsonrpc": "2.0",
+            "id": -1,
+            "result": {
+                "block": {
+                    "header": { "height": height.to_string(), "time": "2023-01-01T00:00:00Z" },
+                    "data": { "txs": txs.iter().map(|tx| STANDARD.encode(tx)).collect::<Vec<_>>() }
+                }
+            }
+        }).to_string()
+    }
+
+    fn mock_blockchain(heights: &[u64]) -> String {
+        let metas: Vec<_> = heights.iter().rev()
+            .map(|height| json!({ "header": { "height": height.to_string(), "time": format!("2023-01-01T00:00:{:02}Z", height % 60) } }))
+            .collect();
+        json!({ "result": { "last_height": "100", "block_metas": metas } }).to_string()
+    }
+
+    fn mock_exec_result() -> serde_json::Value {
+        json!({ "code": 0, "log": "", "gas_wanted": "100", "gas_used": "50", "events": [] })
+    }
+
+    #[tokio::test]
+    async fn test_fetch_transactions_for_height() {
+        let mut server = mockito::Server::new_async().await;
+        let raw_tx = mock_raw_tx();
+        server.mock("GET", "/block")
+            .match_query(Matcher::UrlEncoded("height".into(), "7".into()))
+            .with_body(mock_block(7, std::slice::from_ref(&raw_tx)))
+            .create_async()
+            .await;
+        server.mock("GET", "/block_results")
+            .match_query(Matcher::UrlEncoded("height".into(), "7".into()))
+            .with_body(json!({ "result": { "height": "7", "txs_results": [mock_exec_result()] } }).to_string())
+            .create_async()
+            .await;
+
+        let pages = fetch_transactions_for_height(&mock_client(server.url()), 7).await.unwrap();
+
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].tx_responses[0].txhash, tx_hash(&raw_tx));
+        assert_eq!(pages[0].tx_responses[0].height, "7");
+        assert_eq!(pages[0].txs[0].body.memo, "rpc");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_transactions_for_height_detects_missing_results() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/block")
+            .match_query(Matcher::Any)
+            .with_body(mock_block(7, &[mock_raw_tx(), mock_raw_tx()]))
+            .create_async()
+            .await;
+        server.mock("GET", "/block_results")
+            .match_query(Matcher::Any)
+            .with_body(json!({ "result": { "height": "7", "txs_results": [mock_exec_result()] } }).to_string())
+            .create_async()
+            .await;
+
+        let result = fetch_transactions_for_height(&mock_client(server.url()), 7).await;
+
+        assert!(matches!(result, Err(FetchError::IncompleteData { height: 7, expected: 2, received: 1 })));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_by_tx_hash() {
+        let mut server = mockito::Server::new_async().await;
+        let raw_tx = mock_raw_tx();
+        let hash = tx_hash(&raw_tx);
+        server.mock("GET", "/tx")
+            .match_query(Matcher::UrlEncoded("hash".into(), format!("0x{}", hash)))
+            .with_body(json!({
+                "result": { "hash": hash, "height": "9", "tx_result": mock_exec_result(), "tx": STANDARD.encode(&raw_tx) }
+            }).to_string())
+            .create_async()
+            .await;
+        server.mock("GET", "/blockchain")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("minHeight".into(), "9".into()),
+                Matcher::UrlEncoded("maxHeight".into(), "9".into()),
+            ]))
+            .with_body(mock_blockchain(&[9]))
+            .create_async()
+            .await;
+
+        let data = fetch_by_tx_hash(&mock_client(server.url()), &hash).await.unwrap();
+
+        assert_eq!(data.tx_response.txhash, hash);
+        assert_eq!(data.tx_response.timestamp, "2023-01-01T00:00:09Z");
+    }
+
+    #[tokio::test]
+    async fn test_search_transactions_batches_block_times() {
+        let mut server = mockito::Server::new_async().await;
+        let raw_tx = mock_raw_tx();
+        let hash = tx_hash(&raw_tx);
+        let results: Vec<_> = [3, 3, 12, 40].iter()
+            .map(|height| json!({ "hash": hash, "height": height.to_string(), "tx_result": mock_exec_result(), "tx": STANDARD.encode(&raw_tx) }))
+            .collect();
+        server.mock("GET", "/tx_search")
+            .match_query(Matcher::Any)
+            .with_body(json!({ "result": { "txs": results, "total_count": "4" } }).to_string())
+            .create_async()
+            .await;
+        let mut batches = Vec::new();
+        for (min, max, heights) in [(3, 12, vec![3, 12]), (40, 40, vec![40])] {
+            batches.push(server.mock("GET", "/blockchain")
+                .match_query(Matcher::AllOf(vec![
+                    Matcher::UrlEncoded("minHeight".into(), min.to_string()),
+                    Matcher::UrlEncoded("maxHeight".into(), max.to_string()),
+                ]))
+                .with_body(mock_blockchain(&heights))
+                .expect(1)
+                .create_async()
+                .await);
+        }
+
+        let pages = search_transactions(&mock_client(server.url()), &["message.sender='cosmos1abc'".to_string()]).await.unwrap();
+
+        let times: Vec<&str> = pages[0].tx_responses.iter().map(|response| response.timestamp.as_str()).collect();
+        assert_eq!(times, vec!["2023-01-01T00:00:03Z", "2023-01-01T00:00:03Z", "2023-01-01T00:00:12Z", "2023-01-01T00:00:40Z"]);
+        for batch in batches {
+            batch.assert_async().await;
+        }
+    }
+}
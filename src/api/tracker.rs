@@ -0,0 +1,307 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::api::client::HttpClient;
+use crate::api::error::{FetchError, FetchTarget};
+use crate::api::fetcher::{fetch_by_tx_hash, fetch_transactions_by_events};
+use crate::config::config::Backend;
+use crate::models::event::Event;
+use crate::models::ibc::{FungibleTokenPacketData, PacketStep, TransferLifecycle};
+use crate::models::transaction::TxResponse;
+
+#[derive(Deserialize)]
+struct ClientStateResponse {
+    identified_client_state: IdentifiedClientState,
+}
+
+#[derive(Deserialize)]
+struct IdentifiedClientState {
+    client_state: ClientState,
+}
+
+#[derive(Deserialize)]
+struct ClientState {
+    #[serde(default)]
+    chain_id: String,
+}
+
+/// Reads the packets sent by a transaction on the source chain from its `send_packet` events.
+///
+/// The returned lifecycles only have their `sent` step filled; `destination_chain` is left empty
+/// until the counterparty is known.
+pub async fn sent_packets(client: &HttpClient, tx_hash: &str) -> Result<Vec<TransferLifecycle>, FetchError> {
+    let data = fetch_by_tx_hash(client, tx_hash).await?;
+    let target = FetchTarget::Hash(tx_hash.to_string());
+    let response = &data.tx_response;
+    if response.code != 0 {
+        return Err(FetchError::TaskFailure(format!("{} failed with code {}, no packet was sent", target, response.code)));
+    }
+
+    let sent = packet_step(response).map_err(|e| e.with_target(target.clone()))?;
+    let source_chain = client.config().chain_id().to_string();
+    let packets: Vec<TransferLifecycle> = response.all_events().iter()
+        .filter(|event| event.kind == "send_packet")
+        .filter_map(|event| {
+            Some(TransferLifecycle {
+                source_chain: source_chain.clone(),
+                destination_chain: String::new(),
+                sequence: event.attribute("packet_sequence")?.to_string(),
+                source_port: event.attribute("packet_src_port")?.to_string(),
+                source_channel: event.attribute("packet_src_channel")?.to_string(),
+                destination_port: event.attribute("packet_dst_port")?.to_string(),
+                destination_channel: event.attribute("packet_dst_channel")?.to_string(),
+                transfer: event.attribute("packet_data").and_then(FungibleTokenPacketData::from_json),
+                sent: sent.clone(),
+                received: None,
+                acknowledged: None,
+                timed_out: None,
+                ack_error: None,
+            })
+        })
+        .collect();
+
+    if packets.is_empty() {
+        return Err(FetchError::TaskFailure(format!("{} did not send any IBC packet", target)));
+    }
+    Ok(packets)
+}
+
+/// Looks up the chain id behind a channel from the client state of its connection on the LCD.
+pub async fn counterparty_chain_id(client: &HttpClient, port: &str, channel: &str) -> Result<String, FetchError> {
+    if client.config().backend() == Backend::Rpc {
+        return Err(FetchError::TaskFailure(format!(
+            "the counterparty of {}/{} can only be looked up on the LCD; pass --dest-chain", port, channel
+        )));
+    }
+
+    let path = format!("/ibc/core/channel/v1/channels/{}/ports/{}/client_state", channel, port);
+    let response: ClientStateResponse = client.get_json(&path, &[]).await?;
+    Ok(response.identified_client_state.client_state.chain_id)
+}
+
+/// Fills in the receive step on the destination chain and the acknowledgement or timeout back on
+/// the source chain.
+pub async fn follow_packet(source: &HttpClient, destination: &HttpClient, lifecycle: &mut TransferLifecycle) -> Result<(), FetchError> {
+    let recv_events = packet_query("recv_packet", lifecycle);
+    if let Some(response) = first_packet_tx(destination, &recv_events, "recv_packet", lifecycle).await? {
+        lifecycle.received = Some(packet_step(&response)?);
+        lifecycle.ack_error = response.all_events().iter()
+            .filter(|ack| ack.kind == "write_acknowledgement" && is_same_packet(ack, lifecycle))
+            .find_map(|ack| ack.attribute("packet_ack").and_then(ack_error));
+    }
+
+    let ack_events = packet_query("acknowledge_packet", lifecycle);
+    if let Some(response) = first_packet_tx(source, &ack_events, "acknowledge_packet", lifecycle).await? {
+        lifecycle.acknowledged = Some(packet_step(&response)?);
+        return Ok(());
+    }
+
+    let timeout_events = packet_query("timeout_packet", lifecycle);
+    if let Some(response) = first_packet_tx(source, &timeout_events, "timeout_packet", lifecycle).await? {
+        lifecycle.timed_out = Some(packet_step(&response)?);
+    }
+    Ok(())
+}
+
+/// The event conditions identifying one packet, e.g. `recv_packet.packet_sequence='7'`.
+fn packet_query(kind: &str, lifecycle: &TransferLifecycle) -> Vec<String> {
+    vec![
+        format!("{}.packet_sequence='{}'", kind, lifecycle.sequence),
+        format!("{}.packet_src_channel='{}'", kind, lifecycle.source_channel),
+        format!("{}.packet_dst_channel='{}'", kind, lifecycle.destination_channel),
+    ]
+}
+
+/// The earliest successful transaction carrying the packet event. Relayers racing for the same
+/// packet leave failed or redundant transactions behind, which are skipped.
+async fn first_packet_tx(client: &HttpClient, events: &[String], kind: &str, lifecycle: &TransferLifecycle) -> Result<Option<TxResponse>, FetchError> {
+    let data = fetch_transactions_by_events(client, events).await?;
+    let mut matches: Vec<TxResponse> = data.into_iter()
+        .flat_map(|page| page.tx_responses)
+        .filter(|response| response.code == 0)
+        .filter(|response| response.all_events().iter().any(|event| event.kind == kind && is_same_packet(event, lifecycle)))
+        .collect();
+    matches.sort_by_key(|response| response.height.parse::<u64>().unwrap_or(u64::MAX));
+    Ok(matches.into_iter().next())
+}
+
+fn is_same_packet(event: &Event, lifecycle: &TransferLifecycle) -> bool {
+    event.attribute("packet_sequence") == Some(lifecycle.sequence.as_str())
+        && event.attribute("packet_src_channel") == Some(lifecycle.source_channel.as_str())
+        && event.attribute("packet_dst_channel") == Some(lifecycle.destination_channel.as_str())
+}
+
+/// The error of an ICS-04 acknowledgement, which is either `{"result": ...}` or `{"error": ...}`.
+fn ack_error(ack: &str) -> Option<String> {
+    let ack: Value = serde_json::from_str(ack).ok()?;
+    ack.get("error").and_then(Value::as_str).map(str::to_string)
+}
+
+fn packet_step(response: &TxResponse) -> Result<PacketStep, FetchError> {
+    let height = response.height.parse::<u64>()
+        .map_err(|e| FetchError::TaskFailure(format!("invalid height '{}' for {}: {}", response.height, response.txhash, e)))?;
+    let timestamp = response.timestamp.parse::<DateTime<Utc>>()
+        .map_err(|e| FetchError::TaskFailure(format!("invalid timestamp '{}' for {}: {}", response.timestamp, response.txhash, e)))?;
+    Ok(PacketStep { tx_hash: response.txhash.clone(), height, timestamp })
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::Matcher;
+    use serde_json::json;
+
+    use crate::config::config::Config;
+
+    use super::*;
+    use crate::models::testing::{tx_json, txs_page};
+
+    fn mock_client(url: String, chain_id: &str) -> HttpClient {
+        let config = Config {
+            url,
+            chain_id: chain_id.to_string(),
+            ..Config::default()
+        };
+        HttpClient::new(&config).unwrap()
+    }
+
+    fn packet_event(kind: &str, extra: &[(&str, &str)]) -> Value {
+        let mut attributes = vec![
+            json!({"key": "packet_sequence", "value": "7"}),
+            json!({"key": "packet_src_port", "value": "transfer"}),
+            json!({"key": "packet_src_channel", "value": "channel-141"}),
+            json!({"key": "packet_dst_port", "value": "transfer"}),
+            json!({"key": "packet_dst_channel", "value": "channel-0"}),
+        ];
+        attributes.extend(extra.iter().map(|(key, value)| json!({"key": key, "value": value})));
+        json!({"type": kind, "attributes": attributes})
+    }
+
+    fn tx_response(hash: &str, height: u64, timestamp: &str, code: i32, events: Vec<Value>) -> Value {
+        json!({
+            "height": height.to_string(),
+            "txhash": hash,
+            "codespace": "",
+            "code": code,
+            "data": "",
+            "raw_log": "",
+            "logs": [],
+            "gas_wanted": "200000",
+            "gas_used": "100000",
+            "timestamp": timestamp,
+            "events": events
+        })
+    }
+
+    async fn mock_search(server: &mut mockito::Server, kind: &str, responses: Vec<Value>) -> mockito::Mock {
+        server.mock("GET", "/cosmos/tx/v1beta1/txs")
+            // Repeated `events` parameters are not told apart by `Matcher::UrlEncoded`.
+            .match_query(Matcher::Regex(format!("events={}.packet_sequence", kind)))
+            .with_body(txs_page(responses, json!([])))
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_sent_packets_reads_send_packet_events() {
+        let mut server = mockito::Server::new_async().await;
+        let transfer = r#"{"amount":"100","denom":"uatom","receiver":"osmo1abc","sender":"cosmos1abc"}"#;
+        let send = packet_event("send_packet", &[("packet_data", transfer)]);
+        server.mock("GET", "/cosmos/tx/v1beta1/txs/SEND")
+            .with_body(json!({
+                "tx": tx_json(json!([])),
+                "tx_response": tx_response("SEND", 100, "2023-01-01T00:00:00Z", 0, vec![send])
+            }).to_string())
+            .create_async()
+            .await;
+
+        let client = mock_client(server.url(), "cosmoshub-4");
+        let packets = sent_packets(&client, "SEND").await.unwrap();
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0].source_chain, "cosmoshub-4");
+        assert_eq!(packets[0].sequence, "7");
+        assert_eq!(packets[0].destination_channel, "channel-0");
+        assert_eq!(packets[0].sent.height, 100);
+        assert_eq!(packets[0].transfer.as_ref().unwrap().receiver, "osmo1abc");
+    }
+
+    #[tokio::test]
+    async fn test_follow_packet_to_acknowledgement() {
+        let mut source_server = mockito::Server::new_async().await;
+        let mut destination_server = mockito::Server::new_async().await;
+        let source_client = mock_client(source_server.url(), "cosmoshub-4");
+        let destination_client = mock_client(destination_server.url(), "osmosis-1");
+
+        // A relayer that lost the race leaves a failed transaction behind.
+        let recv = mock_search(&mut destination_server, "recv_packet", vec![
+            tx_response("LATE", 501, "2023-01-01T00:00:20Z", 0, vec![packet_event("recv_packet", &[])]),
+            tx_response("RACED", 500, "2023-01-01T00:00:12Z", 11, vec![packet_event("recv_packet", &[])]),
+            tx_response("RECV", 500, "2023-01-01T00:00:12Z", 0, vec![
+                packet_event("recv_packet", &[]),
+                packet_event("write_acknowledgement", &[("packet_ack", r#"{"result":"AQ=="}"#)]),
+            ]),
+        ]).await;
+        let ack = mock_search(&mut source_server, "acknowledge_packet", vec![
+            tx_response("ACK", 105, "2023-01-01T00:00:30Z", 0, vec![packet_event("acknowledge_packet", &[])]),
+        ]).await;
+
+        let mut lifecycle = sent_lifecycle();
+        follow_packet(&source_client, &destination_client, &mut lifecycle).await.unwrap();
+
+        recv.assert_async().await;
+        ack.assert_async().await;
+        assert_eq!(lifecycle.received.as_ref().unwrap().tx_hash, "RECV");
+        assert_eq!(lifecycle.acknowledged.as_ref().unwrap().tx_hash, "ACK");
+        assert_eq!(lifecycle.status(), crate::models::ibc::TransferStatus::Completed);
+        assert_eq!(lifecycle.completion_latency().unwrap().num_seconds(), 30);
+    }
+
+    #[tokio::test]
+    async fn test_follow_packet_to_timeout() {
+        let mut source_server = mockito::Server::new_async().await;
+        let mut destination_server = mockito::Server::new_async().await;
+        let source_client = mock_client(source_server.url(), "cosmoshub-4");
+        let destination_client = mock_client(destination_server.url(), "osmosis-1");
+
+        mock_search(&mut destination_server, "recv_packet", vec![]).await;
+        mock_search(&mut source_server, "acknowledge_packet", vec![]).await;
+        mock_search(&mut source_server, "timeout_packet", vec![
+            tx_response("TIMEOUT", 200, "2023-01-01T01:00:00Z", 0, vec![packet_event("timeout_packet", &[])]),
+        ]).await;
+
+        let mut lifecycle = sent_lifecycle();
+        follow_packet(&source_client, &destination_client, &mut lifecycle).await.unwrap();
+
+        assert_eq!(lifecycle.received, None);
+        assert_eq!(lifecycle.timed_out.as_ref().unwrap().tx_hash, "TIMEOUT");
+        assert_eq!(lifecycle.status(), crate::models::ibc::TransferStatus::TimedOut);
+    }
+
+    #[test]
+    fn test_ack_error() {
+        assert_eq!(ack_error(r#"{"result":"AQ=="}"#), None);
+        assert_eq!(ack_error(r#"{"error":"ABCI code: 5"}"#), Some("ABCI code: 5".to_string()));
+    }
+
+    fn sent_lifecycle() -> TransferLifecycle {
+        TransferLifecycle {
+            source_chain: "cosmoshub-4".to_string(),
+            destination_chain: "osmosis-1".to_string(),
+            sequence: "7".to_string(),
+            source_port: "transfer".to_string(),
+            source_channel: "channel-141".to_string(),
+            destination_port: "transfer".to_string(),
+            destination_channel: "channel-0".to_string(),
+            transfer: None,
+            sent: PacketStep {
+                tx_hash: "SEND".to_string(),
+                height: 100,
+                timestamp: "2023-01-01T00:00:00Z".parse().unwrap(),
+            },
+            received: None,
+            acknowledged: None,
+            timed_out: None,
+            ack_error: None,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 // Import necessary libraries and modules.
 use clap::{Parser};
-use crate::models;
+use crate::models::block::TimeBound;
+use crate::models::event::EventFilter;
+use crate::models::fee::FeeGrouping;
 use crate::models::message::MessageType;
 
 // Enum to represent the different message types supported.
@@ -25,6 +27,70 @@ pub struct CommonQueryFlags {
     pub raw: Option<bool>,
 }
 
+// Filters shared by every tx-details subcommand.
+#[derive(Parser, Debug, Clone)]
+pub struct TxFilterFlags {
+    /// Only list transactions that emitted a matching event: type, type.key or type.key=value, such as transfer.recipient=cosmos1...
+    #[clap(long)]
+    pub filter_by_event: Option<EventFilter>,
+    /// Only list successful transactions.
+    #[clap(long, conflicts_with = "only_failed")]
+    pub only_success: Option<bool>,
+    /// Only list failed transactions.
+    #[clap(long)]
+    pub only_failed: Option<bool>,
+}
+
+// Filters, annotations and sorting shared by every msg-details subcommand.
+#[derive(Parser, Debug, Clone)]
+pub struct MsgFilterFlags {
+    /// Sort results by the timestamp of the transactions.
+    #[clap(long)]
+    pub sort_by_timestamp: Option<String>,
+    /// Sort results by the gas used in the transactions.
+    #[clap(long)]
+    pub sort_by_gas_used: Option<String>,
+    /// Filter messages by type: a registered name such as MsgSend or MsgDelegate, Other, or a type URL such as /osmosis.gamm.v1beta1.MsgSwapExactAmountIn.
+    #[clap(long)]
+    pub filter_by_msgtype: Option<MessageType>,
+    /// Also list the messages executed through authz MsgExec, with their grantee.
+    #[clap(long)]
+    pub expand_authz: Option<bool>,
+    /// Only list CosmWasm messages addressed to this contract.
+    #[clap(long)]
+    pub contract: Option<String>,
+    /// Only list contract executions of this top-level method, such as swap or transfer.
+    #[clap(long)]
+    pub execute_method: Option<String>,
+    /// Only list messages that emitted a matching event: type, type.key or type.key=value, such as transfer.recipient=cosmos1...
+    /// Nodes report events per top-level message, so with --expand-authz every message inside a MsgExec matches the events of the whole MsgExec.
+    #[clap(long)]
+    pub filter_by_event: Option<EventFilter>,
+    /// Only list the messages of successful transactions.
+    #[clap(long, conflicts_with = "only_failed")]
+    pub only_success: Option<bool>,
+    /// Only list the messages of failed transactions.
+    #[clap(long)]
+    pub only_failed: Option<bool>,
+}
+
+// The heights of a range query, given directly or as the times the range should cover.
+#[derive(Parser, Debug, Clone)]
+pub struct HeightRangeFlags {
+    /// Start the range at the first block at or after this time: RFC3339, or a date for the start of that day in UTC. Defaults to the earliest available block.
+    #[clap(long, conflicts_with_all = ["from_height", "to_height"])]
+    pub from_time: Option<TimeBound>,
+    /// End the range at the last block at or before this time: RFC3339, or a date for the end of that day in UTC. Defaults to the latest block.
+    #[clap(long, conflicts_with_all = ["from_height", "to_height"])]
+    pub to_time: Option<TimeBound>,
+    /// Starting height of the transaction range to query.
+    #[clap(required_unless_present_any = ["from_time", "to_time"])]
+    pub from_height: Option<u64>,
+    /// Ending height of the transaction range to query.
+    #[clap(required_unless_present_any = ["from_time", "to_time"])]
+    pub to_height: Option<u64>,
+}
+
 // Define the primary command line arguments for the application.
 #[derive(Parser, Debug)]
 #[clap(version = "1.0", author = "Your Name")]
@@ -40,10 +106,20 @@ pub struct Opts {
 
 // Enum representing the main subcommands available for transactions.
 #[derive(Parser, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum TxDumpCommand {
     QueryTxAtHeight(QueryTxAtHeightOpts),
     QueryTxHash(QueryTxHashOpts),
     QueryTxForRangeHeight(QueryTxForRangeHeightOpts),
+    /// Query the transactions involving an address as message sender, transfer sender or transfer recipient.
+    QueryTxByAddress(QueryTxByAddressOpts),
+    /// Query the transactions matching every given event condition.
+    QueryTxByEvents(QueryTxByEventsOpts),
+    /// Stream the messages of new blocks as they are produced.
+    QueryTxFollow(FollowOpts),
+    /// Store the transactions of a height range in the local index, fetching only the heights not stored yet.
+    Sync(SyncOpts),
+    TrackTransfer(TrackTransferOpts),
 }
 
 // Struct for options related to querying a transaction by its height.
@@ -69,6 +145,8 @@ pub struct BundledMsgsOpts {
     /// Option to dump data in CSV format.
     #[clap(short, long)]
     pub dump_csv: Option<bool>,
+    #[clap(flatten)]
+    pub filters: TxFilterFlags,
     /// Height of the transaction to query.
     pub height: u64,
 }
@@ -81,15 +159,8 @@ pub struct IndividualMsgOpts {
     /// Option to dump data in CSV format.
     #[clap(short, long)]
     pub dump_csv: Option<bool>,
-    /// Sort results by the timestamp of the transaction.
-    #[clap(long)]
-    pub sort_by_timestamp: Option<String>,
-    /// Sort results by the gas used in the transaction.
-    #[clap(long)]
-    pub sort_by_gas_used: Option<String>,
-    /// Filter transactions based on a specific message type.
-    #[clap(long)]
-    pub filter_by_msgtype: Option<models::message::MessageType>,
+    #[clap(flatten)]
+    pub filters: MsgFilterFlags,
     /// Height of the transaction to query.
     pub height: u64,
 }
@@ -116,6 +187,12 @@ pub struct QueryTxForRangeHeightOpts {
 pub enum QueryTxForRangeHeightSubCommand {
     TxDetails(BundledMsgsRangeOpts),
     MsgDetails(IndividualMsgRangeOpts),
+    /// List the gov votes cast in the range, per proposal, with option weights.
+    Gov(GovReportOpts),
+    /// Sum the fees paid in the range per fee payer or per block.
+    Fees(FeeReportOpts),
+    /// Count the failed transactions in the range per error.
+    Failures(FailureReportOpts),
 }
 
 // Options specific to querying bundled message details over a range of heights.
@@ -126,10 +203,10 @@ pub struct BundledMsgsRangeOpts {
     /// Option to dump data in CSV format.
     #[clap(short, long)]
     pub dump_csv: Option<bool>,
-    /// Starting height of the transaction range to query.
-    pub from_height: u64,
-    /// Ending height of the transaction range to query.
-    pub to_height: u64,
+    #[clap(flatten)]
+    pub filters: TxFilterFlags,
+    #[clap(flatten)]
+    pub range: HeightRangeFlags,
 }
 
 // Options specific to querying individual message details over a range of heights.
@@ -140,17 +217,196 @@ pub struct IndividualMsgRangeOpts {
     /// Option to dump data in CSV format.
     #[clap(short, long)]
     pub dump_csv: Option<bool>,
-    /// Sort results by the timestamp of the transactions.
+    #[clap(flatten)]
+    pub filters: MsgFilterFlags,
+    #[clap(flatten)]
+    pub range: HeightRangeFlags,
+}
+
+// Options for querying the transactions involving an address.
+#[derive(Parser, Debug)]
+pub struct QueryTxByAddressOpts {
+    /// Subcommand to query transaction or message details for the address.
+    #[clap(subcommand)]
+    pub cmd: QueryTxByAddressSubCommand,
+}
+
+// Enum for subcommands under "QueryTxByAddress".
+#[derive(Parser, Debug)]
+pub enum QueryTxByAddressSubCommand {
+    TxDetails(AddressTxOpts),
+    MsgDetails(AddressMsgOpts),
+}
+
+// The address to look up and the optional height bounds of an address query.
+#[derive(Parser, Debug, Clone)]
+pub struct AddressQueryFlags {
+    /// Only list transactions at or above this height.
     #[clap(long)]
-    pub sort_by_timestamp: Option<String>,
-    /// Sort results by the gas used in the transactions.
+    pub from_height: Option<u64>,
+    /// Only list transactions at or below this height.
     #[clap(long)]
-    pub sort_by_gas_used: Option<String>,
-    /// Filter transactions based on a specific message type.
+    pub to_height: Option<u64>,
+    /// The account address, such as cosmos1...
+    pub address: String,
+}
+
+// Options specific to querying bundled message details by address.
+#[derive(Parser, Debug)]
+pub struct AddressTxOpts {
+    /// Option to dump data in CSV format.
+    #[clap(short, long)]
+    pub dump_csv: Option<bool>,
+    #[clap(flatten)]
+    pub filters: TxFilterFlags,
+    #[clap(flatten)]
+    pub query: AddressQueryFlags,
+}
+
+// Options specific to querying individual message details by address.
+#[derive(Parser, Debug)]
+pub struct AddressMsgOpts {
+    /// Option to dump data in CSV format.
+    #[clap(short, long)]
+    pub dump_csv: Option<bool>,
+    #[clap(flatten)]
+    pub filters: MsgFilterFlags,
+    #[clap(flatten)]
+    pub query: AddressQueryFlags,
+}
+
+// Options for querying the transactions matching a set of event conditions.
+#[derive(Parser, Debug)]
+pub struct QueryTxByEventsOpts {
+    /// Subcommand to query transaction or message details for the matching transactions.
+    #[clap(subcommand)]
+    pub cmd: QueryTxByEventsSubCommand,
+}
+
+// Enum for subcommands under "QueryTxByEvents".
+#[derive(Parser, Debug)]
+pub enum QueryTxByEventsSubCommand {
+    TxDetails(EventsTxOpts),
+    MsgDetails(EventsMsgOpts),
+}
+
+// The event conditions of an event query, all of which must match.
+#[derive(Parser, Debug, Clone)]
+pub struct EventQueryFlags {
+    /// Event condition type.key=value, such as wasm._contract_address=juno1...; repeat it to require several.
+    #[clap(long = "event", required = true, value_parser = parse_event_condition)]
+    pub events: Vec<EventFilter>,
+}
+
+impl EventQueryFlags {
+    /// The conditions as sent to the node, such as `wasm._contract_address='juno1...'`.
+    pub fn conditions(&self) -> Vec<String> {
+        self.events.iter().filter_map(EventFilter::to_condition).collect()
+    }
+}
+
+fn parse_event_condition(s: &str) -> Result<EventFilter, String> {
+    let filter: EventFilter = s.parse()?;
+    if filter.value.is_none() {
+        return Err(format!("'{}' is not a valid event condition, expected type.key=value", s));
+    }
+    Ok(filter)
+}
+
+// Options specific to querying bundled message details by event conditions.
+#[derive(Parser, Debug)]
+pub struct EventsTxOpts {
+    #[clap(flatten)]
+    pub common_flags: CommonQueryFlags,
+    /// Option to dump data in CSV format.
+    #[clap(short, long)]
+    pub dump_csv: Option<bool>,
+    #[clap(flatten)]
+    pub filters: TxFilterFlags,
+    #[clap(flatten)]
+    pub query: EventQueryFlags,
+}
+
+// Options specific to querying individual message details by event conditions.
+#[derive(Parser, Debug)]
+pub struct EventsMsgOpts {
+    /// Option to dump data in CSV format.
+    #[clap(short, long)]
+    pub dump_csv: Option<bool>,
+    #[clap(flatten)]
+    pub filters: MsgFilterFlags,
+    #[clap(flatten)]
+    pub query: EventQueryFlags,
+}
+
+// Options for streaming the messages of new blocks as the chain produces them.
+#[derive(Parser, Debug)]
+pub struct FollowOpts {
+    /// Option to also append the messages to a CSV file as they arrive.
+    #[clap(short, long)]
+    pub dump_csv: Option<bool>,
+    #[clap(flatten)]
+    pub filters: MsgFilterFlags,
+    /// Height to start from, catching up on older blocks first. Defaults to the latest block.
     #[clap(long)]
-    pub filter_by_msgtype: Option<MessageType>,
-    /// Starting height of the transaction range to query.
-    pub from_height: u64,
-    /// Ending height of the transaction range to query.
-    pub to_height: u64,
-}
\ No newline at end of file
+    pub from_height: Option<u64>,
+    /// Height to stop after. Follows the chain indefinitely if omitted.
+    #[clap(long)]
+    pub to_height: Option<u64>,
+    /// Seconds between polls of the latest block, and between retries of a failed height.
+    #[clap(long, default_value_t = 2)]
+    pub poll_interval_secs: u64,
+}
+
+// Options for storing a height range in the local index.
+#[derive(Parser, Debug)]
+pub struct SyncOpts {
+    #[clap(flatten)]
+    pub range: HeightRangeFlags,
+}
+
+// Options for following an IBC transfer from the source chain to the destination chain and back.
+#[derive(Parser, Debug)]
+pub struct TrackTransferOpts {
+    /// Name of the destination chain under `[chains]` in the config. Looked up from the channel if omitted.
+    #[clap(long)]
+    pub dest_chain: Option<String>,
+    /// The hash of the transfer transaction on the source chain.
+    pub hash: String,
+}
+
+// Options for the fee report over a range of heights.
+#[derive(Parser, Debug)]
+pub struct FeeReportOpts {
+    /// Option to dump data in CSV format.
+    #[clap(short, long)]
+    pub dump_csv: Option<bool>,
+    /// Group fees by payer (default) or by height.
+    #[clap(long, default_value = "payer")]
+    pub by: FeeGrouping,
+    #[clap(flatten)]
+    pub range: HeightRangeFlags,
+}
+
+// Options for the failure report over a range of heights.
+#[derive(Parser, Debug)]
+pub struct FailureReportOpts {
+    /// Option to dump data in CSV format.
+    #[clap(short, long)]
+    pub dump_csv: Option<bool>,
+    #[clap(flatten)]
+    pub range: HeightRangeFlags,
+}
+
+// Options for the gov vote report over a range of heights.
+#[derive(Parser, Debug)]
+pub struct GovReportOpts {
+    /// Option to dump data in CSV format.
+    #[clap(short, long)]
+    pub dump_csv: Option<bool>,
+    /// Only list votes on this proposal.
+    #[clap(long)]
+    pub proposal_id: Option<u64>,
+    #[clap(flatten)]
+    pub range: HeightRangeFlags,
+}
@@ -1,33 +1,326 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::{Read};
+use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 
+/// Default number of transactions requested per page from the LCD.
+pub const DEFAULT_PAGE_SIZE: u64 = 100;
+
+/// Default number of heights fetched concurrently during a range query.
+pub const DEFAULT_CONCURRENCY: usize = 10;
 
 #[derive(Debug, Deserialize, Serialize,Clone)]
 pub struct Config {
+    /// Primary endpoint, an LCD or a CometBFT RPC depending on `backend`. Optional when `endpoints` is given.
+    #[serde(default)]
     pub(crate) url: String,
+    /// Which API the endpoints serve.
+    #[serde(default)]
+    pub(crate) backend: Backend,
+    /// Additional endpoints for the same chain, used for failover and load balancing.
+    #[serde(default)]
+    pub(crate) endpoints: Vec<String>,
+    /// How requests are spread over the endpoints and when an endpoint is taken out of rotation.
+    #[serde(default)]
+    pub(crate) failover: FailoverConfig,
+    /// Number of transactions requested per page (`pagination.limit`).
+    #[serde(default = "default_page_size")]
+    pub(crate) page_size: u64,
+    /// Maximum number of heights being fetched at the same time during a range query.
+    #[serde(default = "default_concurrency")]
+    pub(crate) concurrency: usize,
+    /// Timeouts and retry policy applied to every REST request.
+    #[serde(default)]
+    pub(crate) http: HttpConfig,
+    /// Chain id of the chain the endpoints belong to, e.g. `cosmoshub-4`.
+    #[serde(default)]
+    pub(crate) chain_id: String,
+    /// Other chains, by name, for commands that follow IBC packets across chains.
+    /// Each entry takes the same settings as the top level.
+    #[serde(default)]
+    pub(crate) chains: BTreeMap<String, Config>,
+    /// TOML file declaring chain-specific message types, registered on top of the built-in ones.
+    #[serde(default)]
+    pub(crate) message_schema: Option<String>,
+    /// JSON file caching resolved IBC denom traces between runs; defaults to the user cache directory.
+    #[serde(default)]
+    pub(crate) denom_trace_cache: Option<String>,
+    /// JSON file caching the bank denom metadata of each chain; defaults to the user cache directory.
+    #[serde(default)]
+    pub(crate) denom_metadata_cache: Option<String>,
+    /// JSON file caching block times read while resolving times to heights; defaults to the user cache directory.
+    #[serde(default)]
+    pub(crate) block_time_cache: Option<String>,
+    /// SQLite file the `sync` command stores transactions in; defaults to the user data directory.
+    #[serde(default)]
+    pub(crate) tx_index: Option<String>,
+    /// How the LCD takes tx search conditions; detected from the node when not set.
+    #[serde(default)]
+    pub(crate) tx_query: Option<TxQueryStyle>,
+}
+
+fn default_page_size() -> u64 {
+    DEFAULT_PAGE_SIZE
+}
+
+fn default_concurrency() -> usize {
+    DEFAULT_CONCURRENCY
+}
+
+/// The API transactions are fetched from.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    /// The Cosmos SDK REST API (`/cosmos/tx/v1beta1/txs`), usually on port 1317.
+    #[default]
+    Lcd,
+    /// The CometBFT RPC (`/block`, `/block_results`, `/tx`), usually on port 26657.
+    Rpc,
+}
+
+/// How conditions are passed to the LCD's tx search, `GetTxsEvent` (`/cosmos/tx/v1beta1/txs`).
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TxQueryStyle {
+    /// One `events` parameter per condition, paged with `pagination.key`; Cosmos SDK up to v0.47.
+    Events,
+    /// A single `query` string, paged with `page` and `limit`; Cosmos SDK v0.50 and later.
+    Query,
+}
+
+/// Timeout and retry settings for the shared HTTP client, read from the `[http]` table.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    /// Time allowed to establish a connection, in seconds.
+    pub connect_timeout_secs: u64,
+    /// Time allowed for a whole request including reading the body, in seconds.
+    pub timeout_secs: u64,
+    /// Number of retries after the first attempt before giving up.
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled on every further retry.
+    pub initial_backoff_ms: u64,
+    /// Upper bound for a single backoff, including a server supplied `Retry-After`.
+    pub max_backoff_ms: u64,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            connect_timeout_secs: 10,
+            timeout_secs: 30,
+            max_retries: 5,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+/// How the next endpoint is chosen when several are configured.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointStrategy {
+    /// Cycle through the healthy endpoints in turn.
+    RoundRobin,
+    /// Prefer the healthy endpoint with the lowest observed response time.
+    LowestLatency,
+    /// Use the first healthy endpoint in configuration order.
+    #[default]
+    PrimaryWithFallback,
+}
+
+/// Endpoint selection and health settings, read from the `[failover]` table.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FailoverConfig {
+    pub strategy: EndpointStrategy,
+    /// Consecutive failures after which an endpoint is taken out of rotation.
+    pub max_failures: u32,
+    /// How long an endpoint stays out of rotation, in seconds.
+    pub cooldown_secs: u64,
+    /// Probe every endpoint before the first request when more than one is configured.
+    pub health_check: bool,
 }
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        FailoverConfig {
+            strategy: EndpointStrategy::default(),
+            max_failures: 3,
+            cooldown_secs: 60,
+            health_check: true,
+        }
+    }
+}
+
 /// Implementation of Config
 impl Config{
     pub fn url(&self) -> &String{
         &self.url
     }
 
+    /// All configured endpoints, `url` first, without trailing slashes or duplicates.
+    pub fn endpoints(&self) -> Vec<String> {
+        let mut endpoints: Vec<String> = Vec::new();
+        for url in std::iter::once(&self.url).chain(self.endpoints.iter()) {
+            let url = url.trim().trim_end_matches('/').to_string();
+            if !url.is_empty() && !endpoints.contains(&url) {
+                endpoints.push(url);
+            }
+        }
+        endpoints
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    pub fn failover(&self) -> &FailoverConfig {
+        &self.failover
+    }
+
+    pub fn page_size(&self) -> u64 {
+        self.page_size
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    pub fn http(&self) -> &HttpConfig {
+        &self.http
+    }
+
+    pub fn chain_id(&self) -> &str {
+        &self.chain_id
+    }
+
+    pub fn chains(&self) -> &BTreeMap<String, Config> {
+        &self.chains
+    }
+
+    pub fn message_schema(&self) -> Option<&str> {
+        self.message_schema.as_deref()
+    }
+
+    pub fn tx_query(&self) -> Option<TxQueryStyle> {
+        self.tx_query
+    }
+
+    /// Where resolved IBC denom traces are cached, `<cache dir>/tx_dump/denom_traces.json` unless configured.
+    pub fn denom_trace_cache(&self) -> PathBuf {
+        match &self.denom_trace_cache {
+            Some(path) => PathBuf::from(path),
+            None => dirs::cache_dir().unwrap_or_default().join("tx_dump").join("denom_traces.json"),
+        }
+    }
+
+    /// Where denom metadata is cached, `<cache dir>/tx_dump/denom_metadata.json` unless configured.
+    pub fn denom_metadata_cache(&self) -> PathBuf {
+        match &self.denom_metadata_cache {
+            Some(path) => PathBuf::from(path),
+            None => dirs::cache_dir().unwrap_or_default().join("tx_dump").join("denom_metadata.json"),
+        }
+    }
+
+    /// Where block times are cached, `<cache dir>/tx_dump/block_times.json` unless configured.
+    pub fn block_time_cache(&self) -> PathBuf {
+        match &self.block_time_cache {
+            Some(path) => PathBuf::from(path),
+            None => dirs::cache_dir().unwrap_or_default().join("tx_dump").join("block_times.json"),
+        }
+    }
+
+    /// Where synced transactions are stored, `<data dir>/tx_dump/index.sqlite` unless configured.
+    pub fn tx_index(&self) -> PathBuf {
+        match &self.tx_index {
+            Some(path) => PathBuf::from(path),
+            None => dirs::data_dir().unwrap_or_default().join("tx_dump").join("index.sqlite"),
+        }
+    }
+
+    /// Names the chain in the on-disk caches: its chain id, or the primary endpoint when none is configured.
+    pub fn cache_key(&self) -> String {
+        if !self.chain_id.is_empty() {
+            return self.chain_id.clone();
+        }
+        self.endpoints().into_iter().next().unwrap_or_default()
+    }
+
+    /// The configured chain with the given chain id.
+    pub fn chain_by_id(&self, chain_id: &str) -> Option<(&String, &Config)> {
+        self.chains.iter().find(|(_, chain)| chain.chain_id == chain_id)
+    }
+
     pub fn from_file(path: &str) -> Result<Config, Box<dyn std::error::Error>> {
         let mut file = fs::File::open(path)?;
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
         let config:Config =toml::from_str(&contents)?;
+        config.validate()?;
+        for (name, chain) in &config.chains {
+            chain.validate().map_err(|e| format!("chains.{}: {}", name, e))?;
+        }
         println!("Config: {:?}", config);
         Ok(config)
     }
+
+    fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.endpoints().is_empty() {
+            return Err("either url or endpoints must be set".into());
+        }
+        if self.page_size == 0 {
+            return Err("page_size must be greater than zero".into());
+        }
+        if self.concurrency == 0 {
+            return Err("concurrency must be greater than zero".into());
+        }
+        Ok(())
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             url: "https://localhost:1317".to_string(),
+            backend: Backend::default(),
+            endpoints: Vec::new(),
+            failover: FailoverConfig::default(),
+            page_size: DEFAULT_PAGE_SIZE,
+            concurrency: DEFAULT_CONCURRENCY,
+            http: HttpConfig::default(),
+            chain_id: String::new(),
+            chains: BTreeMap::new(),
+            message_schema: None,
+            denom_trace_cache: None,
+            denom_metadata_cache: None,
+            block_time_cache: None,
+            tx_index: None,
+            tx_query: None,
         }
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key_falls_back_to_primary_endpoint() {
+        let config = Config { url: String::new(), endpoints: vec!["https://lcd.one/".to_string(), "https://lcd.two".to_string()], ..Config::default() };
+        assert_eq!(config.cache_key(), "https://lcd.one");
+        let config = Config { chain_id: "cosmoshub-4".to_string(), ..config };
+        assert_eq!(config.cache_key(), "cosmoshub-4");
+    }
+
+    #[test]
+    fn test_rejects_unknown_http_and_failover_keys() {
+        let misplaced = "url = \"https://lcd.one\"\n[http]\nendpoints = [\"https://lcd.two\"]\n";
+        assert!(toml::from_str::<Config>(misplaced).unwrap_err().to_string().contains("unknown field `endpoints`"));
+        assert!(toml::from_str::<Config>("[failover]\nmax_failure = 3\n").is_err());
+        let config: Config = toml::from_str("url = \"https://lcd.one\"\nendpoints = [\"https://lcd.two\"]\n[http]\nmax_retries = 1\n").unwrap();
+        assert_eq!(config.endpoints(), vec!["https://lcd.one", "https://lcd.two"]);
+    }
+}
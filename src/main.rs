@@ -42,6 +42,11 @@ async fn main() {
         TxDumpCommand::QueryTxAtHeight(query_height_opts) => api::handlers::handle_query_tx_at_height(&g_config.unwrap(), query_height_opts).await,
         TxDumpCommand::QueryTxHash(query_hash_opts) => api::handlers::handle_query_tx_hash(&g_config.unwrap(), query_hash_opts).await,
         TxDumpCommand::QueryTxForRangeHeight(query_range_height_opts) => api::handlers::handle_query_tx_for_range_height(g_config.unwrap(), query_range_height_opts).await,
+        TxDumpCommand::QueryTxByAddress(query_address_opts) => api::handlers::handle_query_tx_by_address(&g_config.unwrap(), query_address_opts).await,
+        TxDumpCommand::QueryTxByEvents(query_events_opts) => api::handlers::handle_query_tx_by_events(&g_config.unwrap(), query_events_opts).await,
+        TxDumpCommand::QueryTxFollow(follow_opts) => api::handlers::handle_query_tx_follow(&g_config.unwrap(), follow_opts).await,
+        TxDumpCommand::Sync(sync_opts) => api::handlers::handle_sync(&g_config.unwrap(), sync_opts).await,
+        TxDumpCommand::TrackTransfer(track_transfer_opts) => api::handlers::handle_track_transfer(&g_config.unwrap(), track_transfer_opts).await,
     }
 }
 
@@ -0,0 +1,164 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::message::Amount;
+
+/// The `auth_info` of a transaction: who signed it and what it pays in fees.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+pub struct AuthInfo {
+    #[serde(default)]
+    pub signer_infos: Vec<SignerInfo>,
+    #[serde(default)]
+    pub fee: Fee,
+}
+
+/// One signer of a transaction.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct SignerInfo {
+    /// Absent when the signer's account already has a public key on chain.
+    #[serde(default)]
+    pub public_key: Option<PublicKey>,
+    #[serde(default)]
+    pub mode_info: ModeInfo,
+    #[serde(default)]
+    pub sequence: String,
+}
+
+/// A public key `Any`; `key` is the base64 encoded key of single keys such as secp256k1.
+/// Multisig keys keep their threshold and members in `extra`.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct PublicKey {
+    #[serde(rename = "@type")]
+    pub type_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, Value>,
+}
+
+/// How a signer signed: a single sign mode, or one per member of a multisig.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+pub struct ModeInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub single: Option<SingleMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multi: Option<MultiMode>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct SingleMode {
+    /// A `SignMode` name such as `SIGN_MODE_DIRECT`.
+    pub mode: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct MultiMode {
+    #[serde(default)]
+    pub bitarray: Value,
+    #[serde(default)]
+    pub mode_infos: Vec<ModeInfo>,
+}
+
+/// The fee of a transaction and who pays it.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+pub struct Fee {
+    #[serde(default)]
+    pub amount: Vec<Amount>,
+    #[serde(default)]
+    pub gas_limit: String,
+    /// Set when the fee is paid by a signer other than the first one.
+    #[serde(default)]
+    pub payer: String,
+    /// Set when the fee is covered by a feegrant.
+    #[serde(default)]
+    pub granter: String,
+}
+
+impl PublicKey {
+    /// The key algorithm, such as `secp256k1` for `/cosmos.crypto.secp256k1.PubKey`.
+    pub fn key_type(&self) -> &str {
+        let mut segments = self.type_url.rsplit('.');
+        segments.next();
+        segments.next().unwrap_or(&self.type_url)
+    }
+}
+
+impl fmt::Display for ModeInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.single, &self.multi) {
+            (Some(single), _) => write!(f, "{}", single.mode),
+            (None, Some(multi)) => {
+                let modes: Vec<String> = multi.mode_infos.iter().map(ModeInfo::to_string).collect();
+                write!(f, "multi({})", modes.join(","))
+            }
+            (None, None) => write!(f, "-"),
+        }
+    }
+}
+
+impl fmt::Display for SignerInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.public_key {
+            Some(key) => write!(f, "{} {}", key.key_type(), key.key.as_deref().unwrap_or("-"))?,
+            None => write!(f, "- -")?,
+        }
+        write!(f, " seq {} {}", self.sequence, self.mode_info)
+    }
+}
+
+impl Fee {
+    pub fn gas_limit(&self) -> u64 {
+        self.gas_limit.parse().unwrap_or_default()
+    }
+}
+
+/// Renders coins the way the SDK does: `500uatom,10uosmo`.
+pub fn format_coins(coins: &[Amount]) -> String {
+    coins.iter().map(|coin| format!("{}{}", coin.amount, coin.denom)).collect::<Vec<_>>().join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_auth_info_from_lcd_json() {
+        let auth_info: AuthInfo = serde_json::from_value(json!({
+            "signer_infos": [
+                {
+                    "public_key": { "@type": "/cosmos.crypto.secp256k1.PubKey", "key": "A1b2" },
+                    "mode_info": { "single": { "mode": "SIGN_MODE_DIRECT" } },
+                    "sequence": "12"
+                },
+                {
+                    "public_key": { "@type": "/cosmos.crypto.multisig.LegacyAminoPubKey", "threshold": 2, "public_keys": [] },
+                    "mode_info": { "multi": { "bitarray": { "extra_bits_stored": 3, "elems": "wA==" }, "mode_infos": [
+                        { "single": { "mode": "SIGN_MODE_LEGACY_AMINO_JSON" } },
+                        { "single": { "mode": "SIGN_MODE_LEGACY_AMINO_JSON" } }
+                    ] } },
+                    "sequence": "3"
+                }
+            ],
+            "fee": {
+                "amount": [{ "denom": "uatom", "amount": "500" }],
+                "gas_limit": "200000",
+                "payer": "",
+                "granter": "cosmos1granter"
+            },
+            "tip": null
+        })).unwrap();
+
+        assert_eq!(auth_info.fee.gas_limit(), 200_000);
+        assert_eq!(format_coins(&auth_info.fee.amount), "500uatom");
+        assert_eq!(auth_info.signer_infos[0].to_string(), "secp256k1 A1b2 seq 12 SIGN_MODE_DIRECT");
+        assert_eq!(
+            auth_info.signer_infos[1].to_string(),
+            "multisig - seq 3 multi(SIGN_MODE_LEGACY_AMINO_JSON,SIGN_MODE_LEGACY_AMINO_JSON)"
+        );
+        assert_eq!(auth_info.signer_infos[1].public_key.as_ref().unwrap().extra["threshold"], 2);
+    }
+}
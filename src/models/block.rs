@@ -0,0 +1,70 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+/// A bound of a time range as given on the command line: an RFC3339 timestamp, or a date standing
+/// for the whole day in UTC.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeBound {
+    At(DateTime<Utc>),
+    Day(NaiveDate),
+}
+
+impl TimeBound {
+    /// The first instant the bound covers: the timestamp, or midnight at the start of the day.
+    pub fn start(&self) -> DateTime<Utc> {
+        match self {
+            TimeBound::At(time) => *time,
+            TimeBound::Day(date) => Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).expect("midnight is a valid time")),
+        }
+    }
+
+    /// The last instant the bound covers: the timestamp, or the last nanosecond of the day.
+    pub fn end(&self) -> DateTime<Utc> {
+        match self {
+            TimeBound::At(time) => *time,
+            TimeBound::Day(_) => self.start() + Duration::days(1) - Duration::nanoseconds(1),
+        }
+    }
+}
+
+impl FromStr for TimeBound {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+            return Ok(TimeBound::At(time.with_timezone(&Utc)));
+        }
+        NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .map(TimeBound::Day)
+            .map_err(|_| format!("'{}' is neither an RFC3339 time such as 2024-01-31T12:00:00Z nor a date such as 2024-01-31", s))
+    }
+}
+
+impl fmt::Display for TimeBound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeBound::At(time) => write!(f, "{}", time.to_rfc3339()),
+            TimeBound::Day(date) => write!(f, "{}", date),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_bound() {
+        let day: TimeBound = "2024-01-31".parse().unwrap();
+        assert_eq!(day.start().to_rfc3339(), "2024-01-31T00:00:00+00:00");
+        assert_eq!(day.end().to_rfc3339(), "2024-01-31T23:59:59.999999999+00:00");
+
+        let at: TimeBound = "2024-01-31T12:30:00+02:00".parse().unwrap();
+        assert_eq!(at.start(), at.end());
+        assert_eq!(at.start().to_rfc3339(), "2024-01-31T10:30:00+00:00");
+
+        assert!("31/01/2024".parse::<TimeBound>().is_err());
+    }
+}
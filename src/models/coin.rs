@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::ibc::DenomTrace;
+use crate::models::message::Amount;
+
+/// A coin amount in base units, such as `uatom`, with checked arithmetic.
+///
+/// `u128` holds any realistic supply, including 18 decimal denoms such as `aevmos`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coin {
+    pub denom: String,
+    pub amount: u128,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CoinError {
+    /// The amount is not a non-negative integer.
+    InvalidAmount(String),
+    /// A coin string such as `500uatom` that could not be split into amount and denom.
+    InvalidCoin(String),
+    Overflow(String),
+    DenomMismatch(String, String),
+}
+
+impl fmt::Display for CoinError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CoinError::InvalidAmount(amount) => write!(f, "'{}' is not a valid coin amount", amount),
+            CoinError::InvalidCoin(coin) => write!(f, "'{}' is not a valid coin", coin),
+            CoinError::Overflow(denom) => write!(f, "{} amount overflows", denom),
+            CoinError::DenomMismatch(a, b) => write!(f, "cannot combine {} with {}", a, b),
+        }
+    }
+}
+
+impl Error for CoinError {}
+
+impl Coin {
+    pub fn new(denom: impl Into<String>, amount: u128) -> Self {
+        Coin { denom: denom.into(), amount }
+    }
+
+    pub fn checked_add(&self, other: &Coin) -> Result<Coin, CoinError> {
+        self.check_denom(other)?;
+        let amount = self.amount.checked_add(other.amount).ok_or_else(|| CoinError::Overflow(self.denom.clone()))?;
+        Ok(Coin::new(self.denom.clone(), amount))
+    }
+
+    fn check_denom(&self, other: &Coin) -> Result<(), CoinError> {
+        if self.denom != other.denom {
+            return Err(CoinError::DenomMismatch(self.denom.clone(), other.denom.clone()));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Coin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.amount, self.denom)
+    }
+}
+
+/// Parses the SDK coin notation used in events and logs, such as `500uatom` or `1ibc/27394F...`.
+impl FromStr for Coin {
+    type Err = CoinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (amount, denom) = s.split_at(split);
+        if amount.is_empty() || denom.is_empty() {
+            return Err(CoinError::InvalidCoin(s.to_string()));
+        }
+        Ok(Coin::new(denom, parse_amount(amount)?))
+    }
+}
+
+impl TryFrom<&Amount> for Coin {
+    type Error = CoinError;
+
+    fn try_from(amount: &Amount) -> Result<Self, Self::Error> {
+        Ok(Coin::new(amount.denom.clone(), parse_amount(&amount.amount)?))
+    }
+}
+
+fn parse_amount(amount: &str) -> Result<u128, CoinError> {
+    amount.parse().map_err(|_| CoinError::InvalidAmount(amount.to_string()))
+}
+
+/// Collects every `{"denom": ..., "amount": ...}` coin found in a JSON value, such as a serialized
+/// message, in the order they appear. Amounts that are not plain integers are skipped.
+pub fn collect_coins(value: &Value, coins: &mut Vec<Coin>) {
+    match value {
+        Value::Object(map) => match (map.get("denom"), map.get("amount")) {
+            (Some(Value::String(denom)), Some(Value::String(amount))) => {
+                if let Ok(amount) = parse_amount(amount) {
+                    coins.push(Coin::new(denom.clone(), amount));
+                }
+            }
+            _ => map.values().for_each(|value| collect_coins(value, coins)),
+        },
+        Value::Array(values) => values.iter().for_each(|value| collect_coins(value, coins)),
+        _ => {}
+    }
+}
+
+/// How a base denom is shown to people, from the bank module's denom metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DenomMetadata {
+    pub base: String,
+    /// The display name, such as `ATOM`: the symbol when set, else the display denom.
+    pub display: String,
+    /// Decimals between the base and the display denom.
+    pub exponent: u32,
+}
+
+/// One entry of `/cosmos/bank/v1beta1/denoms_metadata`.
+#[derive(Debug, Deserialize)]
+pub struct BankDenomMetadata {
+    #[serde(default)]
+    pub denom_units: Vec<DenomUnit>,
+    pub base: String,
+    #[serde(default)]
+    pub display: String,
+    #[serde(default)]
+    pub symbol: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DenomUnit {
+    pub denom: String,
+    #[serde(default)]
+    pub exponent: u32,
+}
+
+impl From<BankDenomMetadata> for DenomMetadata {
+    fn from(metadata: BankDenomMetadata) -> Self {
+        let exponent = metadata.denom_units.iter()
+            .find(|unit| unit.denom == metadata.display)
+            .map(|unit| unit.exponent)
+            .unwrap_or_default();
+        let display = match (metadata.symbol.is_empty(), metadata.display.is_empty()) {
+            (false, _) => metadata.symbol,
+            (true, false) => metadata.display,
+            (true, true) => metadata.base.clone(),
+        };
+        DenomMetadata { base: metadata.base, display, exponent }
+    }
+}
+
+/// A coin converted to its display unit, such as `1.5` `ATOM` for `1500000uatom`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayCoin {
+    pub amount: String,
+    pub denom: String,
+}
+
+impl fmt::Display for DisplayCoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.denom)
+    }
+}
+
+/// Converts base denoms to display units using the denom metadata of the chain.
+///
+/// Denoms without metadata are shown as they are, in base units, except IBC vouchers with a
+/// known trace, which are shown by their path such as `transfer/channel-0/uosmo`.
+#[derive(Debug, Default, Clone)]
+pub struct DenomResolver {
+    metadata: HashMap<String, DenomMetadata>,
+    /// Traces of `ibc/{hash}` denoms, keyed by the full `ibc/{hash}` denom.
+    traces: HashMap<String, DenomTrace>,
+}
+
+impl DenomResolver {
+    pub fn new(metadata: impl IntoIterator<Item = DenomMetadata>) -> Self {
+        DenomResolver {
+            metadata: metadata.into_iter().map(|metadata| (metadata.base.clone(), metadata)).collect(),
+            traces: HashMap::new(),
+        }
+    }
+
+    pub fn with_traces(mut self, traces: HashMap<String, DenomTrace>) -> Self {
+        self.traces.extend(traces);
+        self
+    }
+
+    pub fn trace(&self, denom: &str) -> Option<&DenomTrace> {
+        self.traces.get(denom)
+    }
+
+    /// Lists the resolved IBC denoms among `denoms` as `ibc/{hash}=transfer/channel-0/uosmo`.
+    pub fn describe_traces<'a>(&self, denoms: impl IntoIterator<Item = &'a String>) -> String {
+        denoms.into_iter()
+            .filter_map(|denom| self.trace(denom).map(|trace| format!("{}={}", denom, trace.full_path())))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Lists coins in display units, such as `1.5 ATOM, 3 transfer/channel-0/uosmo`.
+    pub fn describe_amounts<'a>(&self, coins: impl IntoIterator<Item = &'a Coin>) -> String {
+        coins.into_iter().map(|coin| self.to_display(coin).to_string()).collect::<Vec<_>>().join(", ")
+    }
+
+    pub fn get(&self, denom: &str) -> Option<&DenomMetadata> {
+        self.metadata.get(denom)
+    }
+
+    pub fn to_display(&self, coin: &Coin) -> DisplayCoin {
+        match self.get(&coin.denom) {
+            Some(metadata) => DisplayCoin {
+                amount: shift_decimals(coin.amount, metadata.exponent),
+                denom: metadata.display.clone(),
+            },
+            None => DisplayCoin {
+                amount: coin.amount.to_string(),
+                denom: self.trace(&coin.denom).map(DenomTrace::full_path).unwrap_or_else(|| coin.denom.clone()),
+            },
+        }
+    }
+}
+
+/// Writes `amount / 10^exponent` exactly, without trailing zeros: `1500000` with 6 decimals is `1.5`.
+fn shift_decimals(amount: u128, exponent: u32) -> String {
+    let digits = format!("{:0>width$}", amount, width = exponent as usize + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - exponent as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer.to_string()
+    } else {
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_coin_arithmetic() {
+        let coin: Coin = "1500000uatom".parse().unwrap();
+        assert_eq!(coin, Coin::new("uatom", 1_500_000));
+        assert_eq!(coin.checked_add(&Coin::new("uatom", 5)).unwrap().amount, 1_500_005);
+        assert_eq!(coin.checked_add(&Coin::new("uatom", u128::MAX)), Err(CoinError::Overflow("uatom".to_string())));
+        assert!(matches!(coin.checked_add(&Coin::new("uosmo", 1)), Err(CoinError::DenomMismatch(_, _))));
+
+        let amount = Amount { denom: "aevmos".to_string(), amount: "123000000000000000000000".to_string() };
+        assert_eq!(Coin::try_from(&amount).unwrap().amount, 123_000_000_000_000_000_000_000);
+        assert_eq!("7ibc/27394FB092D2ECCD".parse::<Coin>().unwrap().denom, "ibc/27394FB092D2ECCD");
+        assert!("uatom".parse::<Coin>().is_err());
+        assert!(Coin::try_from(&Amount { denom: "uatom".to_string(), amount: "-1".to_string() }).is_err());
+    }
+
+    #[test]
+    fn test_display_units() {
+        let metadata: BankDenomMetadata = serde_json::from_value(json!({
+            "description": "The native staking token of the Cosmos Hub.",
+            "denom_units": [
+                { "denom": "uatom", "exponent": 0, "aliases": ["microatom"] },
+                { "denom": "atom", "exponent": 6, "aliases": [] }
+            ],
+            "base": "uatom",
+            "display": "atom",
+            "name": "Cosmos Hub Atom",
+            "symbol": "ATOM"
+        })).unwrap();
+        let resolver = DenomResolver::new(vec![DenomMetadata::from(metadata)]);
+
+        assert_eq!(resolver.to_display(&Coin::new("uatom", 1_500_000)).to_string(), "1.5 ATOM");
+        assert_eq!(resolver.to_display(&Coin::new("uatom", 7)).to_string(), "0.000007 ATOM");
+        assert_eq!(resolver.to_display(&Coin::new("uatom", 2_000_000)).to_string(), "2 ATOM");
+        assert_eq!(resolver.to_display(&Coin::new("uosmo", 10)).to_string(), "10 uosmo");
+        let ibc_osmo = "ibc/ED07A3391A112B175915CD8FAF43A2DA8E4790EDE12566649D0C2F97716B8518".to_string();
+        let trace = DenomTrace { path: "transfer/channel-0".to_string(), base_denom: "uosmo".to_string() };
+        let resolver = resolver.with_traces(HashMap::from([(ibc_osmo.clone(), trace)]));
+        assert_eq!(resolver.to_display(&Coin::new(ibc_osmo.clone(), 3)).to_string(), "3 transfer/channel-0/uosmo");
+
+        let mut coins = Vec::new();
+        collect_coins(&json!({ "amount": [{ "denom": "uatom", "amount": "2500000" }], "token": { "denom": ibc_osmo, "amount": "3" } }), &mut coins);
+        assert_eq!(resolver.describe_amounts(&coins), "2.5 ATOM, 3 transfer/channel-0/uosmo");
+        assert_eq!(resolver.describe_traces(&[ibc_osmo, "ibc/UNKNOWN".to_string()]), "ibc/ED07A3391A112B175915CD8FAF43A2DA8E4790EDE12566649D0C2F97716B8518=transfer/channel-0/uosmo");
+        assert_eq!(shift_decimals(u128::MAX, 40), "0.0340282366920938463463374607431768211455");
+    }
+}
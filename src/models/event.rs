@@ -0,0 +1,188 @@
+use std::fmt;
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// An ABCI event emitted while executing a transaction, as returned by the LCD and the CometBFT RPC.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct Event {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub attributes: Vec<EventAttribute>,
+}
+
+/// A single key/value pair of an `Event`.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct EventAttribute {
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub key: String,
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub value: String,
+    #[serde(default)]
+    pub index: bool,
+}
+
+/// Selects events by type, optionally by attribute key and value: `withdraw_rewards`,
+/// `transfer.amount` or `transfer.recipient=cosmos1...`, as in CometBFT event queries.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EventFilter {
+    pub kind: String,
+    pub key: Option<String>,
+    pub value: Option<String>,
+}
+
+impl FromStr for EventFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (selector, value) = match s.split_once('=') {
+            Some((selector, value)) => (selector, Some(value.trim_matches('\'').to_string())),
+            None => (s, None),
+        };
+        // Event types may contain dots (typed events), so the key is whatever follows the last one.
+        let (kind, key) = match selector.rsplit_once('.') {
+            Some((kind, key)) if value.is_some() || !kind.contains('.') => (kind, Some(key.to_string())),
+            _ => (selector, None),
+        };
+        if kind.is_empty() || key.as_deref() == Some("") || (value.is_some() && key.is_none()) {
+            return Err(format!("'{}' is not a valid event filter, expected type, type.key or type.key=value", s));
+        }
+        Ok(EventFilter { kind: kind.to_string(), key, value })
+    }
+}
+
+impl EventFilter {
+    /// The filter as a condition of a node-side tx search, `type.key='value'`; only filters with a
+    /// key and a value can be sent to the node.
+    pub fn to_condition(&self) -> Option<String> {
+        Some(format!("{}.{}='{}'", self.kind, self.key.as_ref()?, self.value.as_ref()?))
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let attributes: Vec<String> = self.attributes.iter()
+            .map(|attribute| format!("{}={}", attribute.key, attribute.value))
+            .collect();
+        write!(f, "{}({})", self.kind, attributes.join(","))
+    }
+}
+
+impl Event {
+    /// Whether the event is of the filter's type and has a matching attribute, if one is given.
+    pub fn matches(&self, filter: &EventFilter) -> bool {
+        if self.kind != filter.kind {
+            return false;
+        }
+        match &filter.key {
+            Some(key) => self.attributes.iter().any(|attribute| {
+                attribute.key == *key && filter.value.as_ref().is_none_or(|value| attribute.value == *value)
+            }),
+            None => true,
+        }
+    }
+
+    /// The value of the first attribute with the given key.
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.iter().find(|attribute| attribute.key == key).map(|attribute| attribute.value.as_str())
+    }
+
+    /// Decodes base64 attribute keys and values, as emitted by Tendermint 0.34 and older SDKs.
+    ///
+    /// Plain-text events are left untouched. An event is treated as base64 encoded only when every
+    /// key decodes to an identifier-like string and every value decodes to UTF-8.
+    pub fn decode_base64_attributes(&mut self) {
+        let decoded: Option<Vec<(String, String)>> = self.attributes.iter().map(|attribute| {
+            let key = decode_utf8(&attribute.key)?;
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-') {
+                return None;
+            }
+            Some((key, decode_utf8(&attribute.value)?))
+        }).collect();
+
+        if let Some(decoded) = decoded {
+            for (attribute, (key, value)) in self.attributes.iter_mut().zip(decoded) {
+                attribute.key = key;
+                attribute.value = value;
+            }
+        }
+    }
+}
+
+fn decode_utf8(encoded: &str) -> Option<String> {
+    let bytes = STANDARD.decode(encoded).ok()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// Older nodes send `null` for empty attribute values.
+fn null_as_empty<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(attributes: &[(&str, &str)]) -> Event {
+        Event {
+            kind: "transfer".to_string(),
+            attributes: attributes.iter().map(|(key, value)| EventAttribute {
+                key: key.to_string(),
+                value: value.to_string(),
+                index: true,
+            }).collect(),
+        }
+    }
+
+    #[test]
+    fn test_decode_base64_attributes() {
+        // "recipient" => "cosmos1abc", "amount" => "100uatom"
+        let mut encoded = event(&[("cmVjaXBpZW50", "Y29zbW9zMWFiYw=="), ("YW1vdW50", "MTAwdWF0b20=")]);
+        encoded.decode_base64_attributes();
+        assert_eq!(encoded, event(&[("recipient", "cosmos1abc"), ("amount", "100uatom")]));
+    }
+
+    #[test]
+    fn test_plain_attributes_are_kept() {
+        let plain = event(&[("receiver", "cosmos1abc"), ("amount", "100uatom")]);
+        let mut decoded = plain.clone();
+        decoded.decode_base64_attributes();
+        assert_eq!(decoded, plain);
+    }
+
+    #[test]
+    fn test_attribute_lookup() {
+        let event = event(&[("receiver", "cosmos1abc"), ("amount", "100uatom")]);
+        assert_eq!(event.attribute("amount"), Some("100uatom"));
+        assert_eq!(event.attribute("sender"), None);
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let event = Event { kind: "withdraw_rewards".to_string(), ..event(&[("amount", "15uatom"), ("validator", "cosmosvaloper1abc")]) };
+        assert!(event.matches(&"withdraw_rewards".parse().unwrap()));
+        assert!(event.matches(&"withdraw_rewards.amount".parse().unwrap()));
+        assert!(event.matches(&"withdraw_rewards.validator='cosmosvaloper1abc'".parse().unwrap()));
+        assert!(!event.matches(&"withdraw_rewards.validator=cosmosvaloper1xyz".parse().unwrap()));
+        assert!(!event.matches(&"transfer".parse().unwrap()));
+        assert_eq!(event.to_string(), "withdraw_rewards(amount=15uatom,validator=cosmosvaloper1abc)");
+
+        let typed: EventFilter = "cosmos.authz.v1beta1.EventGrant".parse().unwrap();
+        assert_eq!(typed, EventFilter { kind: "cosmos.authz.v1beta1.EventGrant".to_string(), key: None, value: None });
+        assert!("=cosmos1".parse::<EventFilter>().is_err());
+        assert!("transfer=cosmos1".parse::<EventFilter>().is_err());
+
+        let condition: EventFilter = "wasm._contract_address=juno1abc".parse().unwrap();
+        assert_eq!(condition.to_condition().as_deref(), Some("wasm._contract_address='juno1abc'"));
+        assert_eq!(typed.to_condition(), None);
+    }
+
+    #[test]
+    fn test_null_attribute_value() {
+        let event: Event = serde_json::from_str(r#"{"type":"message","attributes":[{"key":"action","value":null}]}"#).unwrap();
+        assert_eq!(event.attributes[0].value, "");
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::models::transaction::{failure_reason, ComprehensiveTx};
+
+/// Failed transactions sharing an error, as listed by the failure report.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct FailureCount {
+    pub codespace: String,
+    pub code: i32,
+    /// The root error of the failure log, such as `insufficient funds`.
+    pub reason: String,
+    pub tx_count: u64,
+    /// One of the failed transactions, to look the full log up.
+    pub example_tx_hash: String,
+}
+
+impl fmt::Display for FailureCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} code {}: {} ({} txs, e.g. {})", self.codespace, self.code, self.reason, self.tx_count, self.example_tx_hash)
+    }
+}
+
+impl FailureCount {
+    /// Counts the failed transactions per codespace, code and reason, most frequent first.
+    pub fn from_txs(txs: &[ComprehensiveTx]) -> Vec<FailureCount> {
+        let mut counts: BTreeMap<(String, i32, String), (u64, String)> = BTreeMap::new();
+        for tx in txs.iter().filter(|tx| !tx.is_success()) {
+            let reason = failure_reason(&tx.failure_log).to_string();
+            let count = counts.entry((tx.codespace.clone(), tx.code, reason)).or_insert_with(|| (0, tx.tx_hash.clone()));
+            count.0 += 1;
+        }
+
+        let mut failures: Vec<FailureCount> = counts.into_iter()
+            .map(|((codespace, code, reason), (tx_count, example_tx_hash))| FailureCount { codespace, code, reason, tx_count, example_tx_hash })
+            .collect();
+        // Stable, so equally frequent failures stay ordered by codespace and code.
+        failures.sort_by_key(|failure| std::cmp::Reverse(failure.tx_count));
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::testing::comprehensive_tx;
+
+    fn mock_tx(hash: &str, code: i32, codespace: &str, failure_log: &str) -> ComprehensiveTx {
+        comprehensive_tx(json!({ "tx_hash": hash, "code": code, "codespace": codespace, "failure_log": failure_log }))
+    }
+
+    #[test]
+    fn test_failure_breakdown() {
+        let txs = vec![
+            mock_tx("A", 0, "", ""),
+            mock_tx("B", 32, "sdk", "account sequence mismatch, expected 5, got 4: incorrect account sequence"),
+            mock_tx("C", 5, "sdk", "failed to execute message; message index: 0: 5uatom is smaller than 10uatom: insufficient funds"),
+            mock_tx("D", 5, "sdk", "failed to execute message; message index: 0: 1uatom is smaller than 3uatom: insufficient funds"),
+        ];
+
+        let failures = FailureCount::from_txs(&txs);
+        assert_eq!(failures, vec![
+            FailureCount { codespace: "sdk".to_string(), code: 5, reason: "insufficient funds".to_string(), tx_count: 2, example_tx_hash: "C".to_string() },
+            FailureCount { codespace: "sdk".to_string(), code: 32, reason: "incorrect account sequence".to_string(), tx_count: 1, example_tx_hash: "B".to_string() },
+        ]);
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::models::coin::{Coin, CoinError, DenomResolver};
+use crate::models::transaction::ComprehensiveTx;
+
+/// Shown for transactions whose fee payer the node didn't report (SDKs before v0.46).
+const UNKNOWN_PAYER: &str = "(unknown)";
+
+/// How the fee report groups transactions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeeGrouping {
+    Payer,
+    Height,
+}
+
+impl FromStr for FeeGrouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "payer" => Ok(FeeGrouping::Payer),
+            "height" => Ok(FeeGrouping::Height),
+            _ => Err(format!("'{}' is not a valid grouping, expected payer or height", s)),
+        }
+    }
+}
+
+/// Fees paid in one denom by an account or in a block, as listed by the fee report.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct FeeSpend {
+    /// The fee payer or the height, depending on the grouping.
+    pub group: String,
+    pub denom: String,
+    /// Total in base units, such as `uatom`.
+    pub amount: u128,
+    /// Total in the display unit of the denom metadata, such as `ATOM`.
+    pub display_amount: String,
+    pub display_denom: String,
+    pub tx_count: u64,
+}
+
+impl fmt::Display for FeeSpend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} {} over {} txs", self.group, self.display_amount, self.display_denom, self.tx_count)
+    }
+}
+
+impl FeeSpend {
+    /// Sums the fees of the transactions per payer or per height, and per denom.
+    ///
+    /// Heights are ordered numerically. Fees that are not valid amounts, or totals that would
+    /// overflow, are reported as errors rather than skewing the sums.
+    pub fn from_txs(txs: &[ComprehensiveTx], grouping: FeeGrouping, resolver: &DenomResolver) -> Result<Vec<FeeSpend>, CoinError> {
+        let mut totals: BTreeMap<(u64, String, String), (Coin, u64)> = BTreeMap::new();
+        for tx in txs {
+            let group = match grouping {
+                FeeGrouping::Payer if tx.fee_payer.is_empty() => (0, UNKNOWN_PAYER.to_string()),
+                FeeGrouping::Payer => (0, tx.fee_payer.clone()),
+                FeeGrouping::Height => (tx.height, tx.height.to_string()),
+            };
+            for amount in &tx.fee {
+                let coin = Coin::try_from(amount)?;
+                let total = totals.entry((group.0, group.1.clone(), coin.denom.clone()))
+                    .or_insert_with(|| (Coin::new(coin.denom.clone(), 0), 0));
+                total.0 = total.0.checked_add(&coin)?;
+                total.1 += 1;
+            }
+        }
+
+        Ok(totals.into_iter()
+            .map(|((_, group, denom), (total, tx_count))| {
+                let display = resolver.to_display(&total);
+                FeeSpend { group, denom, amount: total.amount, display_amount: display.amount, display_denom: display.denom, tx_count }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::coin::DenomMetadata;
+    use crate::models::testing::comprehensive_tx;
+
+    fn mock_tx(height: u64, payer: &str, fee: serde_json::Value) -> ComprehensiveTx {
+        comprehensive_tx(json!({ "height": height, "tx_hash": format!("HASH{}", height), "fee": fee, "fee_payer": payer }))
+    }
+
+    #[test]
+    fn test_fee_spend_per_payer_and_height() {
+        let txs = vec![
+            mock_tx(10, "cosmos1a", json!([{ "denom": "uatom", "amount": "500" }])),
+            mock_tx(10, "cosmos1b", json!([{ "denom": "uatom", "amount": "250" }, { "denom": "uosmo", "amount": "1" }])),
+            mock_tx(9, "cosmos1a", json!([{ "denom": "uatom", "amount": "100" }])),
+            mock_tx(100, "", json!([{ "denom": "uatom", "amount": "7" }])),
+        ];
+
+        let resolver = DenomResolver::new(vec![DenomMetadata { base: "uatom".to_string(), display: "ATOM".to_string(), exponent: 6 }]);
+
+        let per_payer = FeeSpend::from_txs(&txs, FeeGrouping::Payer, &resolver).unwrap();
+        assert_eq!(per_payer[0], FeeSpend {
+            group: "(unknown)".to_string(),
+            denom: "uatom".to_string(),
+            amount: 7,
+            display_amount: "0.000007".to_string(),
+            display_denom: "ATOM".to_string(),
+            tx_count: 1,
+        });
+        assert_eq!(per_payer[1].amount, 600);
+        assert_eq!(per_payer[1].tx_count, 2);
+        assert_eq!(per_payer[3].to_string(), "cosmos1b: 1 uosmo over 1 txs");
+        assert_eq!(per_payer.len(), 4);
+
+        let per_height: Vec<(String, String, u128)> = FeeSpend::from_txs(&txs, FeeGrouping::Height, &resolver).unwrap().into_iter()
+            .map(|spend| (spend.group, spend.denom, spend.amount))
+            .collect();
+        assert_eq!(per_height, vec![
+            ("9".to_string(), "uatom".to_string(), 100),
+            ("10".to_string(), "uatom".to_string(), 750),
+            ("10".to_string(), "uosmo".to_string(), 1),
+            ("100".to_string(), "uatom".to_string(), 7),
+        ]);
+
+        let invalid = vec![mock_tx(1, "cosmos1a", json!([{ "denom": "uatom", "amount": "1.5" }]))];
+        assert!(FeeSpend::from_txs(&invalid, FeeGrouping::Payer, &resolver).is_err());
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::models::message::{Message, WeightedVoteOption};
+use crate::models::transaction::IndividualMsgTx;
+
+/// Weight of the single option of a plain (non-weighted) vote.
+const FULL_WEIGHT: &str = "1.000000000000000000";
+
+/// One option of a vote cast on a proposal, as listed by the gov report.
+///
+/// A weighted vote produces one row per option.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct GovVote {
+    pub proposal_id: u64,
+    pub voter: String,
+    pub option: String,
+    pub weight: String,
+    pub height: u64,
+    pub tx_hash: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl fmt::Display for GovVote {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Proposal: {}, Voter: {}, Option: {}, Weight: {}, Height: {}, Tx Hash: {}, Timestamp: {}",
+            self.proposal_id, self.voter, self.option, self.weight, self.height, self.tx_hash, self.timestamp
+        )
+    }
+}
+
+impl GovVote {
+    /// Collects the votes cast by the given messages, ordered by proposal and then by height.
+    ///
+    /// Every vote is listed, including votes later replaced by the same voter.
+    pub fn from_msg_txs(txs: &[IndividualMsgTx]) -> Vec<GovVote> {
+        let mut votes: Vec<GovVote> = txs.iter().flat_map(|tx| {
+            vote_options(&tx.message).into_iter().map(move |(proposal_id, voter, option)| GovVote {
+                proposal_id,
+                voter,
+                option: option.option,
+                weight: option.weight,
+                height: tx.height,
+                tx_hash: tx.tx_hash.clone(),
+                timestamp: tx.timestamp,
+            })
+        }).collect();
+
+        votes.sort_by_key(|vote| (vote.proposal_id, vote.height));
+        votes
+    }
+}
+
+/// The proposal, voter and weighted options of a vote message; empty for any other message.
+fn vote_options(message: &Message) -> Vec<(u64, String, WeightedVoteOption)> {
+    let (proposal_id, voter, options) = match message {
+        Message::MsgVote { proposal_id, voter, option }
+        | Message::MsgVoteV1 { proposal_id, voter, option, .. } => {
+            (proposal_id, voter, vec![WeightedVoteOption { option: option.clone(), weight: FULL_WEIGHT.to_string() }])
+        }
+        Message::MsgVoteWeighted { proposal_id, voter, options }
+        | Message::MsgVoteWeightedV1 { proposal_id, voter, options, .. } => (proposal_id, voter, options.clone()),
+        _ => return Vec::new(),
+    };
+
+    // Proposal ids are uint64 on chain; anything else is not a vote we can report.
+    let Ok(proposal_id) = proposal_id.parse::<u64>() else {
+        return Vec::new();
+    };
+    options.into_iter().map(|option| (proposal_id, voter.clone(), option)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+
+    fn mock_msg_tx(height: u64, message: serde_json::Value) -> IndividualMsgTx {
+        IndividualMsgTx {
+            message: serde_json::from_value(message).unwrap(),
+            msg_path: "0".to_string(),
+            grantee: None,
+            msg_name: String::new(),
+            sender: None,
+            receiver: None,
+            details: String::new(),
+            events: vec![],
+            denom_traces: "".to_string(),
+            display_amounts: "".to_string(),
+            code: 0,
+            codespace: "".to_string(),
+            failure_log: "".to_string(),
+            height,
+            tx_hash: format!("HASH{}", height),
+            timestamp: Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap(),
+            data: String::new(),
+            signatures: vec![],
+            memo: String::new(),
+            timeout_height: "0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_votes_per_proposal_with_weights() {
+        let txs = vec![
+            mock_msg_tx(12, json!({
+                "@type": "/cosmos.gov.v1.MsgVoteWeighted",
+                "proposal_id": "9",
+                "voter": "cosmos1b",
+                "options": [{ "option": "VOTE_OPTION_YES", "weight": "0.6" }, { "option": "VOTE_OPTION_NO", "weight": "0.4" }]
+            })),
+            mock_msg_tx(10, json!({ "@type": "/cosmos.gov.v1beta1.MsgVote", "proposal_id": "9", "voter": "cosmos1a", "option": "VOTE_OPTION_ABSTAIN" })),
+            mock_msg_tx(11, json!({ "@type": "/cosmos.gov.v1beta1.MsgVote", "proposal_id": "3", "voter": "cosmos1a", "option": "VOTE_OPTION_YES" })),
+            mock_msg_tx(11, json!({ "@type": "/cosmos.bank.v1beta1.MsgSend", "from_address": "a", "to_address": "b", "amount": [] })),
+        ];
+
+        let votes = GovVote::from_msg_txs(&txs);
+
+        let rows: Vec<(u64, &str, &str, &str)> = votes.iter()
+            .map(|vote| (vote.proposal_id, vote.voter.as_str(), vote.option.as_str(), vote.weight.as_str()))
+            .collect();
+        assert_eq!(rows, vec![
+            (3, "cosmos1a", "VOTE_OPTION_YES", FULL_WEIGHT),
+            (9, "cosmos1a", "VOTE_OPTION_ABSTAIN", FULL_WEIGHT),
+            (9, "cosmos1b", "VOTE_OPTION_YES", "0.6"),
+            (9, "cosmos1b", "VOTE_OPTION_NO", "0.4"),
+        ]);
+    }
+}
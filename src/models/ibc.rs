@@ -0,0 +1,365 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::models::message::TimeoutHeight;
+
+// Types shared by the IBC core messages (`ibc.core.channel.v1`, `ibc.core.connection.v1`)
+// and the ICS-20 transfer application.
+
+/// An IBC packet as carried by `MsgRecvPacket`, `MsgAcknowledgement` and `MsgTimeout`.
+///
+/// `data` is the base64 encoded packet payload. When it is an ICS-20 transfer, the decoded
+/// payload is also kept in `transfer`.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(from = "PacketJson")]
+pub struct Packet {
+    pub sequence: String,
+    pub source_port: String,
+    pub source_channel: String,
+    pub destination_port: String,
+    pub destination_channel: String,
+    pub data: String,
+    pub timeout_height: TimeoutHeight,
+    pub timeout_timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer: Option<FungibleTokenPacketData>,
+}
+
+/// The packet fields as the LCD returns them, before `data` is decoded.
+#[derive(Deserialize)]
+struct PacketJson {
+    sequence: String,
+    source_port: String,
+    source_channel: String,
+    destination_port: String,
+    destination_channel: String,
+    #[serde(default)]
+    data: String,
+    timeout_height: TimeoutHeight,
+    timeout_timestamp: String,
+}
+
+impl From<PacketJson> for Packet {
+    fn from(packet: PacketJson) -> Self {
+        Packet {
+            transfer: FungibleTokenPacketData::decode(&packet.data),
+            sequence: packet.sequence,
+            source_port: packet.source_port,
+            source_channel: packet.source_channel,
+            destination_port: packet.destination_port,
+            destination_channel: packet.destination_channel,
+            data: packet.data,
+            timeout_height: packet.timeout_height,
+            timeout_timestamp: packet.timeout_timestamp,
+        }
+    }
+}
+
+/// ICS-20 `FungibleTokenPacketData`: the JSON payload of a transfer packet.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct FungibleTokenPacketData {
+    pub denom: String,
+    pub amount: String,
+    pub sender: String,
+    pub receiver: String,
+    #[serde(default)]
+    pub memo: String,
+}
+
+impl FungibleTokenPacketData {
+    /// Decodes base64 packet data, returning `None` when it is not an ICS-20 payload.
+    pub fn decode(data: &str) -> Option<Self> {
+        let bytes = STANDARD.decode(data).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Parses the plain JSON payload, as found in the `packet_data` attribute of packet events.
+    pub fn from_json(data: &str) -> Option<Self> {
+        serde_json::from_str(data).ok()
+    }
+}
+
+/// ICS-20 `DenomTrace`: where an `ibc/{hash}` voucher comes from.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct DenomTrace {
+    /// The ports and channels the token travelled through, such as `transfer/channel-0`.
+    #[serde(default)]
+    pub path: String,
+    pub base_denom: String,
+}
+
+impl DenomTrace {
+    /// The denom as it reads on the chain, such as `transfer/channel-0/uosmo`.
+    pub fn full_path(&self) -> String {
+        if self.path.is_empty() {
+            self.base_denom.clone()
+        } else {
+            format!("{}/{}", self.path, self.base_denom)
+        }
+    }
+
+    /// The `ibc/{hash}` denom of the voucher: the upper-case hex SHA-256 of the full path.
+    pub fn ibc_denom(&self) -> String {
+        let hash: String = Sha256::digest(self.full_path().as_bytes()).iter().map(|byte| format!("{:02X}", byte)).collect();
+        format!("{}{}", IBC_DENOM_PREFIX, hash)
+    }
+}
+
+/// Prefix of IBC voucher denoms.
+pub const IBC_DENOM_PREFIX: &str = "ibc/";
+
+/// Collects every `ibc/{hash}` denom found in a JSON value, such as a serialized message.
+pub fn collect_ibc_denoms(value: &Value, denoms: &mut BTreeSet<String>) {
+    match value {
+        Value::String(s) if s.starts_with(IBC_DENOM_PREFIX) => {
+            denoms.insert(s.clone());
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_ibc_denoms(value, denoms)),
+        Value::Object(map) => map.values().for_each(|value| collect_ibc_denoms(value, denoms)),
+        _ => {}
+    }
+}
+
+/// `ibc.core.channel.v1.Channel`, as proposed in a channel handshake.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ChannelEnd {
+    /// A `State` name such as `STATE_INIT`.
+    pub state: String,
+    /// An `Order` name such as `ORDER_UNORDERED`.
+    pub ordering: String,
+    pub counterparty: ChannelCounterparty,
+    pub connection_hops: Vec<String>,
+    pub version: String,
+}
+
+/// The other end of a channel; `channel_id` is empty until the counterparty has opened it.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ChannelCounterparty {
+    pub port_id: String,
+    pub channel_id: String,
+}
+
+/// The other end of a connection; `connection_id` is empty until the counterparty has opened it.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ConnectionCounterparty {
+    pub client_id: String,
+    pub connection_id: String,
+}
+
+/// A transaction that moved a packet along, on either chain.
+#[derive(Debug, PartialEq, Serialize, Clone)]
+pub struct PacketStep {
+    pub tx_hash: String,
+    pub height: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl fmt::Display for PacketStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at height {} ({})", self.tx_hash, self.height, self.timestamp)
+    }
+}
+
+/// Where a transfer is in its lifecycle.
+#[derive(Debug, PartialEq, Serialize, Clone)]
+pub enum TransferStatus {
+    /// Sent, but not received on the destination chain yet.
+    Pending,
+    /// Received on the destination chain; the acknowledgement has not been relayed back yet.
+    Received,
+    /// Received and acknowledged successfully on the source chain.
+    Completed,
+    /// The destination chain wrote an error acknowledgement; the tokens are refunded on the source chain.
+    Failed(String),
+    /// The packet timed out and the tokens were refunded on the source chain.
+    TimedOut,
+}
+
+impl fmt::Display for TransferStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransferStatus::Pending => write!(f, "pending"),
+            TransferStatus::Received => write!(f, "received, acknowledgement pending"),
+            TransferStatus::Completed => write!(f, "completed"),
+            TransferStatus::Failed(error) => write!(f, "failed: {}", error),
+            TransferStatus::TimedOut => write!(f, "timed out"),
+        }
+    }
+}
+
+/// The path of one packet sent by a transfer: sent on the source chain, received on the
+/// destination chain, then acknowledged or timed out back on the source chain.
+#[derive(Debug, PartialEq, Serialize, Clone)]
+pub struct TransferLifecycle {
+    pub source_chain: String,
+    pub destination_chain: String,
+    pub sequence: String,
+    pub source_port: String,
+    pub source_channel: String,
+    pub destination_port: String,
+    pub destination_channel: String,
+    pub transfer: Option<FungibleTokenPacketData>,
+    pub sent: PacketStep,
+    pub received: Option<PacketStep>,
+    pub acknowledged: Option<PacketStep>,
+    pub timed_out: Option<PacketStep>,
+    /// The error acknowledgement written by the destination chain, if any.
+    pub ack_error: Option<String>,
+}
+
+impl TransferLifecycle {
+    pub fn status(&self) -> TransferStatus {
+        if self.timed_out.is_some() {
+            TransferStatus::TimedOut
+        } else if let Some(error) = &self.ack_error {
+            TransferStatus::Failed(error.clone())
+        } else if self.acknowledged.is_some() {
+            TransferStatus::Completed
+        } else if self.received.is_some() {
+            TransferStatus::Received
+        } else {
+            TransferStatus::Pending
+        }
+    }
+
+    /// Time from sending the packet to its receipt on the destination chain.
+    pub fn receive_latency(&self) -> Option<Duration> {
+        self.received.as_ref().map(|step| step.timestamp - self.sent.timestamp)
+    }
+
+    /// Time from sending the packet to its acknowledgement or timeout on the source chain.
+    pub fn completion_latency(&self) -> Option<Duration> {
+        self.acknowledged.as_ref().or(self.timed_out.as_ref()).map(|step| step.timestamp - self.sent.timestamp)
+    }
+}
+
+impl fmt::Display for TransferLifecycle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Status: {}", self.status())?;
+        writeln!(
+            f,
+            "Packet: {} {}/{} ({}) -> {}/{} ({})",
+            self.sequence, self.source_port, self.source_channel, self.source_chain,
+            self.destination_port, self.destination_channel, self.destination_chain
+        )?;
+        if let Some(transfer) = &self.transfer {
+            writeln!(f, "Transfer: {} {} from {} to {}", transfer.amount, transfer.denom, transfer.sender, transfer.receiver)?;
+        }
+        writeln!(f, "Sent: {}", self.sent)?;
+        let steps = [("Received", &self.received), ("Acknowledged", &self.acknowledged), ("Timed out", &self.timed_out)];
+        for (label, step) in steps {
+            if let Some(step) = step {
+                writeln!(f, "{}: {}", label, step)?;
+            }
+        }
+        if let Some(latency) = self.receive_latency() {
+            writeln!(f, "Receive latency: {}s", latency.num_seconds())?;
+        }
+        match self.completion_latency() {
+            Some(latency) => write!(f, "Completion latency: {}s", latency.num_seconds()),
+            None => write!(f, "Completion latency: -"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_packet_decodes_ics20_data() {
+        let data = STANDARD.encode(r#"{"amount":"100","denom":"uatom","receiver":"osmo1to","sender":"cosmos1from"}"#);
+        let packet: Packet = serde_json::from_value(json!({
+            "sequence": "42",
+            "source_port": "transfer",
+            "source_channel": "channel-141",
+            "destination_port": "transfer",
+            "destination_channel": "channel-0",
+            "data": data,
+            "timeout_height": { "revision_number": "1", "revision_height": "100" },
+            "timeout_timestamp": "0"
+        })).unwrap();
+
+        assert_eq!(packet.transfer, Some(FungibleTokenPacketData {
+            denom: "uatom".to_string(),
+            amount: "100".to_string(),
+            sender: "cosmos1from".to_string(),
+            receiver: "osmo1to".to_string(),
+            memo: String::new(),
+        }));
+        assert_eq!(serde_json::to_value(&packet).unwrap()["transfer"]["amount"], "100");
+    }
+
+    fn step(seconds: i64) -> PacketStep {
+        PacketStep {
+            tx_hash: format!("TX{}", seconds),
+            height: seconds as u64,
+            timestamp: "2023-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap() + Duration::seconds(seconds),
+        }
+    }
+
+    fn lifecycle() -> TransferLifecycle {
+        TransferLifecycle {
+            source_chain: "cosmoshub-4".to_string(),
+            destination_chain: "osmosis-1".to_string(),
+            sequence: "1".to_string(),
+            source_port: "transfer".to_string(),
+            source_channel: "channel-141".to_string(),
+            destination_port: "transfer".to_string(),
+            destination_channel: "channel-0".to_string(),
+            transfer: None,
+            sent: step(0),
+            received: None,
+            acknowledged: None,
+            timed_out: None,
+            ack_error: None,
+        }
+    }
+
+    #[test]
+    fn test_transfer_status_and_latency() {
+        let mut transfer = lifecycle();
+        assert_eq!(transfer.status(), TransferStatus::Pending);
+        assert_eq!(transfer.completion_latency(), None);
+
+        transfer.received = Some(step(12));
+        assert_eq!(transfer.status(), TransferStatus::Received);
+        assert_eq!(transfer.receive_latency(), Some(Duration::seconds(12)));
+
+        transfer.acknowledged = Some(step(30));
+        assert_eq!(transfer.status(), TransferStatus::Completed);
+        assert_eq!(transfer.completion_latency(), Some(Duration::seconds(30)));
+
+        transfer.ack_error = Some("insufficient funds".to_string());
+        assert_eq!(transfer.status(), TransferStatus::Failed("insufficient funds".to_string()));
+    }
+
+    #[test]
+    fn test_non_transfer_packet_data_is_kept_raw() {
+        assert_eq!(FungibleTokenPacketData::decode(&STANDARD.encode("not json")), None);
+        assert_eq!(FungibleTokenPacketData::decode("%%%"), None);
+    }
+
+    #[test]
+    fn test_denom_trace() {
+        let trace = DenomTrace { path: "transfer/channel-0".to_string(), base_denom: "uatom".to_string() };
+        assert_eq!(trace.full_path(), "transfer/channel-0/uatom");
+        assert_eq!(trace.ibc_denom(), "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2");
+
+        let mut denoms = BTreeSet::new();
+        collect_ibc_denoms(&json!({ "amount": [{ "denom": "ibc/ABC", "amount": "1" }, { "denom": "uosmo", "amount": "2" }], "token": { "denom": "ibc/DEF" } }), &mut denoms);
+        assert_eq!(denoms.into_iter().collect::<Vec<_>>(), vec!["ibc/ABC", "ibc/DEF"]);
+    }
+}
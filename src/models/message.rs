@@ -1,6 +1,13 @@
+use std::fmt;
 use std::str::FromStr;
-use clap::Parser;
-use serde::{Deserialize, Serialize};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::models::ibc::{ChannelEnd, ConnectionCounterparty, Packet};
+use crate::models::registry::MessageRegistry;
 
 
 /// Represents various message types from the CosmosSDK.
@@ -23,6 +30,217 @@ pub enum Message {
         amount: Amount,
     },
 
+    #[serde(rename = "/cosmos.staking.v1beta1.MsgUndelegate")]
+    MsgUndelegate {
+        delegator_address: String,
+        validator_address: String,
+        amount: Amount,
+    },
+
+    #[serde(rename = "/cosmos.staking.v1beta1.MsgBeginRedelegate")]
+    MsgBeginRedelegate {
+        delegator_address: String,
+        validator_src_address: String,
+        validator_dst_address: String,
+        amount: Amount,
+    },
+
+    #[serde(rename = "/cosmos.staking.v1beta1.MsgCancelUnbondingDelegation")]
+    MsgCancelUnbondingDelegation {
+        delegator_address: String,
+        validator_address: String,
+        amount: Amount,
+        /// Height at which the unbonding being cancelled was created.
+        creation_height: String,
+    },
+
+    #[serde(rename = "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward")]
+    MsgWithdrawDelegatorReward {
+        delegator_address: String,
+        validator_address: String,
+    },
+
+    #[serde(rename = "/cosmos.distribution.v1beta1.MsgWithdrawValidatorCommission")]
+    MsgWithdrawValidatorCommission {
+        validator_address: String,
+    },
+
+    #[serde(rename = "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress")]
+    MsgSetWithdrawAddress {
+        delegator_address: String,
+        withdraw_address: String,
+    },
+
+    #[serde(rename = "/cosmos.distribution.v1beta1.MsgFundCommunityPool")]
+    MsgFundCommunityPool {
+        amount: Vec<Amount>,
+        depositor: String,
+    },
+
+    /// A gov v1beta1 proposal; `content` is the proposal content `Any` as JSON.
+    #[serde(rename = "/cosmos.gov.v1beta1.MsgSubmitProposal")]
+    MsgSubmitProposal {
+        content: Value,
+        initial_deposit: Vec<Amount>,
+        proposer: String,
+    },
+
+    /// A gov v1 proposal, executing `messages` when it passes.
+    #[serde(rename = "/cosmos.gov.v1.MsgSubmitProposal")]
+    MsgSubmitProposalV1 {
+        messages: Vec<Message>,
+        initial_deposit: Vec<Amount>,
+        proposer: String,
+        #[serde(default)]
+        metadata: String,
+        #[serde(default)]
+        title: String,
+        #[serde(default)]
+        summary: String,
+        #[serde(default)]
+        expedited: bool,
+    },
+
+    #[serde(rename = "/cosmos.gov.v1beta1.MsgVote")]
+    MsgVote {
+        proposal_id: String,
+        voter: String,
+        /// A `VoteOption` name such as `VOTE_OPTION_YES`.
+        option: String,
+    },
+
+    #[serde(rename = "/cosmos.gov.v1.MsgVote")]
+    MsgVoteV1 {
+        proposal_id: String,
+        voter: String,
+        option: String,
+        #[serde(default)]
+        metadata: String,
+    },
+
+    #[serde(rename = "/cosmos.gov.v1beta1.MsgVoteWeighted")]
+    MsgVoteWeighted {
+        proposal_id: String,
+        voter: String,
+        options: Vec<WeightedVoteOption>,
+    },
+
+    #[serde(rename = "/cosmos.gov.v1.MsgVoteWeighted")]
+    MsgVoteWeightedV1 {
+        proposal_id: String,
+        voter: String,
+        options: Vec<WeightedVoteOption>,
+        #[serde(default)]
+        metadata: String,
+    },
+
+    #[serde(rename = "/cosmos.gov.v1beta1.MsgDeposit")]
+    MsgDeposit {
+        proposal_id: String,
+        depositor: String,
+        amount: Vec<Amount>,
+    },
+
+    #[serde(rename = "/cosmos.gov.v1.MsgDeposit")]
+    MsgDepositV1 {
+        proposal_id: String,
+        depositor: String,
+        amount: Vec<Amount>,
+    },
+
+    /// Executes v1beta1 proposal content through gov v1; `content` is the content `Any` as JSON.
+    #[serde(rename = "/cosmos.gov.v1.MsgExecLegacyContent")]
+    MsgExecLegacyContent {
+        content: Value,
+        authority: String,
+    },
+
+    /// Executes `msgs` on behalf of their signers, who granted `grantee` the right to do so.
+    #[serde(rename = "/cosmos.authz.v1beta1.MsgExec")]
+    MsgExec {
+        grantee: String,
+        msgs: Vec<Message>,
+    },
+
+    #[serde(rename = "/cosmos.authz.v1beta1.MsgGrant")]
+    MsgGrant {
+        granter: String,
+        grantee: String,
+        grant: Grant,
+    },
+
+    #[serde(rename = "/cosmos.authz.v1beta1.MsgRevoke")]
+    MsgRevoke {
+        granter: String,
+        grantee: String,
+        msg_type_url: String,
+    },
+
+    /// Uploads wasm code; `wasm_byte_code` is base64 encoded.
+    #[serde(rename = "/cosmwasm.wasm.v1.MsgStoreCode")]
+    MsgStoreCode {
+        sender: String,
+        wasm_byte_code: String,
+        #[serde(default)]
+        instantiate_permission: Option<Value>,
+    },
+
+    #[serde(rename = "/cosmwasm.wasm.v1.MsgInstantiateContract")]
+    MsgInstantiateContract {
+        sender: String,
+        #[serde(default)]
+        admin: String,
+        code_id: String,
+        label: String,
+        #[serde(deserialize_with = "contract_msg")]
+        msg: Value,
+        #[serde(default)]
+        funds: Vec<Amount>,
+    },
+
+    /// Instantiates at an address derived from `salt` (base64 encoded) rather than a counter.
+    #[serde(rename = "/cosmwasm.wasm.v1.MsgInstantiateContract2")]
+    MsgInstantiateContract2 {
+        sender: String,
+        #[serde(default)]
+        admin: String,
+        code_id: String,
+        label: String,
+        #[serde(deserialize_with = "contract_msg")]
+        msg: Value,
+        #[serde(default)]
+        funds: Vec<Amount>,
+        salt: String,
+        #[serde(default)]
+        fix_msg: bool,
+    },
+
+    #[serde(rename = "/cosmwasm.wasm.v1.MsgExecuteContract")]
+    MsgExecuteContract {
+        sender: String,
+        contract: String,
+        #[serde(deserialize_with = "contract_msg")]
+        msg: Value,
+        #[serde(default)]
+        funds: Vec<Amount>,
+    },
+
+    #[serde(rename = "/cosmwasm.wasm.v1.MsgMigrateContract")]
+    MsgMigrateContract {
+        sender: String,
+        contract: String,
+        code_id: String,
+        #[serde(deserialize_with = "contract_msg")]
+        msg: Value,
+    },
+
+    #[serde(rename = "/cosmwasm.wasm.v1.MsgUpdateAdmin")]
+    MsgUpdateAdmin {
+        sender: String,
+        new_admin: String,
+        contract: String,
+    },
+
     #[serde(rename = "/ibc.applications.transfer.v1.MsgTransfer")]
     MsgTransfer {
         source_port: String,
@@ -35,13 +253,247 @@ pub enum Message {
         memo: String,
     },
 
-    /// A fallback variant for any unexpected message types.
-    #[serde(other)]
-    Other,
+    #[serde(rename = "/ibc.core.channel.v1.MsgRecvPacket")]
+    MsgRecvPacket {
+        packet: Packet,
+        signer: String,
+    },
+
+    #[serde(rename = "/ibc.core.channel.v1.MsgAcknowledgement")]
+    MsgAcknowledgement {
+        packet: Packet,
+        /// The base64 encoded acknowledgement written by the receiving chain.
+        acknowledgement: String,
+        signer: String,
+    },
+
+    #[serde(rename = "/ibc.core.channel.v1.MsgTimeout")]
+    MsgTimeout {
+        packet: Packet,
+        next_sequence_recv: String,
+        signer: String,
+    },
+
+    /// A light client update; `client_message` is the header `Any` as JSON.
+    #[serde(rename = "/ibc.core.client.v1.MsgUpdateClient")]
+    MsgUpdateClient {
+        client_id: String,
+        // ibc-go before v7 calls the field `header`.
+        #[serde(alias = "header")]
+        client_message: Value,
+        signer: String,
+    },
+
+    #[serde(rename = "/ibc.core.channel.v1.MsgChannelOpenInit")]
+    MsgChannelOpenInit {
+        port_id: String,
+        channel: ChannelEnd,
+        signer: String,
+    },
+
+    #[serde(rename = "/ibc.core.channel.v1.MsgChannelOpenTry")]
+    MsgChannelOpenTry {
+        port_id: String,
+        #[serde(default)]
+        previous_channel_id: String,
+        channel: ChannelEnd,
+        counterparty_version: String,
+        signer: String,
+    },
+
+    #[serde(rename = "/ibc.core.channel.v1.MsgChannelOpenAck")]
+    MsgChannelOpenAck {
+        port_id: String,
+        channel_id: String,
+        counterparty_channel_id: String,
+        counterparty_version: String,
+        signer: String,
+    },
+
+    #[serde(rename = "/ibc.core.channel.v1.MsgChannelOpenConfirm")]
+    MsgChannelOpenConfirm {
+        port_id: String,
+        channel_id: String,
+        signer: String,
+    },
+
+    #[serde(rename = "/ibc.core.channel.v1.MsgChannelCloseInit")]
+    MsgChannelCloseInit {
+        port_id: String,
+        channel_id: String,
+        signer: String,
+    },
+
+    #[serde(rename = "/ibc.core.channel.v1.MsgChannelCloseConfirm")]
+    MsgChannelCloseConfirm {
+        port_id: String,
+        channel_id: String,
+        signer: String,
+    },
+
+    #[serde(rename = "/ibc.core.connection.v1.MsgConnectionOpenInit")]
+    MsgConnectionOpenInit {
+        client_id: String,
+        counterparty: ConnectionCounterparty,
+        delay_period: String,
+        signer: String,
+    },
+
+    #[serde(rename = "/ibc.core.connection.v1.MsgConnectionOpenTry")]
+    MsgConnectionOpenTry {
+        client_id: String,
+        counterparty: ConnectionCounterparty,
+        delay_period: String,
+        signer: String,
+    },
+
+    #[serde(rename = "/ibc.core.connection.v1.MsgConnectionOpenAck")]
+    MsgConnectionOpenAck {
+        connection_id: String,
+        counterparty_connection_id: String,
+        signer: String,
+    },
 
+    #[serde(rename = "/ibc.core.connection.v1.MsgConnectionOpenConfirm")]
+    MsgConnectionOpenConfirm {
+        connection_id: String,
+        signer: String,
+    },
+
+    /// A protobuf `Any` whose type we don't model, decoded from raw tx bytes.
+    /// `value` holds the base64 encoded protobuf payload.
+    #[serde(rename = "/google.protobuf.Any")]
+    Undecoded {
+        type_url: String,
+        value: String,
+    },
+
+    /// A fallback for any message type we don't model, keeping its `@type` and full JSON.
+    /// Also used when a known `@type` carries fields that don't match our model.
+    #[serde(untagged)]
+    Unknown(RawMessage),
+}
+
+impl Message {
+    /// The type URL (`@type`) of the message.
+    pub fn type_url(&self) -> &str {
+        match self {
+            Message::MsgSend { .. } => "/cosmos.bank.v1beta1.MsgSend",
+            Message::MsgDelegate { .. } => "/cosmos.staking.v1beta1.MsgDelegate",
+            Message::MsgUndelegate { .. } => "/cosmos.staking.v1beta1.MsgUndelegate",
+            Message::MsgBeginRedelegate { .. } => "/cosmos.staking.v1beta1.MsgBeginRedelegate",
+            Message::MsgCancelUnbondingDelegation { .. } => "/cosmos.staking.v1beta1.MsgCancelUnbondingDelegation",
+            Message::MsgWithdrawDelegatorReward { .. } => "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward",
+            Message::MsgWithdrawValidatorCommission { .. } => "/cosmos.distribution.v1beta1.MsgWithdrawValidatorCommission",
+            Message::MsgSetWithdrawAddress { .. } => "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress",
+            Message::MsgFundCommunityPool { .. } => "/cosmos.distribution.v1beta1.MsgFundCommunityPool",
+            Message::MsgSubmitProposal { .. } => "/cosmos.gov.v1beta1.MsgSubmitProposal",
+            Message::MsgSubmitProposalV1 { .. } => "/cosmos.gov.v1.MsgSubmitProposal",
+            Message::MsgVote { .. } => "/cosmos.gov.v1beta1.MsgVote",
+            Message::MsgVoteV1 { .. } => "/cosmos.gov.v1.MsgVote",
+            Message::MsgVoteWeighted { .. } => "/cosmos.gov.v1beta1.MsgVoteWeighted",
+            Message::MsgVoteWeightedV1 { .. } => "/cosmos.gov.v1.MsgVoteWeighted",
+            Message::MsgDeposit { .. } => "/cosmos.gov.v1beta1.MsgDeposit",
+            Message::MsgDepositV1 { .. } => "/cosmos.gov.v1.MsgDeposit",
+            Message::MsgExecLegacyContent { .. } => "/cosmos.gov.v1.MsgExecLegacyContent",
+            Message::MsgExec { .. } => "/cosmos.authz.v1beta1.MsgExec",
+            Message::MsgGrant { .. } => "/cosmos.authz.v1beta1.MsgGrant",
+            Message::MsgRevoke { .. } => "/cosmos.authz.v1beta1.MsgRevoke",
+            Message::MsgStoreCode { .. } => "/cosmwasm.wasm.v1.MsgStoreCode",
+            Message::MsgInstantiateContract { .. } => "/cosmwasm.wasm.v1.MsgInstantiateContract",
+            Message::MsgInstantiateContract2 { .. } => "/cosmwasm.wasm.v1.MsgInstantiateContract2",
+            Message::MsgExecuteContract { .. } => "/cosmwasm.wasm.v1.MsgExecuteContract",
+            Message::MsgMigrateContract { .. } => "/cosmwasm.wasm.v1.MsgMigrateContract",
+            Message::MsgUpdateAdmin { .. } => "/cosmwasm.wasm.v1.MsgUpdateAdmin",
+            Message::MsgTransfer { .. } => "/ibc.applications.transfer.v1.MsgTransfer",
+            Message::MsgRecvPacket { .. } => "/ibc.core.channel.v1.MsgRecvPacket",
+            Message::MsgAcknowledgement { .. } => "/ibc.core.channel.v1.MsgAcknowledgement",
+            Message::MsgTimeout { .. } => "/ibc.core.channel.v1.MsgTimeout",
+            Message::MsgUpdateClient { .. } => "/ibc.core.client.v1.MsgUpdateClient",
+            Message::MsgChannelOpenInit { .. } => "/ibc.core.channel.v1.MsgChannelOpenInit",
+            Message::MsgChannelOpenTry { .. } => "/ibc.core.channel.v1.MsgChannelOpenTry",
+            Message::MsgChannelOpenAck { .. } => "/ibc.core.channel.v1.MsgChannelOpenAck",
+            Message::MsgChannelOpenConfirm { .. } => "/ibc.core.channel.v1.MsgChannelOpenConfirm",
+            Message::MsgChannelCloseInit { .. } => "/ibc.core.channel.v1.MsgChannelCloseInit",
+            Message::MsgChannelCloseConfirm { .. } => "/ibc.core.channel.v1.MsgChannelCloseConfirm",
+            Message::MsgConnectionOpenInit { .. } => "/ibc.core.connection.v1.MsgConnectionOpenInit",
+            Message::MsgConnectionOpenTry { .. } => "/ibc.core.connection.v1.MsgConnectionOpenTry",
+            Message::MsgConnectionOpenAck { .. } => "/ibc.core.connection.v1.MsgConnectionOpenAck",
+            Message::MsgConnectionOpenConfirm { .. } => "/ibc.core.connection.v1.MsgConnectionOpenConfirm",
+            Message::Undecoded { type_url, .. } => type_url,
+            Message::Unknown(raw) => &raw.type_url,
+        }
+    }
+
+    /// The contract a CosmWasm message is addressed to. Instantiations have none yet.
+    pub fn contract_address(&self) -> Option<&str> {
+        match self {
+            Message::MsgExecuteContract { contract, .. }
+            | Message::MsgMigrateContract { contract, .. }
+            | Message::MsgUpdateAdmin { contract, .. } => Some(contract),
+            _ => None,
+        }
+    }
+
+    /// The top-level method of a `MsgExecuteContract`, such as `swap` for `{"swap": {...}}`.
+    pub fn execute_method(&self) -> Option<&str> {
+        match self {
+            Message::MsgExecuteContract { msg: Value::Object(msg), .. } => msg.keys().next().map(String::as_str),
+            _ => None,
+        }
+    }
 }
 
-/// Represents the `amount` field in the `MsgSend` and `MsgDelegate` message types.
+/// Contract messages are JSON, but some nodes and amino JSON encode them as base64. Either way
+/// the JSON value is kept; a string that isn't base64 encoded JSON is kept as is.
+fn contract_msg<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    Ok(match &value {
+        Value::String(encoded) => decode_contract_msg(encoded).unwrap_or(value),
+        _ => value,
+    })
+}
+
+fn decode_contract_msg(encoded: &str) -> Option<Value> {
+    let bytes = STANDARD.decode(encoded).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::Unknown(raw) => write!(f, "{} {}", raw.type_url, raw.value),
+            Message::Undecoded { type_url, value } => write!(f, "{} (undecoded: {})", type_url, value),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// A message of a type we don't model: the JSON object exactly as received, `@type` included.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RawMessage {
+    pub type_url: String,
+    pub value: Value,
+}
+
+impl Serialize for RawMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RawMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let type_url = value.get("@type")
+            .and_then(Value::as_str)
+            .ok_or_else(|| D::Error::custom("message without an @type"))?
+            .to_string();
+        Ok(RawMessage { type_url, value })
+    }
+}
+
+/// Represents a coin amount, such as the `amount` field of `MsgSend` and the staking messages.
 #[derive(Debug,PartialEq ,Serialize, Deserialize, Clone)]
 pub struct Amount {
     pub denom: String,
@@ -50,13 +502,43 @@ pub struct Amount {
 
 
 
-/// Represents various message types from the CosmosSDK.
-#[derive(Debug, Serialize, Deserialize, Clone,Parser,PartialEq)]
+/// One option of a weighted gov vote; `weight` is a decimal such as `0.500000000000000000`.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct WeightedVoteOption {
+    pub option: String,
+    pub weight: String,
+}
+
+/// An authz grant; `authorization` is the authorization `Any` as JSON, such as a
+/// `GenericAuthorization` with its `msg` type URL.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct Grant {
+    pub authorization: Value,
+    /// RFC 3339 expiry time; grants without one never expire.
+    #[serde(default)]
+    pub expiration: Option<String>,
+}
+
+/// A message filter, as given to `--filter-by-msgtype`.
+#[derive(Debug, Serialize, Deserialize, Clone,PartialEq)]
 pub enum MessageType {
-    MsgSend,
-    MsgDelegate,
-    MsgTransfer,
+    /// A registered name such as `MsgSend`; versions of a message share their name.
+    Name(String),
+    /// Any message type the registry doesn't know.
     Other,
+    /// An exact type URL such as `/cosmwasm.wasm.v1.MsgExecuteContract`.
+    TypeUrl(String),
+}
+
+impl MessageType {
+    /// Whether the message is of this type.
+    pub fn matches(&self, message: &Message, registry: &MessageRegistry) -> bool {
+        match self {
+            MessageType::Name(name) => registry.name_of(message.type_url()) == Some(name.as_str()),
+            MessageType::Other => registry.get(message.type_url()).is_none(),
+            MessageType::TypeUrl(type_url) => message.type_url() == type_url,
+        }
+    }
 }
 
 /// Implementation to convert string slices to MsgType
@@ -65,10 +547,11 @@ impl FromStr for MessageType {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "MsgSend" => Ok(MessageType::MsgSend),
-            "MsgDelegate" => Ok(MessageType::MsgDelegate),
-            "MsgTransfer" => Ok(MessageType::MsgTransfer),
             "Other" => Ok(MessageType::Other),
+            type_url if type_url.starts_with('/') => Ok(MessageType::TypeUrl(type_url.to_string())),
+            name if name.starts_with(|c: char| c.is_ascii_alphabetic()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') => {
+                Ok(MessageType::Name(name.to_string()))
+            }
             _ => Err(format!("'{}' is not a valid MsgType value", s)),
         }
     }
@@ -77,6 +560,238 @@ impl FromStr for MessageType {
 /// Represents the `timeout_height` field in the `MsgTransfer` message type.
 #[derive(Debug, PartialEq,Serialize, Deserialize, Clone)]
 pub struct TimeoutHeight {
-    revision_number: String,
-    revision_height: String,
-}
\ No newline at end of file
+    pub revision_number: String,
+    pub revision_height: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn registry() -> MessageRegistry {
+        MessageRegistry::builtin()
+    }
+
+    #[test]
+    fn test_unknown_message_keeps_type_and_json() {
+        let json = json!({
+            "@type": "/osmosis.gamm.v1beta1.MsgSwapExactAmountIn",
+            "sender": "osmo1sender",
+            "routes": [{ "pool_id": "1", "token_out_denom": "uatom" }],
+            "token_in": { "denom": "uosmo", "amount": "5" },
+            "token_out_min_amount": "1"
+        });
+
+        let message: Message = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(message.type_url(), "/osmosis.gamm.v1beta1.MsgSwapExactAmountIn");
+        assert!(matches!(&message, Message::Unknown(raw) if raw.value == json));
+        assert_eq!(serde_json::to_value(&message).unwrap(), json);
+    }
+
+    #[test]
+    fn test_known_message_still_typed() {
+        let message: Message = serde_json::from_value(json!({
+            "@type": "/cosmos.bank.v1beta1.MsgSend",
+            "from_address": "a",
+            "to_address": "b",
+            "amount": [{ "denom": "uatom", "amount": "1" }]
+        })).unwrap();
+
+        assert!(matches!(message, Message::MsgSend { .. }));
+        assert_eq!(serde_json::to_value(&message).unwrap()["@type"], "/cosmos.bank.v1beta1.MsgSend");
+    }
+
+    #[test]
+    fn test_known_type_with_unexpected_fields_is_kept() {
+        let json = json!({ "@type": "/cosmos.bank.v1beta1.MsgSend", "from_address": "a" });
+        let message: Message = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(message, Message::Unknown(RawMessage { type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(), value: json }));
+    }
+
+    #[test]
+    fn test_staking_and_distribution_messages() {
+        let redelegate: Message = serde_json::from_value(json!({
+            "@type": "/cosmos.staking.v1beta1.MsgBeginRedelegate",
+            "delegator_address": "cosmos1delegator",
+            "validator_src_address": "cosmosvaloper1src",
+            "validator_dst_address": "cosmosvaloper1dst",
+            "amount": { "denom": "uatom", "amount": "10" }
+        })).unwrap();
+        let withdraw: Message = serde_json::from_value(json!({
+            "@type": "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward",
+            "delegator_address": "cosmos1delegator",
+            "validator_address": "cosmosvaloper1src"
+        })).unwrap();
+
+        assert!(matches!(redelegate, Message::MsgBeginRedelegate { .. }));
+        assert!(MessageType::from_str("MsgBeginRedelegate").unwrap().matches(&redelegate, &registry()));
+        assert!(MessageType::from_str("MsgWithdrawDelegatorReward").unwrap().matches(&withdraw, &registry()));
+        assert!(!MessageType::Name("MsgDelegate".to_string()).matches(&withdraw, &registry()));
+    }
+
+    #[test]
+    fn test_authz_messages() {
+        let exec: Message = serde_json::from_value(json!({
+            "@type": "/cosmos.authz.v1beta1.MsgExec",
+            "grantee": "cosmos1restake",
+            "msgs": [{
+                "@type": "/cosmos.staking.v1beta1.MsgDelegate",
+                "delegator_address": "cosmos1delegator",
+                "validator_address": "cosmosvaloper1abc",
+                "amount": { "denom": "uatom", "amount": "10" }
+            }]
+        })).unwrap();
+        let grant: Message = serde_json::from_value(json!({
+            "@type": "/cosmos.authz.v1beta1.MsgGrant",
+            "granter": "cosmos1delegator",
+            "grantee": "cosmos1restake",
+            "grant": {
+                "authorization": { "@type": "/cosmos.authz.v1beta1.GenericAuthorization", "msg": "/cosmos.staking.v1beta1.MsgDelegate" },
+                "expiration": null
+            }
+        })).unwrap();
+
+        match &exec {
+            Message::MsgExec { grantee, msgs } => {
+                assert_eq!(grantee, "cosmos1restake");
+                assert!(MessageType::Name("MsgDelegate".to_string()).matches(&msgs[0], &registry()));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+        assert!(matches!(&grant, Message::MsgGrant { grant, .. } if grant.expiration.is_none()));
+        assert!(MessageType::from_str("MsgGrant").unwrap().matches(&grant, &registry()));
+    }
+
+    #[test]
+    fn test_cosmwasm_messages() {
+        let execute: Message = serde_json::from_value(json!({
+            "@type": "/cosmwasm.wasm.v1.MsgExecuteContract",
+            "sender": "osmo1sender",
+            "contract": "osmo1contract",
+            "msg": { "swap": { "offer_asset": "uosmo" } },
+            "funds": [{ "denom": "uosmo", "amount": "5" }]
+        })).unwrap();
+        // {"transfer":{"recipient":"osmo1to","amount":"1"}}
+        let encoded: Message = serde_json::from_value(json!({
+            "@type": "/cosmwasm.wasm.v1.MsgExecuteContract",
+            "sender": "osmo1sender",
+            "contract": "osmo1token",
+            "msg": "eyJ0cmFuc2ZlciI6eyJyZWNpcGllbnQiOiJvc21vMXRvIiwiYW1vdW50IjoiMSJ9fQ==",
+            "funds": []
+        })).unwrap();
+        let instantiate: Message = serde_json::from_value(json!({
+            "@type": "/cosmwasm.wasm.v1.MsgInstantiateContract2",
+            "sender": "osmo1sender",
+            "admin": "",
+            "code_id": "42",
+            "label": "pool",
+            "msg": {},
+            "funds": [],
+            "salt": "c2FsdA==",
+            "fix_msg": false
+        })).unwrap();
+
+        assert_eq!(execute.contract_address(), Some("osmo1contract"));
+        assert_eq!(execute.execute_method(), Some("swap"));
+        assert_eq!(encoded.execute_method(), Some("transfer"));
+        assert!(matches!(&encoded, Message::MsgExecuteContract { msg, .. } if msg["transfer"]["recipient"] == "osmo1to"));
+        assert!(MessageType::Name("MsgInstantiateContract".to_string()).matches(&instantiate, &registry()));
+        assert_eq!(instantiate.contract_address(), None);
+    }
+
+    #[test]
+    fn test_gov_v1_proposal_with_nested_messages() {
+        let proposal: Message = serde_json::from_value(json!({
+            "@type": "/cosmos.gov.v1.MsgSubmitProposal",
+            "messages": [{
+                "@type": "/cosmos.distribution.v1beta1.MsgFundCommunityPool",
+                "amount": [{ "denom": "uatom", "amount": "1" }],
+                "depositor": "cosmos1gov"
+            }],
+            "initial_deposit": [],
+            "proposer": "cosmos1proposer",
+            "metadata": "ipfs://meta",
+            "title": "Fund the pool",
+            "summary": "",
+            "expedited": false
+        })).unwrap();
+
+        match &proposal {
+            Message::MsgSubmitProposalV1 { messages, title, .. } => {
+                assert!(matches!(messages[0], Message::MsgFundCommunityPool { .. }));
+                assert_eq!(title, "Fund the pool");
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+        assert!(MessageType::Name("MsgSubmitProposal".to_string()).matches(&proposal, &registry()));
+    }
+
+    #[test]
+    fn test_gov_votes_match_both_versions() {
+        let v1beta1: Message = serde_json::from_value(json!({
+            "@type": "/cosmos.gov.v1beta1.MsgVote", "proposal_id": "7", "voter": "cosmos1voter", "option": "VOTE_OPTION_YES"
+        })).unwrap();
+        let v1: Message = serde_json::from_value(json!({
+            "@type": "/cosmos.gov.v1.MsgVoteWeighted",
+            "proposal_id": "7",
+            "voter": "cosmos1voter",
+            "options": [{ "option": "VOTE_OPTION_YES", "weight": "0.7" }, { "option": "VOTE_OPTION_NO", "weight": "0.3" }],
+            "metadata": ""
+        })).unwrap();
+
+        assert!(MessageType::from_str("MsgVote").unwrap().matches(&v1beta1, &registry()));
+        assert!(MessageType::from_str("MsgVoteWeighted").unwrap().matches(&v1, &registry()));
+        assert!(!MessageType::Name("MsgVote".to_string()).matches(&v1, &registry()));
+    }
+
+    #[test]
+    fn test_ibc_relayer_messages() {
+        let recv: Message = serde_json::from_value(json!({
+            "@type": "/ibc.core.channel.v1.MsgRecvPacket",
+            "packet": {
+                "sequence": "7",
+                "source_port": "transfer",
+                "source_channel": "channel-0",
+                "destination_port": "transfer",
+                "destination_channel": "channel-141",
+                "data": "eyJhbW91bnQiOiI1IiwiZGVub20iOiJ1b3NtbyIsInJlY2VpdmVyIjoiY29zbW9zMXRvIiwic2VuZGVyIjoib3NtbzFmcm9tIn0=",
+                "timeout_height": { "revision_number": "4", "revision_height": "100" },
+                "timeout_timestamp": "0"
+            },
+            "proof_commitment": "AAAA",
+            "proof_height": { "revision_number": "1", "revision_height": "99" },
+            "signer": "cosmos1relayer"
+        })).unwrap();
+        let update: Message = serde_json::from_value(json!({
+            "@type": "/ibc.core.client.v1.MsgUpdateClient",
+            "client_id": "07-tendermint-0",
+            "header": { "@type": "/ibc.lightclients.tendermint.v1.Header" },
+            "signer": "cosmos1relayer"
+        })).unwrap();
+
+        match &recv {
+            Message::MsgRecvPacket { packet, signer } => {
+                assert_eq!(signer, "cosmos1relayer");
+                assert_eq!(packet.transfer.as_ref().map(|t| t.denom.as_str()), Some("uosmo"));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+        assert!(MessageType::from_str("MsgRecvPacket").unwrap().matches(&recv, &registry()));
+        assert!(MessageType::from_str("MsgUpdateClient").unwrap().matches(&update, &registry()));
+    }
+
+    #[test]
+    fn test_message_type_matches_type_url() {
+        let message: Message = serde_json::from_value(json!({ "@type": "/osmosis.gamm.v1beta1.MsgSwapExactAmountIn", "sender": "osmo1" })).unwrap();
+
+        assert!(MessageType::from_str("/osmosis.gamm.v1beta1.MsgSwapExactAmountIn").unwrap().matches(&message, &registry()));
+        assert!(MessageType::Other.matches(&message, &registry()));
+        assert!(!MessageType::Name("MsgSend".to_string()).matches(&message, &registry()));
+        // Chain-specific names only match once a schema registers them.
+        assert!(!MessageType::from_str("MsgSwapExactAmountIn").unwrap().matches(&message, &registry()));
+        assert!(MessageType::from_str("not a type").is_err());
+    }
+}
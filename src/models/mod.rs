@@ -1,3 +1,16 @@
 pub mod message;
+pub mod coin;
 pub mod transaction;
-pub mod pagination;
\ No newline at end of file
+pub mod pagination;
+pub mod event;
+pub mod proto;
+pub mod rpc;
+pub mod gov;
+pub mod ibc;
+pub mod registry;
+pub mod auth;
+pub mod fee;
+pub mod failure;
+pub mod block;
+#[cfg(test)]
+pub mod testing;
@@ -0,0 +1,1373 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{SecondsFormat, TimeZone, Utc};
+use prost::Message as _;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::models::auth;
+use crate::models::ibc;
+use crate::models::message::{self, Amount, Message, TimeoutHeight, WeightedVoteOption};
+use crate::models::transaction::{Body, TranslationError, Tx};
+
+// Protobuf definitions of the Cosmos SDK transaction envelope (`cosmos.tx.v1beta1`) and of the
+// messages we model, used to decode raw tx bytes such as those returned by the CometBFT RPC.
+
+/// `cosmos.tx.v1beta1.TxRaw`: the signed bytes exactly as included in a block.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TxRaw {
+    #[prost(bytes = "vec", tag = "1")]
+    pub body_bytes: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub auth_info_bytes: Vec<u8>,
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub signatures: Vec<Vec<u8>>,
+}
+
+/// `cosmos.tx.v1beta1.TxBody`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TxBody {
+    #[prost(message, repeated, tag = "1")]
+    pub messages: Vec<Any>,
+    #[prost(string, tag = "2")]
+    pub memo: String,
+    #[prost(uint64, tag = "3")]
+    pub timeout_height: u64,
+}
+
+/// `google.protobuf.Any`: a message payload tagged with its type URL.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Any {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+/// `cosmos.tx.v1beta1.AuthInfo`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AuthInfo {
+    #[prost(message, repeated, tag = "1")]
+    pub signer_infos: Vec<SignerInfo>,
+    #[prost(message, optional, tag = "2")]
+    pub fee: Option<Fee>,
+}
+
+/// `cosmos.tx.v1beta1.SignerInfo`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SignerInfo {
+    #[prost(message, optional, tag = "1")]
+    pub public_key: Option<Any>,
+    #[prost(message, optional, tag = "2")]
+    pub mode_info: Option<ModeInfo>,
+    #[prost(uint64, tag = "3")]
+    pub sequence: u64,
+}
+
+/// `cosmos.tx.v1beta1.ModeInfo`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ModeInfo {
+    #[prost(oneof = "mode_info::Sum", tags = "1, 2")]
+    pub sum: Option<mode_info::Sum>,
+}
+
+pub mod mode_info {
+    /// `cosmos.tx.v1beta1.ModeInfo.sum`.
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Sum {
+        #[prost(message, tag = "1")]
+        Single(Single),
+        #[prost(message, tag = "2")]
+        Multi(Multi),
+    }
+
+    /// `cosmos.tx.v1beta1.ModeInfo.Single`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Single {
+        #[prost(int32, tag = "1")]
+        pub mode: i32,
+    }
+
+    /// `cosmos.tx.v1beta1.ModeInfo.Multi`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Multi {
+        #[prost(message, optional, tag = "1")]
+        pub bitarray: Option<CompactBitArray>,
+        #[prost(message, repeated, tag = "2")]
+        pub mode_infos: Vec<super::ModeInfo>,
+    }
+
+    /// `cosmos.crypto.multisig.v1beta1.CompactBitArray`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct CompactBitArray {
+        #[prost(uint32, tag = "1")]
+        pub extra_bits_stored: u32,
+        #[prost(bytes = "vec", tag = "2")]
+        pub elems: Vec<u8>,
+    }
+}
+
+/// `cosmos.tx.v1beta1.Fee`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Fee {
+    #[prost(message, repeated, tag = "1")]
+    pub amount: Vec<Coin>,
+    #[prost(uint64, tag = "2")]
+    pub gas_limit: u64,
+    #[prost(string, tag = "3")]
+    pub payer: String,
+    #[prost(string, tag = "4")]
+    pub granter: String,
+}
+
+/// `cosmos.crypto.secp256k1.PubKey` and `cosmos.crypto.ed25519.PubKey`, which share one layout.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PubKey {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key: Vec<u8>,
+}
+
+/// `cosmos.base.v1beta1.Coin`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Coin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+/// `ibc.core.client.v1.Height`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Height {
+    #[prost(uint64, tag = "1")]
+    pub revision_number: u64,
+    #[prost(uint64, tag = "2")]
+    pub revision_height: u64,
+}
+
+/// `cosmos.bank.v1beta1.MsgSend`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgSend {
+    #[prost(string, tag = "1")]
+    pub from_address: String,
+    #[prost(string, tag = "2")]
+    pub to_address: String,
+    #[prost(message, repeated, tag = "3")]
+    pub amount: Vec<Coin>,
+}
+
+/// `cosmos.staking.v1beta1.MsgDelegate`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgDelegate {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(message, optional, tag = "3")]
+    pub amount: Option<Coin>,
+}
+
+/// `cosmos.staking.v1beta1.MsgUndelegate`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgUndelegate {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(message, optional, tag = "3")]
+    pub amount: Option<Coin>,
+}
+
+/// `cosmos.staking.v1beta1.MsgBeginRedelegate`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgBeginRedelegate {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_src_address: String,
+    #[prost(string, tag = "3")]
+    pub validator_dst_address: String,
+    #[prost(message, optional, tag = "4")]
+    pub amount: Option<Coin>,
+}
+
+/// `cosmos.staking.v1beta1.MsgCancelUnbondingDelegation`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgCancelUnbondingDelegation {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(message, optional, tag = "3")]
+    pub amount: Option<Coin>,
+    #[prost(int64, tag = "4")]
+    pub creation_height: i64,
+}
+
+/// `cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgWithdrawDelegatorReward {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+}
+
+/// `cosmos.distribution.v1beta1.MsgWithdrawValidatorCommission`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgWithdrawValidatorCommission {
+    #[prost(string, tag = "1")]
+    pub validator_address: String,
+}
+
+/// `cosmos.distribution.v1beta1.MsgSetWithdrawAddress`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgSetWithdrawAddress {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub withdraw_address: String,
+}
+
+/// `cosmos.distribution.v1beta1.MsgFundCommunityPool`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgFundCommunityPool {
+    #[prost(message, repeated, tag = "1")]
+    pub amount: Vec<Coin>,
+    #[prost(string, tag = "2")]
+    pub depositor: String,
+}
+
+/// `cosmos.gov.v1beta1.MsgSubmitProposal`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgSubmitProposal {
+    #[prost(message, optional, tag = "1")]
+    pub content: Option<Any>,
+    #[prost(message, repeated, tag = "2")]
+    pub initial_deposit: Vec<Coin>,
+    #[prost(string, tag = "3")]
+    pub proposer: String,
+}
+
+/// `cosmos.gov.v1.MsgSubmitProposal`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgSubmitProposalV1 {
+    #[prost(message, repeated, tag = "1")]
+    pub messages: Vec<Any>,
+    #[prost(message, repeated, tag = "2")]
+    pub initial_deposit: Vec<Coin>,
+    #[prost(string, tag = "3")]
+    pub proposer: String,
+    #[prost(string, tag = "4")]
+    pub metadata: String,
+    #[prost(string, tag = "5")]
+    pub title: String,
+    #[prost(string, tag = "6")]
+    pub summary: String,
+    #[prost(bool, tag = "7")]
+    pub expedited: bool,
+}
+
+/// `cosmos.gov.v1.MsgVote`; v1beta1 has the same layout without `metadata`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgVote {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub voter: String,
+    #[prost(int32, tag = "3")]
+    pub option: i32,
+    #[prost(string, tag = "4")]
+    pub metadata: String,
+}
+
+/// `cosmos.gov.v1.WeightedVoteOption`, shared with v1beta1.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct WeightedOption {
+    #[prost(int32, tag = "1")]
+    pub option: i32,
+    #[prost(string, tag = "2")]
+    pub weight: String,
+}
+
+/// `cosmos.gov.v1.MsgVoteWeighted`; v1beta1 has the same layout without `metadata`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgVoteWeighted {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub voter: String,
+    #[prost(message, repeated, tag = "3")]
+    pub options: Vec<WeightedOption>,
+    #[prost(string, tag = "4")]
+    pub metadata: String,
+}
+
+/// `cosmos.gov.v1.MsgDeposit`, identical in v1beta1.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgDeposit {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub depositor: String,
+    #[prost(message, repeated, tag = "3")]
+    pub amount: Vec<Coin>,
+}
+
+/// `cosmos.gov.v1.MsgExecLegacyContent`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgExecLegacyContent {
+    #[prost(message, optional, tag = "1")]
+    pub content: Option<Any>,
+    #[prost(string, tag = "2")]
+    pub authority: String,
+}
+
+/// `google.protobuf.Timestamp`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Timestamp {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+/// `cosmos.authz.v1beta1.MsgExec`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgExec {
+    #[prost(string, tag = "1")]
+    pub grantee: String,
+    #[prost(message, repeated, tag = "2")]
+    pub msgs: Vec<Any>,
+}
+
+/// `cosmos.authz.v1beta1.Grant`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Grant {
+    #[prost(message, optional, tag = "1")]
+    pub authorization: Option<Any>,
+    #[prost(message, optional, tag = "2")]
+    pub expiration: Option<Timestamp>,
+}
+
+/// `cosmos.authz.v1beta1.GenericAuthorization`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GenericAuthorization {
+    #[prost(string, tag = "1")]
+    pub msg: String,
+}
+
+/// `cosmos.authz.v1beta1.MsgGrant`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgGrant {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+    #[prost(message, optional, tag = "3")]
+    pub grant: Option<Grant>,
+}
+
+/// `cosmos.authz.v1beta1.MsgRevoke`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgRevoke {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+    #[prost(string, tag = "3")]
+    pub msg_type_url: String,
+}
+
+/// `cosmwasm.wasm.v1.AccessConfig`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AccessConfig {
+    #[prost(int32, tag = "1")]
+    pub permission: i32,
+    #[prost(string, repeated, tag = "3")]
+    pub addresses: Vec<String>,
+}
+
+/// `cosmwasm.wasm.v1.MsgStoreCode`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgStoreCode {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub wasm_byte_code: Vec<u8>,
+    #[prost(message, optional, tag = "5")]
+    pub instantiate_permission: Option<AccessConfig>,
+}
+
+/// `cosmwasm.wasm.v1.MsgInstantiateContract`, and `MsgInstantiateContract2` with `salt` and `fix_msg`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgInstantiateContract {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub admin: String,
+    #[prost(uint64, tag = "3")]
+    pub code_id: u64,
+    #[prost(string, tag = "4")]
+    pub label: String,
+    #[prost(bytes = "vec", tag = "5")]
+    pub msg: Vec<u8>,
+    #[prost(message, repeated, tag = "6")]
+    pub funds: Vec<Coin>,
+    #[prost(bytes = "vec", tag = "7")]
+    pub salt: Vec<u8>,
+    #[prost(bool, tag = "8")]
+    pub fix_msg: bool,
+}
+
+/// `cosmwasm.wasm.v1.MsgExecuteContract`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgExecuteContract {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub contract: String,
+    #[prost(bytes = "vec", tag = "3")]
+    pub msg: Vec<u8>,
+    #[prost(message, repeated, tag = "5")]
+    pub funds: Vec<Coin>,
+}
+
+/// `cosmwasm.wasm.v1.MsgMigrateContract`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgMigrateContract {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub contract: String,
+    #[prost(uint64, tag = "3")]
+    pub code_id: u64,
+    #[prost(bytes = "vec", tag = "4")]
+    pub msg: Vec<u8>,
+}
+
+/// `cosmwasm.wasm.v1.MsgUpdateAdmin`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgUpdateAdmin {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub new_admin: String,
+    #[prost(string, tag = "3")]
+    pub contract: String,
+}
+
+/// `ibc.applications.transfer.v1.MsgTransfer`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgTransfer {
+    #[prost(string, tag = "1")]
+    pub source_port: String,
+    #[prost(string, tag = "2")]
+    pub source_channel: String,
+    #[prost(message, optional, tag = "3")]
+    pub token: Option<Coin>,
+    #[prost(string, tag = "4")]
+    pub sender: String,
+    #[prost(string, tag = "5")]
+    pub receiver: String,
+    #[prost(message, optional, tag = "6")]
+    pub timeout_height: Option<Height>,
+    #[prost(uint64, tag = "7")]
+    pub timeout_timestamp: u64,
+    #[prost(string, tag = "8")]
+    pub memo: String,
+}
+
+/// `ibc.core.channel.v1.Packet`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Packet {
+    #[prost(uint64, tag = "1")]
+    pub sequence: u64,
+    #[prost(string, tag = "2")]
+    pub source_port: String,
+    #[prost(string, tag = "3")]
+    pub source_channel: String,
+    #[prost(string, tag = "4")]
+    pub destination_port: String,
+    #[prost(string, tag = "5")]
+    pub destination_channel: String,
+    #[prost(bytes = "vec", tag = "6")]
+    pub data: Vec<u8>,
+    #[prost(message, optional, tag = "7")]
+    pub timeout_height: Option<Height>,
+    #[prost(uint64, tag = "8")]
+    pub timeout_timestamp: u64,
+}
+
+/// `ibc.core.channel.v1.MsgRecvPacket`; proofs are not kept.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgRecvPacket {
+    #[prost(message, optional, tag = "1")]
+    pub packet: Option<Packet>,
+    #[prost(string, tag = "4")]
+    pub signer: String,
+}
+
+/// `ibc.core.channel.v1.MsgAcknowledgement`; proofs are not kept.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgAcknowledgement {
+    #[prost(message, optional, tag = "1")]
+    pub packet: Option<Packet>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub acknowledgement: Vec<u8>,
+    #[prost(string, tag = "5")]
+    pub signer: String,
+}
+
+/// `ibc.core.channel.v1.MsgTimeout`; proofs are not kept.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgTimeout {
+    #[prost(message, optional, tag = "1")]
+    pub packet: Option<Packet>,
+    #[prost(uint64, tag = "4")]
+    pub next_sequence_recv: u64,
+    #[prost(string, tag = "5")]
+    pub signer: String,
+}
+
+/// `ibc.core.client.v1.MsgUpdateClient`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgUpdateClient {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+    #[prost(message, optional, tag = "2")]
+    pub client_message: Option<Any>,
+    #[prost(string, tag = "3")]
+    pub signer: String,
+}
+
+/// `ibc.core.channel.v1.Channel`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Channel {
+    #[prost(int32, tag = "1")]
+    pub state: i32,
+    #[prost(int32, tag = "2")]
+    pub ordering: i32,
+    #[prost(message, optional, tag = "3")]
+    pub counterparty: Option<ChannelCounterparty>,
+    #[prost(string, repeated, tag = "4")]
+    pub connection_hops: Vec<String>,
+    #[prost(string, tag = "5")]
+    pub version: String,
+}
+
+/// `ibc.core.channel.v1.Counterparty`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ChannelCounterparty {
+    #[prost(string, tag = "1")]
+    pub port_id: String,
+    #[prost(string, tag = "2")]
+    pub channel_id: String,
+}
+
+/// `ibc.core.channel.v1.MsgChannelOpenInit`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgChannelOpenInit {
+    #[prost(string, tag = "1")]
+    pub port_id: String,
+    #[prost(message, optional, tag = "2")]
+    pub channel: Option<Channel>,
+    #[prost(string, tag = "3")]
+    pub signer: String,
+}
+
+/// `ibc.core.channel.v1.MsgChannelOpenTry`; proofs are not kept.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgChannelOpenTry {
+    #[prost(string, tag = "1")]
+    pub port_id: String,
+    #[prost(string, tag = "2")]
+    pub previous_channel_id: String,
+    #[prost(message, optional, tag = "3")]
+    pub channel: Option<Channel>,
+    #[prost(string, tag = "4")]
+    pub counterparty_version: String,
+    #[prost(string, tag = "7")]
+    pub signer: String,
+}
+
+/// `ibc.core.channel.v1.MsgChannelOpenAck`; proofs are not kept.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgChannelOpenAck {
+    #[prost(string, tag = "1")]
+    pub port_id: String,
+    #[prost(string, tag = "2")]
+    pub channel_id: String,
+    #[prost(string, tag = "3")]
+    pub counterparty_channel_id: String,
+    #[prost(string, tag = "4")]
+    pub counterparty_version: String,
+    #[prost(string, tag = "7")]
+    pub signer: String,
+}
+
+/// `ibc.core.channel.v1.MsgChannelOpenConfirm` and `MsgChannelCloseConfirm`, which share the fields we keep.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgChannelConfirm {
+    #[prost(string, tag = "1")]
+    pub port_id: String,
+    #[prost(string, tag = "2")]
+    pub channel_id: String,
+    #[prost(string, tag = "5")]
+    pub signer: String,
+}
+
+/// `ibc.core.channel.v1.MsgChannelCloseInit`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgChannelCloseInit {
+    #[prost(string, tag = "1")]
+    pub port_id: String,
+    #[prost(string, tag = "2")]
+    pub channel_id: String,
+    #[prost(string, tag = "3")]
+    pub signer: String,
+}
+
+/// `ibc.core.connection.v1.Counterparty`; the commitment prefix is not kept.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ConnectionCounterparty {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+    #[prost(string, tag = "2")]
+    pub connection_id: String,
+}
+
+/// `ibc.core.connection.v1.MsgConnectionOpenInit`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgConnectionOpenInit {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+    #[prost(message, optional, tag = "2")]
+    pub counterparty: Option<ConnectionCounterparty>,
+    #[prost(uint64, tag = "4")]
+    pub delay_period: u64,
+    #[prost(string, tag = "5")]
+    pub signer: String,
+}
+
+/// `ibc.core.connection.v1.MsgConnectionOpenTry`; client state and proofs are not kept.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgConnectionOpenTry {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+    #[prost(message, optional, tag = "4")]
+    pub counterparty: Option<ConnectionCounterparty>,
+    #[prost(uint64, tag = "5")]
+    pub delay_period: u64,
+    #[prost(string, tag = "12")]
+    pub signer: String,
+}
+
+/// `ibc.core.connection.v1.MsgConnectionOpenAck`; client state and proofs are not kept.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgConnectionOpenAck {
+    #[prost(string, tag = "1")]
+    pub connection_id: String,
+    #[prost(string, tag = "2")]
+    pub counterparty_connection_id: String,
+    #[prost(string, tag = "10")]
+    pub signer: String,
+}
+
+/// `ibc.core.connection.v1.MsgConnectionOpenConfirm`; proofs are not kept.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgConnectionOpenConfirm {
+    #[prost(string, tag = "1")]
+    pub connection_id: String,
+    #[prost(string, tag = "4")]
+    pub signer: String,
+}
+
+impl From<Packet> for ibc::Packet {
+    fn from(packet: Packet) -> Self {
+        let data = STANDARD.encode(&packet.data);
+        ibc::Packet {
+            sequence: packet.sequence.to_string(),
+            source_port: packet.source_port,
+            source_channel: packet.source_channel,
+            destination_port: packet.destination_port,
+            destination_channel: packet.destination_channel,
+            transfer: ibc::FungibleTokenPacketData::decode(&data),
+            data,
+            timeout_height: packet.timeout_height.unwrap_or_default().into(),
+            timeout_timestamp: packet.timeout_timestamp.to_string(),
+        }
+    }
+}
+
+impl From<Channel> for ibc::ChannelEnd {
+    fn from(channel: Channel) -> Self {
+        let counterparty = channel.counterparty.unwrap_or_default();
+        ibc::ChannelEnd {
+            state: enum_name(&["STATE_UNINITIALIZED_UNSPECIFIED", "STATE_INIT", "STATE_TRYOPEN", "STATE_OPEN", "STATE_CLOSED"], channel.state),
+            ordering: enum_name(&["ORDER_NONE_UNSPECIFIED", "ORDER_UNORDERED", "ORDER_ORDERED"], channel.ordering),
+            counterparty: ibc::ChannelCounterparty { port_id: counterparty.port_id, channel_id: counterparty.channel_id },
+            connection_hops: channel.connection_hops,
+            version: channel.version,
+        }
+    }
+}
+
+impl From<ConnectionCounterparty> for ibc::ConnectionCounterparty {
+    fn from(counterparty: ConnectionCounterparty) -> Self {
+        ibc::ConnectionCounterparty { client_id: counterparty.client_id, connection_id: counterparty.connection_id }
+    }
+}
+
+/// The JSON name of a protobuf enum value, or the number itself when it is out of range.
+fn enum_name(names: &[&str], value: i32) -> String {
+    usize::try_from(value).ok()
+        .and_then(|index| names.get(index))
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| value.to_string())
+}
+
+impl From<Coin> for Amount {
+    fn from(coin: Coin) -> Self {
+        Amount { denom: coin.denom, amount: coin.amount }
+    }
+}
+
+impl From<Height> for TimeoutHeight {
+    fn from(height: Height) -> Self {
+        TimeoutHeight {
+            revision_number: height.revision_number.to_string(),
+            revision_height: height.revision_height.to_string(),
+        }
+    }
+}
+
+impl From<WeightedOption> for WeightedVoteOption {
+    fn from(option: WeightedOption) -> Self {
+        WeightedVoteOption { option: vote_option_name(option.option), weight: legacy_dec(&option.weight) }
+    }
+}
+
+impl From<Grant> for message::Grant {
+    fn from(grant: Grant) -> Self {
+        message::Grant {
+            authorization: grant.authorization.map(|authorization| authorization.authorization_json()).unwrap_or_default(),
+            expiration: grant.expiration.and_then(|time| {
+                let time = Utc.timestamp_opt(time.seconds, time.nanos as u32).single()?;
+                Some(time.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }),
+        }
+    }
+}
+
+impl From<AccessConfig> for Value {
+    fn from(config: AccessConfig) -> Self {
+        let names = ["ACCESS_TYPE_UNSPECIFIED", "ACCESS_TYPE_NOBODY", "ACCESS_TYPE_ONLY_ADDRESS", "ACCESS_TYPE_EVERYBODY", "ACCESS_TYPE_ANY_OF_ADDRESSES"];
+        json!({ "permission": enum_name(&names, config.permission), "addresses": config.addresses })
+    }
+}
+
+/// Contract messages are JSON bytes; anything else is kept base64 encoded.
+fn contract_msg_json(msg: &[u8]) -> Value {
+    serde_json::from_slice(msg).unwrap_or_else(|_| Value::String(STANDARD.encode(msg)))
+}
+
+/// Renders a `LegacyDec` the way the LCD does: on the wire it is the integer scaled by 10^18, so
+/// `700000000000000000` becomes `0.700000000000000000`.
+fn legacy_dec(scaled: &str) -> String {
+    if scaled.is_empty() || !scaled.bytes().all(|byte| byte.is_ascii_digit()) {
+        return scaled.to_string();
+    }
+    let digits = format!("{:0>19}", scaled);
+    let (int, frac) = digits.split_at(digits.len() - 18);
+    format!("{}.{}", int, frac)
+}
+
+/// The JSON name of a `cosmos.gov.v1.VoteOption` value.
+fn vote_option_name(option: i32) -> String {
+    enum_name(&["VOTE_OPTION_UNSPECIFIED", "VOTE_OPTION_YES", "VOTE_OPTION_ABSTAIN", "VOTE_OPTION_NO", "VOTE_OPTION_NO_WITH_VETO"], option)
+}
+
+impl Any {
+    /// Renders an `Any` we can't decode into a JSON value: its type URL and base64 encoded bytes.
+    fn to_json(&self) -> Value {
+        json!({ "@type": self.type_url, "value": STANDARD.encode(&self.value) })
+    }
+
+    /// Renders an authz authorization, decoding `GenericAuthorization` as the LCD does.
+    fn authorization_json(&self) -> Value {
+        match self.type_url.as_str() {
+            "/cosmos.authz.v1beta1.GenericAuthorization" => match GenericAuthorization::decode(self.value.as_slice()) {
+                Ok(authorization) => json!({ "@type": self.type_url, "msg": authorization.msg }),
+                Err(_) => self.to_json(),
+            },
+            _ => self.to_json(),
+        }
+    }
+
+    /// Decodes the payload into the matching `Message` variant.
+    ///
+    /// Types we don't model, and payloads that fail to decode, are kept as `Message::Undecoded`
+    /// with their type URL and base64 encoded bytes.
+    pub fn to_message(&self) -> Message {
+        let decoded = match self.type_url.as_str() {
+            "/cosmos.bank.v1beta1.MsgSend" => MsgSend::decode(self.value.as_slice()).ok().map(|msg| Message::MsgSend {
+                from_address: msg.from_address,
+                to_address: msg.to_address,
+                amount: msg.amount.into_iter().map(Amount::from).collect(),
+            }),
+            "/cosmos.staking.v1beta1.MsgDelegate" => MsgDelegate::decode(self.value.as_slice()).ok().map(|msg| Message::MsgDelegate {
+                delegator_address: msg.delegator_address,
+                validator_address: msg.validator_address,
+                amount: msg.amount.unwrap_or_default().into(),
+            }),
+            "/cosmos.staking.v1beta1.MsgUndelegate" => MsgUndelegate::decode(self.value.as_slice()).ok().map(|msg| Message::MsgUndelegate {
+                delegator_address: msg.delegator_address,
+                validator_address: msg.validator_address,
+                amount: msg.amount.unwrap_or_default().into(),
+            }),
+            "/cosmos.staking.v1beta1.MsgBeginRedelegate" => MsgBeginRedelegate::decode(self.value.as_slice()).ok().map(|msg| Message::MsgBeginRedelegate {
+                delegator_address: msg.delegator_address,
+                validator_src_address: msg.validator_src_address,
+                validator_dst_address: msg.validator_dst_address,
+                amount: msg.amount.unwrap_or_default().into(),
+            }),
+            "/cosmos.staking.v1beta1.MsgCancelUnbondingDelegation" => MsgCancelUnbondingDelegation::decode(self.value.as_slice()).ok().map(|msg| Message::MsgCancelUnbondingDelegation {
+                delegator_address: msg.delegator_address,
+                validator_address: msg.validator_address,
+                amount: msg.amount.unwrap_or_default().into(),
+                creation_height: msg.creation_height.to_string(),
+            }),
+            "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward" => MsgWithdrawDelegatorReward::decode(self.value.as_slice()).ok().map(|msg| Message::MsgWithdrawDelegatorReward {
+                delegator_address: msg.delegator_address,
+                validator_address: msg.validator_address,
+            }),
+            "/cosmos.distribution.v1beta1.MsgWithdrawValidatorCommission" => MsgWithdrawValidatorCommission::decode(self.value.as_slice()).ok().map(|msg| Message::MsgWithdrawValidatorCommission {
+                validator_address: msg.validator_address,
+            }),
+            "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress" => MsgSetWithdrawAddress::decode(self.value.as_slice()).ok().map(|msg| Message::MsgSetWithdrawAddress {
+                delegator_address: msg.delegator_address,
+                withdraw_address: msg.withdraw_address,
+            }),
+            "/cosmos.distribution.v1beta1.MsgFundCommunityPool" => MsgFundCommunityPool::decode(self.value.as_slice()).ok().map(|msg| Message::MsgFundCommunityPool {
+                amount: msg.amount.into_iter().map(Amount::from).collect(),
+                depositor: msg.depositor,
+            }),
+            "/cosmos.gov.v1beta1.MsgSubmitProposal" => MsgSubmitProposal::decode(self.value.as_slice()).ok().map(|msg| Message::MsgSubmitProposal {
+                content: msg.content.map(|content| content.to_json()).unwrap_or_default(),
+                initial_deposit: msg.initial_deposit.into_iter().map(Amount::from).collect(),
+                proposer: msg.proposer,
+            }),
+            "/cosmos.gov.v1.MsgSubmitProposal" => MsgSubmitProposalV1::decode(self.value.as_slice()).ok().map(|msg| Message::MsgSubmitProposalV1 {
+                messages: msg.messages.iter().map(Any::to_message).collect(),
+                initial_deposit: msg.initial_deposit.into_iter().map(Amount::from).collect(),
+                proposer: msg.proposer,
+                metadata: msg.metadata,
+                title: msg.title,
+                summary: msg.summary,
+                expedited: msg.expedited,
+            }),
+            "/cosmos.gov.v1beta1.MsgVote" => MsgVote::decode(self.value.as_slice()).ok().map(|msg| Message::MsgVote {
+                proposal_id: msg.proposal_id.to_string(),
+                voter: msg.voter,
+                option: vote_option_name(msg.option),
+            }),
+            "/cosmos.gov.v1.MsgVote" => MsgVote::decode(self.value.as_slice()).ok().map(|msg| Message::MsgVoteV1 {
+                proposal_id: msg.proposal_id.to_string(),
+                voter: msg.voter,
+                option: vote_option_name(msg.option),
+                metadata: msg.metadata,
+            }),
+            "/cosmos.gov.v1beta1.MsgVoteWeighted" => MsgVoteWeighted::decode(self.value.as_slice()).ok().map(|msg| Message::MsgVoteWeighted {
+                proposal_id: msg.proposal_id.to_string(),
+                voter: msg.voter,
+                options: msg.options.into_iter().map(WeightedVoteOption::from).collect(),
+            }),
+            "/cosmos.gov.v1.MsgVoteWeighted" => MsgVoteWeighted::decode(self.value.as_slice()).ok().map(|msg| Message::MsgVoteWeightedV1 {
+                proposal_id: msg.proposal_id.to_string(),
+                voter: msg.voter,
+                options: msg.options.into_iter().map(WeightedVoteOption::from).collect(),
+                metadata: msg.metadata,
+            }),
+            "/cosmos.gov.v1beta1.MsgDeposit" => MsgDeposit::decode(self.value.as_slice()).ok().map(|msg| Message::MsgDeposit {
+                proposal_id: msg.proposal_id.to_string(),
+                depositor: msg.depositor,
+                amount: msg.amount.into_iter().map(Amount::from).collect(),
+            }),
+            "/cosmos.gov.v1.MsgDeposit" => MsgDeposit::decode(self.value.as_slice()).ok().map(|msg| Message::MsgDepositV1 {
+                proposal_id: msg.proposal_id.to_string(),
+                depositor: msg.depositor,
+                amount: msg.amount.into_iter().map(Amount::from).collect(),
+            }),
+            "/cosmos.gov.v1.MsgExecLegacyContent" => MsgExecLegacyContent::decode(self.value.as_slice()).ok().map(|msg| Message::MsgExecLegacyContent {
+                content: msg.content.map(|content| content.to_json()).unwrap_or_default(),
+                authority: msg.authority,
+            }),
+            "/cosmos.authz.v1beta1.MsgExec" => MsgExec::decode(self.value.as_slice()).ok().map(|msg| Message::MsgExec {
+                grantee: msg.grantee,
+                msgs: msg.msgs.iter().map(Any::to_message).collect(),
+            }),
+            "/cosmos.authz.v1beta1.MsgGrant" => MsgGrant::decode(self.value.as_slice()).ok().map(|msg| Message::MsgGrant {
+                granter: msg.granter,
+                grantee: msg.grantee,
+                grant: msg.grant.unwrap_or_default().into(),
+            }),
+            "/cosmos.authz.v1beta1.MsgRevoke" => MsgRevoke::decode(self.value.as_slice()).ok().map(|msg| Message::MsgRevoke {
+                granter: msg.granter,
+                grantee: msg.grantee,
+                msg_type_url: msg.msg_type_url,
+            }),
+            "/cosmwasm.wasm.v1.MsgStoreCode" => MsgStoreCode::decode(self.value.as_slice()).ok().map(|msg| Message::MsgStoreCode {
+                sender: msg.sender,
+                wasm_byte_code: STANDARD.encode(&msg.wasm_byte_code),
+                instantiate_permission: msg.instantiate_permission.map(Value::from),
+            }),
+            "/cosmwasm.wasm.v1.MsgInstantiateContract" => MsgInstantiateContract::decode(self.value.as_slice()).ok().map(|msg| Message::MsgInstantiateContract {
+                sender: msg.sender,
+                admin: msg.admin,
+                code_id: msg.code_id.to_string(),
+                label: msg.label,
+                msg: contract_msg_json(&msg.msg),
+                funds: msg.funds.into_iter().map(Amount::from).collect(),
+            }),
+            "/cosmwasm.wasm.v1.MsgInstantiateContract2" => MsgInstantiateContract::decode(self.value.as_slice()).ok().map(|msg| Message::MsgInstantiateContract2 {
+                sender: msg.sender,
+                admin: msg.admin,
+                code_id: msg.code_id.to_string(),
+                label: msg.label,
+                msg: contract_msg_json(&msg.msg),
+                funds: msg.funds.into_iter().map(Amount::from).collect(),
+                salt: STANDARD.encode(&msg.salt),
+                fix_msg: msg.fix_msg,
+            }),
+            "/cosmwasm.wasm.v1.MsgExecuteContract" => MsgExecuteContract::decode(self.value.as_slice()).ok().map(|msg| Message::MsgExecuteContract {
+                sender: msg.sender,
+                contract: msg.contract,
+                msg: contract_msg_json(&msg.msg),
+                funds: msg.funds.into_iter().map(Amount::from).collect(),
+            }),
+            "/cosmwasm.wasm.v1.MsgMigrateContract" => MsgMigrateContract::decode(self.value.as_slice()).ok().map(|msg| Message::MsgMigrateContract {
+                sender: msg.sender,
+                contract: msg.contract,
+                code_id: msg.code_id.to_string(),
+                msg: contract_msg_json(&msg.msg),
+            }),
+            "/cosmwasm.wasm.v1.MsgUpdateAdmin" => MsgUpdateAdmin::decode(self.value.as_slice()).ok().map(|msg| Message::MsgUpdateAdmin {
+                sender: msg.sender,
+                new_admin: msg.new_admin,
+                contract: msg.contract,
+            }),
+            "/ibc.applications.transfer.v1.MsgTransfer" => MsgTransfer::decode(self.value.as_slice()).ok().map(|msg| Message::MsgTransfer {
+                source_port: msg.source_port,
+                source_channel: msg.source_channel,
+                token: msg.token.unwrap_or_default().into(),
+                sender: msg.sender,
+                receiver: msg.receiver,
+                timeout_height: msg.timeout_height.unwrap_or_default().into(),
+                timeout_timestamp: msg.timeout_timestamp.to_string(),
+                memo: msg.memo,
+            }),
+            "/ibc.core.channel.v1.MsgRecvPacket" => MsgRecvPacket::decode(self.value.as_slice()).ok().map(|msg| Message::MsgRecvPacket {
+                packet: msg.packet.unwrap_or_default().into(),
+                signer: msg.signer,
+            }),
+            "/ibc.core.channel.v1.MsgAcknowledgement" => MsgAcknowledgement::decode(self.value.as_slice()).ok().map(|msg| Message::MsgAcknowledgement {
+                packet: msg.packet.unwrap_or_default().into(),
+                acknowledgement: STANDARD.encode(msg.acknowledgement),
+                signer: msg.signer,
+            }),
+            "/ibc.core.channel.v1.MsgTimeout" => MsgTimeout::decode(self.value.as_slice()).ok().map(|msg| Message::MsgTimeout {
+                packet: msg.packet.unwrap_or_default().into(),
+                next_sequence_recv: msg.next_sequence_recv.to_string(),
+                signer: msg.signer,
+            }),
+            "/ibc.core.client.v1.MsgUpdateClient" => MsgUpdateClient::decode(self.value.as_slice()).ok().map(|msg| Message::MsgUpdateClient {
+                client_id: msg.client_id,
+                client_message: msg.client_message.map(|header| header.to_json()).unwrap_or_default(),
+                signer: msg.signer,
+            }),
+            "/ibc.core.channel.v1.MsgChannelOpenInit" => MsgChannelOpenInit::decode(self.value.as_slice()).ok().map(|msg| Message::MsgChannelOpenInit {
+                port_id: msg.port_id,
+                channel: msg.channel.unwrap_or_default().into(),
+                signer: msg.signer,
+            }),
+            "/ibc.core.channel.v1.MsgChannelOpenTry" => MsgChannelOpenTry::decode(self.value.as_slice()).ok().map(|msg| Message::MsgChannelOpenTry {
+                port_id: msg.port_id,
+                previous_channel_id: msg.previous_channel_id,
+                channel: msg.channel.unwrap_or_default().into(),
+                counterparty_version: msg.counterparty_version,
+                signer: msg.signer,
+            }),
+            "/ibc.core.channel.v1.MsgChannelOpenAck" => MsgChannelOpenAck::decode(self.value.as_slice()).ok().map(|msg| Message::MsgChannelOpenAck {
+                port_id: msg.port_id,
+                channel_id: msg.channel_id,
+                counterparty_channel_id: msg.counterparty_channel_id,
+                counterparty_version: msg.counterparty_version,
+                signer: msg.signer,
+            }),
+            "/ibc.core.channel.v1.MsgChannelOpenConfirm" => MsgChannelConfirm::decode(self.value.as_slice()).ok().map(|msg| Message::MsgChannelOpenConfirm {
+                port_id: msg.port_id,
+                channel_id: msg.channel_id,
+                signer: msg.signer,
+            }),
+            "/ibc.core.channel.v1.MsgChannelCloseInit" => MsgChannelCloseInit::decode(self.value.as_slice()).ok().map(|msg| Message::MsgChannelCloseInit {
+                port_id: msg.port_id,
+                channel_id: msg.channel_id,
+                signer: msg.signer,
+            }),
+            "/ibc.core.channel.v1.MsgChannelCloseConfirm" => MsgChannelConfirm::decode(self.value.as_slice()).ok().map(|msg| Message::MsgChannelCloseConfirm {
+                port_id: msg.port_id,
+                channel_id: msg.channel_id,
+                signer: msg.signer,
+            }),
+            "/ibc.core.connection.v1.MsgConnectionOpenInit" => MsgConnectionOpenInit::decode(self.value.as_slice()).ok().map(|msg| Message::MsgConnectionOpenInit {
+                client_id: msg.client_id,
+                counterparty: msg.counterparty.unwrap_or_default().into(),
+                delay_period: msg.delay_period.to_string(),
+                signer: msg.signer,
+            }),
+            "/ibc.core.connection.v1.MsgConnectionOpenTry" => MsgConnectionOpenTry::decode(self.value.as_slice()).ok().map(|msg| Message::MsgConnectionOpenTry {
+                client_id: msg.client_id,
+                counterparty: msg.counterparty.unwrap_or_default().into(),
+                delay_period: msg.delay_period.to_string(),
+                signer: msg.signer,
+            }),
+            "/ibc.core.connection.v1.MsgConnectionOpenAck" => MsgConnectionOpenAck::decode(self.value.as_slice()).ok().map(|msg| Message::MsgConnectionOpenAck {
+                connection_id: msg.connection_id,
+                counterparty_connection_id: msg.counterparty_connection_id,
+                signer: msg.signer,
+            }),
+            "/ibc.core.connection.v1.MsgConnectionOpenConfirm" => MsgConnectionOpenConfirm::decode(self.value.as_slice()).ok().map(|msg| Message::MsgConnectionOpenConfirm {
+                connection_id: msg.connection_id,
+                signer: msg.signer,
+            }),
+            _ => None,
+        };
+
+        decoded.unwrap_or_else(|| Message::Undecoded {
+            type_url: self.type_url.clone(),
+            value: STANDARD.encode(&self.value),
+        })
+    }
+}
+
+impl From<AuthInfo> for auth::AuthInfo {
+    fn from(auth_info: AuthInfo) -> Self {
+        let fee = auth_info.fee.unwrap_or_default();
+        auth::AuthInfo {
+            signer_infos: auth_info.signer_infos.into_iter().map(auth::SignerInfo::from).collect(),
+            fee: auth::Fee {
+                amount: fee.amount.into_iter().map(Amount::from).collect(),
+                gas_limit: fee.gas_limit.to_string(),
+                payer: fee.payer,
+                granter: fee.granter,
+            },
+        }
+    }
+}
+
+impl From<SignerInfo> for auth::SignerInfo {
+    fn from(info: SignerInfo) -> Self {
+        auth::SignerInfo {
+            public_key: info.public_key.map(|key| auth::PublicKey {
+                key: PubKey::decode(key.value.as_slice()).ok()
+                    .filter(|_| key.type_url.ends_with(".PubKey"))
+                    .map(|pub_key| STANDARD.encode(pub_key.key)),
+                type_url: key.type_url,
+                extra: Default::default(),
+            }),
+            mode_info: info.mode_info.map(auth::ModeInfo::from).unwrap_or_default(),
+            sequence: info.sequence.to_string(),
+        }
+    }
+}
+
+impl From<ModeInfo> for auth::ModeInfo {
+    fn from(mode_info: ModeInfo) -> Self {
+        match mode_info.sum {
+            Some(mode_info::Sum::Single(single)) => auth::ModeInfo {
+                single: Some(auth::SingleMode { mode: sign_mode_name(single.mode) }),
+                multi: None,
+            },
+            Some(mode_info::Sum::Multi(multi)) => {
+                let bitarray = multi.bitarray.unwrap_or_default();
+                auth::ModeInfo {
+                    single: None,
+                    multi: Some(auth::MultiMode {
+                        bitarray: json!({ "extra_bits_stored": bitarray.extra_bits_stored, "elems": STANDARD.encode(bitarray.elems) }),
+                        mode_infos: multi.mode_infos.into_iter().map(auth::ModeInfo::from).collect(),
+                    }),
+                }
+            }
+            None => auth::ModeInfo::default(),
+        }
+    }
+}
+
+/// `cosmos.tx.signing.v1beta1.SignMode`, whose values are not contiguous.
+fn sign_mode_name(mode: i32) -> String {
+    match mode {
+        0 => "SIGN_MODE_UNSPECIFIED".to_string(),
+        1 => "SIGN_MODE_DIRECT".to_string(),
+        2 => "SIGN_MODE_TEXTUAL".to_string(),
+        3 => "SIGN_MODE_DIRECT_AUX".to_string(),
+        127 => "SIGN_MODE_LEGACY_AMINO_JSON".to_string(),
+        191 => "SIGN_MODE_EIP_191".to_string(),
+        other => other.to_string(),
+    }
+}
+
+impl TxRaw {
+    /// Decodes the envelope and its body from raw tx bytes.
+    pub fn decode_with_body(bytes: &[u8]) -> Result<(TxRaw, TxBody), prost::DecodeError> {
+        let raw = TxRaw::decode(bytes)?;
+        let body = TxBody::decode(raw.body_bytes.as_slice())?;
+        Ok((raw, body))
+    }
+}
+
+/// Decodes raw tx bytes into the `Tx` the LCD would return for them.
+pub fn decode_tx(bytes: &[u8]) -> Result<Tx, TranslationError> {
+    let (raw, body) = TxRaw::decode_with_body(bytes).map_err(TranslationError::TxDecodeError)?;
+    let auth_info = AuthInfo::decode(raw.auth_info_bytes.as_slice()).map_err(TranslationError::TxDecodeError)?;
+
+    Ok(Tx {
+        body: Body {
+            messages: body.messages.iter().map(Any::to_message).collect(),
+            memo: body.memo,
+            timeout_height: body.timeout_height.to_string(),
+        },
+        auth_info: auth_info.into(),
+        signatures: raw.signatures.iter().map(|signature| STANDARD.encode(signature)).collect(),
+    })
+}
+
+/// Checks a reported tx hash against the hash of the raw bytes, ignoring case and a `0x` prefix.
+pub fn verify_tx_hash(bytes: &[u8], reported: &str) -> Result<(), TranslationError> {
+    let computed = tx_hash(bytes);
+    let reported = reported.trim_start_matches("0x").to_uppercase();
+    if reported == computed {
+        Ok(())
+    } else {
+        Err(TranslationError::TxHashMismatch { reported, computed })
+    }
+}
+
+/// The transaction hash as reported by the LCD: upper-case hex SHA-256 of the raw tx bytes.
+pub fn tx_hash(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_with_body_round_trip() {
+        let body = TxBody {
+            messages: vec![Any { type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(), value: vec![1, 2, 3] }],
+            memo: "memo".to_string(),
+            timeout_height: 42,
+        };
+        let raw = TxRaw { body_bytes: body.encode_to_vec(), auth_info_bytes: vec![], signatures: vec![vec![9; 64]] };
+
+        let (decoded_raw, decoded_body) = TxRaw::decode_with_body(&raw.encode_to_vec()).unwrap();
+
+        assert_eq!(decoded_raw, raw);
+        assert_eq!(decoded_body, body);
+    }
+
+    #[test]
+    fn test_decode_tx_types_known_messages() {
+        let send = MsgSend {
+            from_address: "cosmos1from".to_string(),
+            to_address: "cosmos1to".to_string(),
+            amount: vec![Coin { denom: "uatom".to_string(), amount: "5".to_string() }],
+        };
+        let body = TxBody {
+            messages: vec![
+                Any { type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(), value: send.encode_to_vec() },
+                Any { type_url: "/osmosis.gamm.v1beta1.MsgSwapExactAmountIn".to_string(), value: vec![8, 1] },
+            ],
+            memo: String::new(),
+            timeout_height: 0,
+        };
+        let auth_info = AuthInfo {
+            signer_infos: vec![SignerInfo {
+                public_key: Some(Any { type_url: "/cosmos.crypto.secp256k1.PubKey".to_string(), value: PubKey { key: vec![2; 33] }.encode_to_vec() }),
+                mode_info: Some(ModeInfo { sum: Some(mode_info::Sum::Single(mode_info::Single { mode: 127 })) }),
+                sequence: 4,
+            }],
+            fee: Some(Fee { amount: vec![], gas_limit: 200_000, payer: String::new(), granter: String::new() }),
+        };
+        let raw = TxRaw { body_bytes: body.encode_to_vec(), auth_info_bytes: auth_info.encode_to_vec(), signatures: vec![] };
+
+        let tx = decode_tx(&raw.encode_to_vec()).unwrap();
+
+        assert_eq!(tx.body.messages[0], Message::MsgSend {
+            from_address: "cosmos1from".to_string(),
+            to_address: "cosmos1to".to_string(),
+            amount: vec![Amount { denom: "uatom".to_string(), amount: "5".to_string() }],
+        });
+        assert_eq!(tx.body.messages[1], Message::Undecoded { type_url: "/osmosis.gamm.v1beta1.MsgSwapExactAmountIn".to_string(), value: "CAE=".to_string() });
+        assert_eq!(tx.auth_info.fee.gas_limit(), 200_000);
+        assert_eq!(tx.auth_info.signer_infos[0].to_string(), format!("secp256k1 {} seq 4 SIGN_MODE_LEGACY_AMINO_JSON", STANDARD.encode([2; 33])));
+    }
+
+    #[test]
+    fn test_to_message_staking() {
+        let redelegate = MsgBeginRedelegate {
+            delegator_address: "cosmos1delegator".to_string(),
+            validator_src_address: "cosmosvaloper1src".to_string(),
+            validator_dst_address: "cosmosvaloper1dst".to_string(),
+            amount: Some(Coin { denom: "uatom".to_string(), amount: "10".to_string() }),
+        };
+        let any = Any { type_url: "/cosmos.staking.v1beta1.MsgBeginRedelegate".to_string(), value: redelegate.encode_to_vec() };
+
+        assert_eq!(any.to_message(), Message::MsgBeginRedelegate {
+            delegator_address: "cosmos1delegator".to_string(),
+            validator_src_address: "cosmosvaloper1src".to_string(),
+            validator_dst_address: "cosmosvaloper1dst".to_string(),
+            amount: Amount { denom: "uatom".to_string(), amount: "10".to_string() },
+        });
+    }
+
+    #[test]
+    fn test_to_message_authz() {
+        let delegate = MsgDelegate {
+            delegator_address: "cosmos1granter".to_string(),
+            validator_address: "cosmosvaloper1abc".to_string(),
+            amount: Some(Coin { denom: "uatom".to_string(), amount: "10".to_string() }),
+        };
+        let exec = MsgExec {
+            grantee: "cosmos1restake".to_string(),
+            msgs: vec![Any { type_url: "/cosmos.staking.v1beta1.MsgDelegate".to_string(), value: delegate.encode_to_vec() }],
+        };
+        let any = Any { type_url: "/cosmos.authz.v1beta1.MsgExec".to_string(), value: exec.encode_to_vec() };
+        assert_eq!(any.to_message(), Message::MsgExec {
+            grantee: "cosmos1restake".to_string(),
+            msgs: vec![Message::MsgDelegate {
+                delegator_address: "cosmos1granter".to_string(),
+                validator_address: "cosmosvaloper1abc".to_string(),
+                amount: Amount { denom: "uatom".to_string(), amount: "10".to_string() },
+            }],
+        });
+
+        let authorization = GenericAuthorization { msg: "/cosmos.staking.v1beta1.MsgDelegate".to_string() };
+        let grant = MsgGrant {
+            granter: "cosmos1granter".to_string(),
+            grantee: "cosmos1restake".to_string(),
+            grant: Some(Grant {
+                authorization: Some(Any { type_url: "/cosmos.authz.v1beta1.GenericAuthorization".to_string(), value: authorization.encode_to_vec() }),
+                expiration: Some(Timestamp { seconds: 1_704_067_200, nanos: 0 }),
+            }),
+        };
+        let any = Any { type_url: "/cosmos.authz.v1beta1.MsgGrant".to_string(), value: grant.encode_to_vec() };
+        assert_eq!(any.to_message(), Message::MsgGrant {
+            granter: "cosmos1granter".to_string(),
+            grantee: "cosmos1restake".to_string(),
+            grant: message::Grant {
+                authorization: json!({ "@type": "/cosmos.authz.v1beta1.GenericAuthorization", "msg": "/cosmos.staking.v1beta1.MsgDelegate" }),
+                expiration: Some("2024-01-01T00:00:00Z".to_string()),
+            },
+        });
+    }
+
+    #[test]
+    fn test_to_message_execute_contract() {
+        let execute = MsgExecuteContract {
+            sender: "osmo1sender".to_string(),
+            contract: "osmo1contract".to_string(),
+            msg: br#"{"swap":{"min_output":"1"}}"#.to_vec(),
+            funds: vec![Coin { denom: "uosmo".to_string(), amount: "5".to_string() }],
+        };
+        let any = Any { type_url: "/cosmwasm.wasm.v1.MsgExecuteContract".to_string(), value: execute.encode_to_vec() };
+
+        assert_eq!(any.to_message(), Message::MsgExecuteContract {
+            sender: "osmo1sender".to_string(),
+            contract: "osmo1contract".to_string(),
+            msg: json!({ "swap": { "min_output": "1" } }),
+            funds: vec![Amount { denom: "uosmo".to_string(), amount: "5".to_string() }],
+        });
+    }
+
+    #[test]
+    fn test_to_message_gov_vote() {
+        let vote = MsgVoteWeighted {
+            proposal_id: 12,
+            voter: "cosmos1voter".to_string(),
+            options: vec![
+                WeightedOption { option: 1, weight: "600000000000000000".to_string() },
+                WeightedOption { option: 3, weight: "400000000000000000".to_string() },
+            ],
+            metadata: String::new(),
+        };
+        let any = Any { type_url: "/cosmos.gov.v1beta1.MsgVoteWeighted".to_string(), value: vote.encode_to_vec() };
+
+        assert_eq!(any.to_message(), Message::MsgVoteWeighted {
+            proposal_id: "12".to_string(),
+            voter: "cosmos1voter".to_string(),
+            options: vec![
+                WeightedVoteOption { option: "VOTE_OPTION_YES".to_string(), weight: "0.600000000000000000".to_string() },
+                WeightedVoteOption { option: "VOTE_OPTION_NO".to_string(), weight: "0.400000000000000000".to_string() },
+            ],
+        });
+    }
+
+    #[test]
+    fn test_legacy_dec() {
+        assert_eq!(legacy_dec("1000000000000000000"), "1.000000000000000000");
+        assert_eq!(legacy_dec("700000000000000000"), "0.700000000000000000");
+        assert_eq!(legacy_dec("5"), "0.000000000000000005");
+        assert_eq!(legacy_dec("0.5"), "0.5");
+    }
+
+    #[test]
+    fn test_to_message_recv_packet() {
+        let data = br#"{"amount":"5","denom":"uosmo","receiver":"cosmos1to","sender":"osmo1from"}"#.to_vec();
+        let recv = MsgRecvPacket {
+            packet: Some(Packet {
+                sequence: 7,
+                source_port: "transfer".to_string(),
+                source_channel: "channel-0".to_string(),
+                destination_port: "transfer".to_string(),
+                destination_channel: "channel-141".to_string(),
+                data,
+                timeout_height: None,
+                timeout_timestamp: 1_700_000_000_000_000_000,
+            }),
+            signer: "cosmos1relayer".to_string(),
+        };
+        let any = Any { type_url: "/ibc.core.channel.v1.MsgRecvPacket".to_string(), value: recv.encode_to_vec() };
+
+        match any.to_message() {
+            Message::MsgRecvPacket { packet, signer } => {
+                assert_eq!(signer, "cosmos1relayer");
+                assert_eq!(packet.sequence, "7");
+                assert_eq!(packet.timeout_timestamp, "1700000000000000000");
+                assert_eq!(packet.transfer.map(|transfer| transfer.amount), Some("5".to_string()));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_enum_name() {
+        assert_eq!(enum_name(&["A", "B"], 1), "B");
+        assert_eq!(enum_name(&["A", "B"], 5), "5");
+        assert_eq!(vote_option_name(4), "VOTE_OPTION_NO_WITH_VETO");
+    }
+
+    #[test]
+    fn test_verify_tx_hash() {
+        let hash = "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855";
+        assert!(verify_tx_hash(b"", hash).is_ok());
+        assert!(verify_tx_hash(b"", &format!("0x{}", hash.to_lowercase())).is_ok());
+        assert!(matches!(verify_tx_hash(b"x", hash), Err(TranslationError::TxHashMismatch { .. })));
+    }
+
+    #[test]
+    fn test_tx_hash() {
+        assert_eq!(tx_hash(b""), "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855");
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+use std::fs;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::config::config::Config;
+use crate::models::message::Message;
+
+/// Built-in message types: type URL, display name, sender path and receiver path.
+///
+/// Versions of the same message (gov v1beta1 and v1, instantiate v1 and v2) share a name so that a
+/// filter by name matches all of them.
+const BUILTIN_MESSAGES: &[(&str, &str, &str, &str)] = &[
+    ("/cosmos.bank.v1beta1.MsgSend", "MsgSend", "from_address", "to_address"),
+    ("/cosmos.staking.v1beta1.MsgDelegate", "MsgDelegate", "delegator_address", "validator_address"),
+    ("/cosmos.staking.v1beta1.MsgUndelegate", "MsgUndelegate", "delegator_address", "validator_address"),
+    ("/cosmos.staking.v1beta1.MsgBeginRedelegate", "MsgBeginRedelegate", "delegator_address", "validator_dst_address"),
+    ("/cosmos.staking.v1beta1.MsgCancelUnbondingDelegation", "MsgCancelUnbondingDelegation", "delegator_address", "validator_address"),
+    ("/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward", "MsgWithdrawDelegatorReward", "delegator_address", "validator_address"),
+    ("/cosmos.distribution.v1beta1.MsgWithdrawValidatorCommission", "MsgWithdrawValidatorCommission", "validator_address", ""),
+    ("/cosmos.distribution.v1beta1.MsgSetWithdrawAddress", "MsgSetWithdrawAddress", "delegator_address", "withdraw_address"),
+    ("/cosmos.distribution.v1beta1.MsgFundCommunityPool", "MsgFundCommunityPool", "depositor", ""),
+    ("/cosmos.gov.v1beta1.MsgSubmitProposal", "MsgSubmitProposal", "proposer", ""),
+    ("/cosmos.gov.v1.MsgSubmitProposal", "MsgSubmitProposal", "proposer", ""),
+    ("/cosmos.gov.v1beta1.MsgVote", "MsgVote", "voter", ""),
+    ("/cosmos.gov.v1.MsgVote", "MsgVote", "voter", ""),
+    ("/cosmos.gov.v1beta1.MsgVoteWeighted", "MsgVoteWeighted", "voter", ""),
+    ("/cosmos.gov.v1.MsgVoteWeighted", "MsgVoteWeighted", "voter", ""),
+    ("/cosmos.gov.v1beta1.MsgDeposit", "MsgDeposit", "depositor", ""),
+    ("/cosmos.gov.v1.MsgDeposit", "MsgDeposit", "depositor", ""),
+    ("/cosmos.gov.v1.MsgExecLegacyContent", "MsgExecLegacyContent", "authority", ""),
+    ("/cosmos.authz.v1beta1.MsgExec", "MsgExec", "grantee", ""),
+    ("/cosmos.authz.v1beta1.MsgGrant", "MsgGrant", "granter", "grantee"),
+    ("/cosmos.authz.v1beta1.MsgRevoke", "MsgRevoke", "granter", "grantee"),
+    ("/cosmwasm.wasm.v1.MsgStoreCode", "MsgStoreCode", "sender", ""),
+    ("/cosmwasm.wasm.v1.MsgInstantiateContract", "MsgInstantiateContract", "sender", ""),
+    ("/cosmwasm.wasm.v1.MsgInstantiateContract2", "MsgInstantiateContract", "sender", ""),
+    ("/cosmwasm.wasm.v1.MsgExecuteContract", "MsgExecuteContract", "sender", "contract"),
+    ("/cosmwasm.wasm.v1.MsgMigrateContract", "MsgMigrateContract", "sender", "contract"),
+    ("/cosmwasm.wasm.v1.MsgUpdateAdmin", "MsgUpdateAdmin", "sender", "contract"),
+    ("/ibc.applications.transfer.v1.MsgTransfer", "MsgTransfer", "sender", "receiver"),
+    ("/ibc.core.channel.v1.MsgRecvPacket", "MsgRecvPacket", "signer", "packet.transfer.receiver"),
+    ("/ibc.core.channel.v1.MsgAcknowledgement", "MsgAcknowledgement", "signer", ""),
+    ("/ibc.core.channel.v1.MsgTimeout", "MsgTimeout", "signer", ""),
+    ("/ibc.core.client.v1.MsgUpdateClient", "MsgUpdateClient", "signer", ""),
+    ("/ibc.core.channel.v1.MsgChannelOpenInit", "MsgChannelOpenInit", "signer", ""),
+    ("/ibc.core.channel.v1.MsgChannelOpenTry", "MsgChannelOpenTry", "signer", ""),
+    ("/ibc.core.channel.v1.MsgChannelOpenAck", "MsgChannelOpenAck", "signer", ""),
+    ("/ibc.core.channel.v1.MsgChannelOpenConfirm", "MsgChannelOpenConfirm", "signer", ""),
+    ("/ibc.core.channel.v1.MsgChannelCloseInit", "MsgChannelCloseInit", "signer", ""),
+    ("/ibc.core.channel.v1.MsgChannelCloseConfirm", "MsgChannelCloseConfirm", "signer", ""),
+    ("/ibc.core.connection.v1.MsgConnectionOpenInit", "MsgConnectionOpenInit", "signer", ""),
+    ("/ibc.core.connection.v1.MsgConnectionOpenTry", "MsgConnectionOpenTry", "signer", ""),
+    ("/ibc.core.connection.v1.MsgConnectionOpenAck", "MsgConnectionOpenAck", "signer", ""),
+    ("/ibc.core.connection.v1.MsgConnectionOpenConfirm", "MsgConnectionOpenConfirm", "signer", ""),
+];
+
+/// How to present one message type: its name, and the JSON paths of its sender, receiver and
+/// extra columns. Paths are dot separated, with numbers indexing into arrays: `routes.0.pool_id`.
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+pub struct MessageSchema {
+    pub type_url: String,
+    /// Name used by `--filter-by-msgtype`; defaults to the last segment of the type URL.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub sender: Option<String>,
+    #[serde(default)]
+    pub receiver: Option<String>,
+    #[serde(default)]
+    pub columns: Vec<SchemaColumn>,
+}
+
+/// An extra column shown in the `details` of a message.
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+pub struct SchemaColumn {
+    pub name: String,
+    pub path: String,
+}
+
+/// The layout of the schema file referenced by `message_schema` in the config.
+#[derive(Debug, Deserialize)]
+struct SchemaFile {
+    #[serde(default)]
+    messages: Vec<MessageSchema>,
+}
+
+/// What the registry extracted from a message.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MessageSummary {
+    pub name: String,
+    pub sender: Option<String>,
+    pub receiver: Option<String>,
+    /// The extra columns as `name=value` pairs, separated by `; `.
+    pub details: String,
+}
+
+/// Message types known by type URL, with how to present each of them.
+///
+/// Messages the `Message` enum doesn't model are kept as JSON, so a schema is all it takes to
+/// name, filter and tabulate a chain-specific type.
+#[derive(Debug, Clone)]
+pub struct MessageRegistry {
+    schemas: HashMap<String, MessageSchema>,
+}
+
+impl MessageSchema {
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or_else(|| {
+            self.type_url.rsplit(['.', '/']).next().unwrap_or(&self.type_url)
+        })
+    }
+
+    /// Whether the schema reads any field of the message JSON.
+    pub fn reads_fields(&self) -> bool {
+        self.sender.is_some() || self.receiver.is_some() || !self.columns.is_empty()
+    }
+}
+
+impl MessageRegistry {
+    /// The message types modelled by `Message`.
+    pub fn builtin() -> Self {
+        let mut registry = MessageRegistry { schemas: HashMap::new() };
+        for (type_url, name, sender, receiver) in BUILTIN_MESSAGES {
+            registry.register(MessageSchema {
+                type_url: type_url.to_string(),
+                name: Some(name.to_string()),
+                sender: Some(sender.to_string()).filter(|path| !path.is_empty()),
+                receiver: Some(receiver.to_string()).filter(|path| !path.is_empty()),
+                columns: Vec::new(),
+            });
+        }
+        registry
+    }
+
+    /// The built-in types plus those declared in the config's `message_schema` file.
+    pub fn from_config(config: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut registry = MessageRegistry::builtin();
+        if let Some(path) = config.message_schema() {
+            registry.load_schema_file(path).map_err(|e| format!("message_schema {}: {}", path, e))?;
+        }
+        Ok(registry)
+    }
+
+    /// Registers a message type, replacing any earlier schema for the same type URL.
+    pub fn register(&mut self, schema: MessageSchema) {
+        self.schemas.insert(schema.type_url.clone(), schema);
+    }
+
+    /// Registers every `[[messages]]` entry of a TOML schema file.
+    pub fn load_schema_file(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let contents = fs::read_to_string(path)?;
+        self.load_schema(&contents)
+    }
+
+    fn load_schema(&mut self, contents: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let file: SchemaFile = toml::from_str(contents)?;
+        for schema in file.messages {
+            if !schema.type_url.starts_with('/') {
+                return Err(format!("type_url '{}' must start with '/'", schema.type_url).into());
+            }
+            self.register(schema);
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, type_url: &str) -> Option<&MessageSchema> {
+        self.schemas.get(type_url)
+    }
+
+    /// The registered name of a type URL.
+    pub fn name_of(&self, type_url: &str) -> Option<&str> {
+        self.get(type_url).map(MessageSchema::name)
+    }
+
+    /// Whether any registered type goes by this name.
+    pub fn knows_name(&self, name: &str) -> bool {
+        self.schemas.values().any(|schema| schema.name() == name)
+    }
+
+    /// Extracts the name, sender, receiver and extra columns of a registered message.
+    pub fn describe(&self, message: &Message) -> Option<MessageSummary> {
+        let schema = self.get(message.type_url())?;
+        let json = serde_json::to_value(message).ok()?;
+        let field = |path: &Option<String>| path.as_deref().and_then(|path| lookup(&json, path));
+        let details = schema.columns.iter()
+            .filter_map(|column| Some(format!("{}={}", column.name, lookup(&json, &column.path)?)))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        Some(MessageSummary {
+            name: schema.name().to_string(),
+            sender: field(&schema.sender),
+            receiver: field(&schema.receiver),
+            details,
+        })
+    }
+}
+
+/// Follows a dot separated path through a JSON value. Strings are returned as they are, anything
+/// else as compact JSON.
+fn lookup(json: &Value, path: &str) -> Option<String> {
+    let value = path.split('.').try_fold(json, |value, key| match value {
+        Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => value.get(key),
+    })?;
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const OSMOSIS_SCHEMA: &str = r#"
+        [[messages]]
+        type_url = "/osmosis.gamm.v1beta1.MsgSwapExactAmountIn"
+        sender = "sender"
+
+        [[messages.columns]]
+        name = "pool"
+        path = "routes.0.pool_id"
+
+        [[messages.columns]]
+        name = "token_in"
+        path = "token_in"
+
+        [[messages]]
+        type_url = "/stride.stakeibc.MsgLiquidStake"
+        name = "LiquidStake"
+        sender = "creator"
+    "#;
+
+    fn swap() -> Message {
+        serde_json::from_value(json!({
+            "@type": "/osmosis.gamm.v1beta1.MsgSwapExactAmountIn",
+            "sender": "osmo1sender",
+            "routes": [{ "pool_id": "1", "token_out_denom": "uatom" }],
+            "token_in": { "denom": "uosmo", "amount": "5" },
+            "token_out_min_amount": "1"
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_names_group_versions() {
+        let registry = MessageRegistry::builtin();
+        assert_eq!(registry.name_of("/cosmos.gov.v1.MsgVote"), Some("MsgVote"));
+        assert_eq!(registry.name_of("/cosmos.gov.v1beta1.MsgVote"), Some("MsgVote"));
+        assert_eq!(registry.name_of("/cosmwasm.wasm.v1.MsgInstantiateContract2"), Some("MsgInstantiateContract"));
+        assert!(!registry.knows_name("MsgSwapExactAmountIn"));
+    }
+
+    #[test]
+    fn test_builtin_sender_and_receiver() {
+        let send: Message = serde_json::from_value(json!({
+            "@type": "/cosmos.bank.v1beta1.MsgSend",
+            "from_address": "cosmos1from",
+            "to_address": "cosmos1to",
+            "amount": [{ "denom": "uatom", "amount": "1" }]
+        })).unwrap();
+
+        let summary = MessageRegistry::builtin().describe(&send).unwrap();
+        assert_eq!(summary.sender.as_deref(), Some("cosmos1from"));
+        assert_eq!(summary.receiver.as_deref(), Some("cosmos1to"));
+        assert_eq!(summary.details, "");
+    }
+
+    #[test]
+    fn test_schema_file_registers_chain_messages() {
+        let mut registry = MessageRegistry::builtin();
+        registry.load_schema(OSMOSIS_SCHEMA).unwrap();
+
+        assert!(registry.knows_name("MsgSwapExactAmountIn"));
+        assert_eq!(registry.name_of("/stride.stakeibc.MsgLiquidStake"), Some("LiquidStake"));
+        assert_eq!(registry.describe(&swap()), Some(MessageSummary {
+            name: "MsgSwapExactAmountIn".to_string(),
+            sender: Some("osmo1sender".to_string()),
+            receiver: None,
+            details: r#"pool=1; token_in={"amount":"5","denom":"uosmo"}"#.to_string(),
+        }));
+    }
+
+    #[test]
+    fn test_schema_rejects_relative_type_url() {
+        let mut registry = MessageRegistry::builtin();
+        let error = registry.load_schema("[[messages]]\ntype_url = \"osmosis.gamm.v1beta1.MsgSwapExactAmountIn\"").unwrap_err();
+        assert!(error.to_string().contains("must start with '/'"));
+    }
+}
@@ -0,0 +1,244 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::event::Event;
+use crate::models::pagination::Pagination;
+use crate::models::proto::{decode_tx, tx_hash, verify_tx_hash};
+use crate::models::transaction::{ResponseData, ResponseDataForHashQuery, TranslationError, Tx, TxResponse};
+
+// These structs represent the responses of the CometBFT RPC (`/block`, `/block_results`, `/tx`).
+
+/// JSON-RPC envelope wrapped around every CometBFT RPC result.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RpcResponse<T> {
+    pub result: T,
+}
+
+/// Result of `/block?height=N`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockResponse {
+    pub block: Block,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Block {
+    pub header: Header,
+    pub data: BlockData,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Header {
+    pub height: String,
+    pub time: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockData {
+    // Base64 encoded raw txs; `null` for an empty block.
+    #[serde(default)]
+    pub txs: Option<Vec<String>>,
+}
+
+/// Result of `/blockchain?minHeight=A&maxHeight=B`: the metadata of up to 20 blocks, newest first.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockchainInfo {
+    pub block_metas: Vec<BlockMeta>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockMeta {
+    pub header: Header,
+}
+
+/// Result of `/block_results?height=N`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockResults {
+    pub height: String,
+    // One execution result per tx, in block order; `null` for an empty block.
+    #[serde(default)]
+    pub txs_results: Option<Vec<ExecTxResult>>,
+}
+
+/// Execution result of a single tx, shared by `/block_results` and `/tx`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExecTxResult {
+    #[serde(default)]
+    pub code: i32,
+    #[serde(default)]
+    pub data: Option<String>,
+    #[serde(default)]
+    pub log: String,
+    #[serde(default)]
+    pub gas_wanted: String,
+    #[serde(default)]
+    pub gas_used: String,
+    #[serde(default)]
+    pub events: Vec<Event>,
+    #[serde(default)]
+    pub codespace: String,
+}
+
+/// Result of `/tx?hash=0x...`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TxResult {
+    pub hash: String,
+    pub height: String,
+    pub tx_result: ExecTxResult,
+    pub tx: String,
+}
+
+/// Result of `/tx_search`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TxSearchResult {
+    pub txs: Vec<TxResult>,
+    pub total_count: String,
+}
+
+impl Block {
+    /// Pairs the raw txs of a block with their execution results, producing the same
+    /// `ResponseData` the LCD returns for `events=tx.height=N`.
+    pub fn to_response_data(&self, results: &BlockResults) -> Result<ResponseData, TranslationError> {
+        let raw_txs = self.data.txs.as_deref().unwrap_or_default();
+        let exec_results = results.txs_results.as_deref().unwrap_or_default();
+
+        let mut txs = Vec::with_capacity(raw_txs.len());
+        let mut tx_responses = Vec::with_capacity(raw_txs.len());
+        for (raw_tx, result) in raw_txs.iter().zip(exec_results) {
+            let (tx, tx_response) = combine_tx(raw_tx, result, &self.header.height, &self.header.time)?;
+            txs.push(tx);
+            tx_responses.push(tx_response);
+        }
+
+        Ok(ResponseData {
+            // The block's tx count, so a shorter `block_results` shows up as incomplete data.
+            pagination: Pagination {
+                next_key: None,
+                total: raw_txs.len().to_string(),
+            },
+            txs,
+            tx_responses,
+        })
+    }
+}
+
+impl TxResult {
+    /// Converts a `/tx` result into the LCD `GetTx` shape, using the time of the block it was included in.
+    ///
+    /// The hash reported by the node is checked against the hash of the returned tx bytes.
+    pub fn to_hash_query(&self, block_time: &str) -> Result<ResponseDataForHashQuery, TranslationError> {
+        let bytes = STANDARD.decode(&self.tx).map_err(TranslationError::Base64DecodeError)?;
+        verify_tx_hash(&bytes, &self.hash)?;
+        let (tx, tx_response) = combine_tx(&self.tx, &self.tx_result, &self.height, block_time)?;
+        Ok(ResponseDataForHashQuery { tx, tx_response })
+    }
+}
+
+/// Decodes a base64 raw tx and combines it with its execution result.
+fn combine_tx(raw_tx: &str, result: &ExecTxResult, height: &str, time: &str) -> Result<(Tx, TxResponse), TranslationError> {
+    let bytes = STANDARD.decode(raw_tx).map_err(TranslationError::Base64DecodeError)?;
+    let tx = decode_tx(&bytes)?;
+
+    let mut events = result.events.clone();
+    for event in events.iter_mut() {
+        event.decode_base64_attributes();
+    }
+
+    let tx_response = TxResponse {
+        height: height.to_string(),
+        txhash: tx_hash(&bytes),
+        codespace: result.codespace.clone(),
+        code: result.code,
+        data: result.data.clone().unwrap_or_default(),
+        raw_log: Value::String(result.log.clone()),
+        // Older SDKs put the per-message logs into `log` as JSON; newer ones leave it empty.
+        logs: serde_json::from_str(&result.log).unwrap_or_else(|_| Value::Array(vec![])),
+        gas_wanted: result.gas_wanted.clone(),
+        gas_used: result.gas_used.clone(),
+        timestamp: time.to_string(),
+        events,
+    };
+
+    Ok((tx, tx_response))
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message as _;
+    use serde_json::json;
+
+    use crate::models::message::Message;
+    use crate::models::proto::{Any, TxBody, TxRaw};
+
+    use super::*;
+
+    fn mock_raw_tx(memo: &str) -> String {
+        let body = TxBody {
+            messages: vec![Any { type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(), value: vec![] }],
+            memo: memo.to_string(),
+            timeout_height: 0,
+        };
+        let raw = TxRaw { body_bytes: body.encode_to_vec(), auth_info_bytes: vec![], signatures: vec![vec![1; 64]] };
+        STANDARD.encode(raw.encode_to_vec())
+    }
+
+    #[test]
+    fn test_block_to_response_data() {
+        let raw_tx = mock_raw_tx("hello");
+        let block: BlockResponse = serde_json::from_value(json!({
+            "block": {
+                "header": { "height": "100", "time": "2023-01-01T00:00:00.123456789Z" },
+                "data": { "txs": [raw_tx] }
+            }
+        })).unwrap();
+        let results: BlockResults = serde_json::from_value(json!({
+            "height": "100",
+            "txs_results": [{
+                "code": 0,
+                "data": null,
+                "log": "",
+                "gas_wanted": "200000",
+                "gas_used": "80000",
+                "events": [{ "type": "message", "attributes": [{ "key": "YWN0aW9u", "value": "c2VuZA==", "index": true }] }],
+                "codespace": ""
+            }]
+        })).unwrap();
+
+        let data = block.block.to_response_data(&results).unwrap();
+
+        assert_eq!(data.pagination.total, "1");
+        assert_eq!(data.txs[0].body.memo, "hello");
+        assert!(matches!(data.txs[0].body.messages[0], Message::MsgSend { .. }));
+        assert_eq!(data.tx_responses[0].txhash, tx_hash(&STANDARD.decode(&raw_tx).unwrap()));
+        assert_eq!(data.tx_responses[0].gas_used, "80000");
+        assert_eq!(data.tx_responses[0].events[0].attributes[0].key, "action");
+        assert_eq!(data.tx_responses[0].events[0].attributes[0].value, "send");
+    }
+
+    #[test]
+    fn test_empty_block() {
+        let block: BlockResponse = serde_json::from_value(json!({
+            "block": { "header": { "height": "1", "time": "2023-01-01T00:00:00Z" }, "data": { "txs": null } }
+        })).unwrap();
+        let results: BlockResults = serde_json::from_value(json!({ "height": "1", "txs_results": null })).unwrap();
+
+        let data = block.block.to_response_data(&results).unwrap();
+        assert!(data.txs.is_empty());
+        assert_eq!(data.pagination.total, "0");
+    }
+
+    #[test]
+    fn test_to_hash_query_rejects_wrong_hash() {
+        let tx: TxResult = serde_json::from_value(json!({
+            "hash": "00", "height": "1", "tx": mock_raw_tx("memo"), "tx_result": {}
+        })).unwrap();
+        assert!(matches!(tx.to_hash_query("2023-01-01T00:00:00Z"), Err(TranslationError::TxHashMismatch { .. })));
+    }
+
+    #[test]
+    fn test_invalid_tx_bytes() {
+        let result = combine_tx("not base64!", &serde_json::from_value(json!({})).unwrap(), "1", "2023-01-01T00:00:00Z");
+        assert!(matches!(result, Err(TranslationError::Base64DecodeError(_))));
+    }
+}
@@ -0,0 +1,70 @@
+//! Fixtures shared by the tests: LCD responses as the node sends them and a `ComprehensiveTx`
+//! with every field filled in.
+
+use serde_json::{json, Value};
+
+use crate::models::transaction::ComprehensiveTx;
+
+/// A tx of an LCD page carrying `messages`.
+pub fn tx_json(messages: Value) -> Value {
+    json!({ "body": { "messages": messages, "memo": "", "timeout_height": "0" }, "auth_info": {}, "signatures": [] })
+}
+
+/// The `tx_response` of a successful tx at `height`.
+pub fn tx_response_json(height: u64, hash: &str) -> Value {
+    json!({
+        "height": height.to_string(),
+        "txhash": hash,
+        "codespace": "",
+        "code": 0,
+        "data": "",
+        "raw_log": "",
+        "logs": [],
+        "gas_wanted": "200000",
+        "gas_used": "100000",
+        "timestamp": "2023-01-01T00:00:00Z"
+    })
+}
+
+/// A last `GetTxsEvent` page holding `tx_responses`, each paired with a tx carrying `messages`.
+pub fn txs_page(tx_responses: Vec<Value>, messages: Value) -> String {
+    json!({
+        "txs": tx_responses.iter().map(|_| tx_json(messages.clone())).collect::<Vec<_>>(),
+        "pagination": { "next_key": null, "total": tx_responses.len().to_string() },
+        "tx_responses": tx_responses
+    }).to_string()
+}
+
+/// An LCD block at `height` produced at `time`, holding `tx_count` txs.
+pub fn block_json(height: u64, time: &str, tx_count: usize) -> String {
+    let txs = vec!["dHg="; tx_count];
+    json!({ "block": { "header": { "height": height.to_string(), "time": time }, "data": { "txs": txs } } }).to_string()
+}
+
+/// A successful tx at height 1 without messages, fees or signers, with `fields` replacing the defaults.
+pub fn comprehensive_tx(fields: Value) -> ComprehensiveTx {
+    let mut tx = json!({
+        "messages": [],
+        "height": 1,
+        "tx_hash": "",
+        "code": 0,
+        "codespace": "",
+        "failure_log": "",
+        "gas_used": 100000,
+        "gas_wanted": "200000",
+        "timestamp": "2023-01-01T00:00:00Z",
+        "data": "",
+        "signatures": [],
+        "memo": "",
+        "timeout_height": "0",
+        "fee": [],
+        "gas_limit": 200000,
+        "fee_payer": "",
+        "fee_granter": "",
+        "signer_infos": []
+    });
+    if let (Some(tx), Value::Object(fields)) = (tx.as_object_mut(), fields) {
+        tx.extend(fields);
+    }
+    serde_json::from_value(tx).unwrap()
+}
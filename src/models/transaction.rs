@@ -1,13 +1,19 @@
+use std::collections::{BTreeSet, HashSet};
 use std::fmt;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::models::auth::{format_coins, AuthInfo, SignerInfo};
+use crate::models::coin::{collect_coins, Coin, DenomResolver};
+use crate::models::event::{Event, EventFilter};
+use crate::models::ibc::{collect_ibc_denoms, IBC_DENOM_PREFIX};
 use crate::models::message::MessageType;
 use crate::models::pagination::Pagination;
+use crate::models::registry::{MessageRegistry, MessageSchema};
 
-use super::message::Message;
+use super::message::{Amount, Message};
 
 // Traits
 
@@ -29,32 +35,68 @@ pub trait SortableField<T>: Ord {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ComprehensiveTx {
     messages: Vec<Message>,
-    height: u64,
-    tx_hash: String,
+    pub(crate) height: u64,
+    pub(crate) tx_hash: String,
+    /// Zero when the transaction succeeded; its messages had no effect otherwise.
+    pub(crate) code: i32,
+    pub(crate) codespace: String,
+    /// The error logged by the node for a failed transaction; empty on success.
+    pub(crate) failure_log: String,
     gas_used: u64,
     gas_wanted: String,
-    timestamp: DateTime<Utc>,
+    pub(crate) timestamp: DateTime<Utc>,
     data: String,
     signatures: Vec<String>,
     memo: String,
     timeout_height: String,
+    pub(crate) fee: Vec<Amount>,
+    pub(crate) gas_limit: u64,
+    /// The account charged the fee: the explicit payer, or else the one reported by the node.
+    pub(crate) fee_payer: String,
+    /// The feegrant granter covering the fee, if any.
+    pub(crate) fee_granter: String,
+    pub(crate) signer_infos: Vec<SignerInfo>,
+    /// Every event the transaction emitted, including the fee and signature events of the ante handler.
+    #[serde(default)]
+    pub(crate) events: Vec<Event>,
+    /// The events of each top-level message, by message index.
+    #[serde(default)]
+    msg_events: Vec<Vec<Event>>,
+    /// The `ibc/{hash}` denoms of the messages and fee with their trace, such as
+    /// `ibc/27394F...=transfer/channel-0/uatom`; empty until `annotate_denoms` is called.
+    #[serde(default)]
+    pub(crate) denom_traces: String,
+    /// The fee in display units, such as `0.005 ATOM`; empty until `annotate_denoms` is called.
+    #[serde(default)]
+    pub(crate) display_fee: String,
 }
 
 impl fmt::Display for ComprehensiveTx {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Messages: {:?}\nHeight: {}\nTx Hash: {}\nGas Used: {}\nGas Wanted: {}\nTimestamp: {}\nData: {}\nSignatures: {:?}\nMemo: {}\nTimeout Height: {}",
+            "Messages: {:?}\nHeight: {}\nTx Hash: {}\nCode: {}\nCodespace: {}\nFailure: {}\nGas Used: {}\nGas Wanted: {}\nTimestamp: {}\nData: {}\nSignatures: {:?}\nMemo: {}\nTimeout Height: {}\nFee: {}\nGas Limit: {}\nFee Payer: {}\nFee Granter: {}\nSigners: {}\nEvents: {}\nDenom Traces: {}\nDisplay Fee: {}",
             self.messages,
             self.height,
             self.tx_hash,
+            self.code,
+            self.codespace,
+            self.failure_log,
             self.gas_used,
             self.gas_wanted,
             self.timestamp,
             self.data,
             self.signatures,
             self.memo,
-            self.timeout_height
+            self.timeout_height,
+            format_coins(&self.fee),
+            self.gas_limit,
+            self.fee_payer,
+            self.fee_granter,
+            self.signer_infos.iter().map(SignerInfo::to_string).collect::<Vec<_>>().join("; "),
+            format_events(&self.events),
+            self.denom_traces,
+            self.display_fee
         )
     }
 }
@@ -65,19 +107,57 @@ impl fmt::Display for ComprehensiveTx {
 impl ComprehensiveTx {
     /// Converts the comprehensive transaction into individual message transactions.
     pub fn to_individual_transactions(&self) -> Result<Vec<IndividualMsgTx>, TranslationError> {
-        let results: Vec<IndividualMsgTx> = self.messages.iter()
-            .map(|msg| self.to_individual_msg_txs(msg))
+        let results: Vec<IndividualMsgTx> = self.messages.iter().enumerate()
+            .map(|(index, msg)| self.to_individual_msg_txs(msg, index.to_string(), None))
             .collect();
 
         Ok(results)
     }
 
-    // assuming you already have this function
-    fn to_individual_msg_txs(&self, msg: &Message) -> IndividualMsgTx {
+    /// Like `to_individual_transactions`, but each message executed through an authz `MsgExec`
+    /// also gets a row of its own, right after the `MsgExec`, with its path and grantee.
+    pub fn to_expanded_individual_transactions(&self) -> Result<Vec<IndividualMsgTx>, TranslationError> {
+        let mut results = Vec::new();
+        for (index, msg) in self.messages.iter().enumerate() {
+            self.expand_msg_txs(msg, index.to_string(), None, &mut results);
+        }
+
+        Ok(results)
+    }
+
+    fn expand_msg_txs(&self, msg: &Message, msg_path: String, grantee: Option<&str>, results: &mut Vec<IndividualMsgTx>) {
+        results.push(self.to_individual_msg_txs(msg, msg_path.clone(), grantee));
+        // Grantees can themselves be granted to, so a MsgExec may wrap further MsgExecs.
+        if let Message::MsgExec { grantee, msgs } = msg {
+            for (index, inner) in msgs.iter().enumerate() {
+                self.expand_msg_txs(inner, format!("{}.{}", msg_path, index), Some(grantee), results);
+            }
+        }
+    }
+
+    fn to_individual_msg_txs(&self, msg: &Message, msg_path: String, grantee: Option<&str>) -> IndividualMsgTx {
+        // Nodes report events per top-level message, so authz-nested messages share those of their MsgExec.
+        let events = msg_path.split('.').next()
+            .and_then(|index| index.parse::<usize>().ok())
+            .and_then(|index| self.msg_events.get(index))
+            .cloned()
+            .unwrap_or_default();
         IndividualMsgTx {
             message: msg.clone(),
+            msg_path,
+            grantee: grantee.map(str::to_string),
+            msg_name: String::new(),
+            sender: None,
+            receiver: None,
+            details: String::new(),
+            denom_traces: String::new(),
+            display_amounts: String::new(),
+            events,
             height: self.height,
             tx_hash: self.tx_hash.clone(),
+            code: self.code,
+            codespace: self.codespace.clone(),
+            failure_log: self.failure_log.clone(),
             timestamp: self.timestamp,
             data: self.data.clone(),
             signatures: self.signatures.clone(),
@@ -85,8 +165,42 @@ impl ComprehensiveTx {
             timeout_height: self.timeout_height.clone(),
         }
     }
+    /// The `ibc/{hash}` denoms used by the messages and the fee.
+    pub fn ibc_denoms(&self) -> BTreeSet<String> {
+        let mut denoms = BTreeSet::new();
+        if let Ok(messages) = serde_json::to_value(&self.messages) {
+            collect_ibc_denoms(&messages, &mut denoms);
+        }
+        denoms.extend(self.fee.iter().filter(|coin| coin.denom.starts_with(IBC_DENOM_PREFIX)).map(|coin| coin.denom.clone()));
+        denoms
+    }
+
+    /// Fills in the traces of the IBC denoms each transaction uses and its fee in display units.
+    pub fn annotate_denoms(txs: &mut [Self], resolver: &DenomResolver) {
+        for tx in txs.iter_mut() {
+            tx.denom_traces = resolver.describe_traces(&tx.ibc_denoms());
+            let fee: Vec<Coin> = tx.fee.iter().filter_map(|amount| Coin::try_from(amount).ok()).collect();
+            tx.display_fee = resolver.describe_amounts(&fee);
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.code == 0
+    }
+
+    /// Keeps the successful transactions, or the failed ones.
+    pub fn filter_by_success(txs: Vec<Self>, success: bool) -> Vec<Self> {
+        txs.into_iter().filter(|tx| tx.is_success() == success).collect()
+    }
+
+    /// Keeps the transactions that emitted an event matching the filter.
+    pub fn filter_by_event(txs: Vec<Self>, filter: &EventFilter) -> Vec<Self> {
+        txs.into_iter().filter(|tx| tx.events.iter().any(|event| event.matches(filter))).collect()
+    }
+
     /// Sorts a list of transactions based on a given sortable field.
-    pub fn sort_by<T: SortableField<Self>>(transactions: &mut Vec<Self>, ascending: bool) {
+    #[allow(dead_code)]
+    pub fn sort_by<T: SortableField<Self>>(transactions: &mut [Self], ascending: bool) {
         transactions.sort_by(|a, b| {
             let a_val = T::get_field_value(a);
             let b_val = T::get_field_value(b);
@@ -104,26 +218,164 @@ impl ComprehensiveTx {
 /// Includes a subset of the attributes present in `ComprehensiveTx`.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct IndividualMsgTx {
-    #[serde(serialize_with = "serialize_message")]
-    message: Message,
+    pub(crate) message: Message,
+    /// Position of the message in the tx; messages run through authz `MsgExec` are nested, so
+    /// `0.1` is the second message wrapped by the first message.
+    #[serde(default)]
+    pub(crate) msg_path: String,
+    /// The grantee that executed the message through authz, for nested messages.
+    #[serde(default)]
+    pub(crate) grantee: Option<String>,
+    /// Registered name of the message type; empty until `annotate` is called.
+    #[serde(default)]
+    pub(crate) msg_name: String,
+    #[serde(default)]
+    pub(crate) sender: Option<String>,
+    #[serde(default)]
+    pub(crate) receiver: Option<String>,
+    /// Extra columns declared for the message type, as `name=value` pairs.
+    #[serde(default)]
+    pub(crate) details: String,
+    /// The `ibc/{hash}` denoms of the message with their trace; empty until `annotate_denoms` is called.
+    #[serde(default)]
+    pub(crate) denom_traces: String,
+    /// The coins of the message in display units, such as `2.5 ATOM`; empty until `annotate_denoms` is called.
+    #[serde(default)]
+    pub(crate) display_amounts: String,
+    /// The events emitted by the message, such as the `withdraw_rewards` amount of a `MsgWithdrawDelegatorReward`.
+    #[serde(default)]
+    pub(crate) events: Vec<Event>,
+    pub(crate) height: u64,
+    pub(crate) tx_hash: String,
+    /// The result of the whole transaction: a message of a failed transaction had no effect.
+    #[serde(default)]
+    pub(crate) code: i32,
+    #[serde(default)]
+    pub(crate) codespace: String,
+    #[serde(default)]
+    pub(crate) failure_log: String,
+    pub(crate) timestamp: DateTime<Utc>,
+    pub(crate) data: String,
+    pub(crate) signatures: Vec<String>,
+    pub(crate) memo: String,
+    pub(crate) timeout_height: String,
+}
+
+/// A transaction as one flat CSV record: messages as JSON, coins, signers and events in their
+/// display form.
+#[derive(Debug, Serialize)]
+pub struct ComprehensiveTxRow {
+    messages: String,
     height: u64,
     tx_hash: String,
+    code: i32,
+    codespace: String,
+    failure_log: String,
+    gas_used: u64,
+    gas_wanted: String,
     timestamp: DateTime<Utc>,
     data: String,
-    #[serde(flatten)]
-    signatures: Vec<String>,
+    signatures: String,
     memo: String,
     timeout_height: String,
+    fee: String,
+    display_fee: String,
+    gas_limit: u64,
+    fee_payer: String,
+    fee_granter: String,
+    signer_infos: String,
+    events: String,
+    denom_traces: String,
 }
 
-// TRYING TO FLATTEN TO USE WITH CSV        todo:
-fn serialize_message<S>(msg: &Message, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-{
-    // Convert the Message enum to a JSON string
-    let s = serde_json::to_string(msg).unwrap();
-    serializer.serialize_str(&s)
+impl ComprehensiveTx {
+    pub fn csv_row(&self) -> ComprehensiveTxRow {
+        ComprehensiveTxRow {
+            messages: serde_json::to_string(&self.messages).unwrap_or_default(),
+            height: self.height,
+            tx_hash: self.tx_hash.clone(),
+            code: self.code,
+            codespace: self.codespace.clone(),
+            failure_log: self.failure_log.clone(),
+            gas_used: self.gas_used,
+            gas_wanted: self.gas_wanted.clone(),
+            timestamp: self.timestamp,
+            data: self.data.clone(),
+            signatures: self.signatures.join(","),
+            memo: self.memo.clone(),
+            timeout_height: self.timeout_height.clone(),
+            fee: format_coins(&self.fee),
+            display_fee: self.display_fee.clone(),
+            gas_limit: self.gas_limit,
+            fee_payer: self.fee_payer.clone(),
+            fee_granter: self.fee_granter.clone(),
+            signer_infos: self.signer_infos.iter().map(SignerInfo::to_string).collect::<Vec<_>>().join("; "),
+            events: format_events(&self.events),
+            denom_traces: self.denom_traces.clone(),
+        }
+    }
+}
+
+/// A message as one flat CSV record: the message as JSON, amounts and events in their display form.
+#[derive(Debug, Serialize)]
+pub struct IndividualMsgTxRow {
+    message: String,
+    msg_path: String,
+    grantee: Option<String>,
+    msg_name: String,
+    sender: Option<String>,
+    receiver: Option<String>,
+    details: String,
+    display_amounts: String,
+    denom_traces: String,
+    events: String,
+    height: u64,
+    tx_hash: String,
+    code: i32,
+    codespace: String,
+    failure_log: String,
+    timestamp: DateTime<Utc>,
+    data: String,
+    signatures: String,
+    memo: String,
+    timeout_height: String,
+}
+
+impl IndividualMsgTx {
+    pub fn csv_row(&self) -> IndividualMsgTxRow {
+        IndividualMsgTxRow {
+            message: serde_json::to_string(&self.message).unwrap_or_default(),
+            msg_path: self.msg_path.clone(),
+            grantee: self.grantee.clone(),
+            msg_name: self.msg_name.clone(),
+            sender: self.sender.clone(),
+            receiver: self.receiver.clone(),
+            details: self.details.clone(),
+            display_amounts: self.display_amounts.clone(),
+            denom_traces: self.denom_traces.clone(),
+            events: format_events(&self.events),
+            height: self.height,
+            tx_hash: self.tx_hash.clone(),
+            code: self.code,
+            codespace: self.codespace.clone(),
+            failure_log: self.failure_log.clone(),
+            timestamp: self.timestamp,
+            data: self.data.clone(),
+            signatures: self.signatures.join(","),
+            memo: self.memo.clone(),
+            timeout_height: self.timeout_height.clone(),
+        }
+    }
+}
+
+/// The root cause of a failure log: the SDK wraps errors as `context: cause: root error`,
+/// e.g. `failed to execute message; message index: 0: 5uatom is smaller than 10uatom: insufficient funds`.
+pub fn failure_reason(log: &str) -> &str {
+    log.rsplit(": ").next().unwrap_or(log).trim()
+}
+
+fn format_events(events: &[Event]) -> String {
+    events.iter().map(Event::to_string).collect::<Vec<_>>().join("; ")
 }
 
 impl fmt::Display for IndividualMsgTx {
@@ -131,9 +383,34 @@ impl fmt::Display for IndividualMsgTx {
         // This is a very basic representation; you can adjust as necessary.
         write!(
             f,
-            "Message: {:?}, Height: {}, Tx Hash: {}, Timestamp: {}",
+            "Message: {}, Height: {}, Tx Hash: {}, Timestamp: {}",
             self.message, self.height, self.tx_hash, self.timestamp
-        )
+        )?;
+        if self.code != 0 {
+            write!(f, ", Failed: {} code {}: {}", self.codespace, self.code, failure_reason(&self.failure_log))?;
+        }
+        if let Some(grantee) = &self.grantee {
+            write!(f, ", Path: {}, Grantee: {}", self.msg_path, grantee)?;
+        }
+        if let Some(sender) = &self.sender {
+            write!(f, ", Sender: {}", sender)?;
+        }
+        if let Some(receiver) = &self.receiver {
+            write!(f, ", Receiver: {}", receiver)?;
+        }
+        if !self.details.is_empty() {
+            write!(f, ", Details: {}", self.details)?;
+        }
+        if !self.display_amounts.is_empty() {
+            write!(f, ", Amounts: {}", self.display_amounts)?;
+        }
+        if !self.denom_traces.is_empty() {
+            write!(f, ", Denoms: {}", self.denom_traces)?;
+        }
+        if !self.events.is_empty() {
+            write!(f, ", Events: {}", format_events(&self.events))?;
+        }
+        Ok(())
     }
 }
 
@@ -141,19 +418,89 @@ impl fmt::Display for IndividualMsgTx {
 
 impl IndividualMsgTx {
     /// Filters the transactions based on the given message type.
-    pub fn filter_by_type(txs: &Vec<Self>, msg_type: MessageType) -> Vec<Self> {
-        txs.iter().filter(|tx| {
-            match (&tx.message, &msg_type) {
-                (Message::MsgSend { .. }, MessageType::MsgSend) => true,
-                (Message::MsgDelegate { .. }, MessageType::MsgDelegate) => true,
-                (Message::MsgTransfer { .. }, MessageType::MsgTransfer) => true,
-                (_, MessageType::Other) => true,
-                _ => false,
+    pub fn filter_by_type(txs: &[Self], msg_type: MessageType, registry: &MessageRegistry) -> Vec<Self> {
+        txs.iter().filter(|tx| msg_type.matches(&tx.message, registry)).cloned().collect()
+    }
+
+    /// Fills in the name, sender, receiver and details of each message from the registry.
+    /// Messages of unregistered types are named by their type URL.
+    ///
+    /// Messages the RPC backend could not decode carry only protobuf bytes, so the schema paths
+    /// find nothing in them; a warning names each such type once.
+    pub fn annotate(txs: &mut [Self], registry: &MessageRegistry) {
+        let mut warned = BTreeSet::new();
+        for tx in txs.iter_mut() {
+            if let Message::Undecoded { type_url, .. } = &tx.message {
+                if registry.get(type_url).is_some_and(MessageSchema::reads_fields) && warned.insert(type_url.clone()) {
+                    eprintln!("{} is not decoded from protobuf, so its sender, receiver and columns are left empty; use the LCD backend to read them", type_url);
+                }
+            }
+            match registry.describe(&tx.message) {
+                Some(summary) => {
+                    tx.msg_name = summary.name;
+                    tx.sender = summary.sender;
+                    tx.receiver = summary.receiver;
+                    tx.details = summary.details;
+                }
+                None => tx.msg_name = tx.message.type_url().to_string(),
+            }
+        }
+    }
+
+    /// The `ibc/{hash}` denoms used by the message.
+    pub fn ibc_denoms(&self) -> BTreeSet<String> {
+        let mut denoms = BTreeSet::new();
+        if let Ok(message) = serde_json::to_value(&self.message) {
+            collect_ibc_denoms(&message, &mut denoms);
+        }
+        denoms
+    }
+
+    /// Fills in the traces of the IBC denoms each message uses and its amounts in display units.
+    ///
+    /// A `MsgExec` whose messages have rows of their own leaves their amounts to those rows, so
+    /// summing the amounts of all rows counts each coin once.
+    pub fn annotate_denoms(txs: &mut [Self], resolver: &DenomResolver) {
+        let expanded: HashSet<(String, String)> = txs.iter()
+            .filter(|tx| tx.grantee.is_some())
+            .filter_map(|tx| Some((tx.tx_hash.clone(), tx.msg_path.rsplit_once('.')?.0.to_string())))
+            .collect();
+        for tx in txs.iter_mut() {
+            tx.denom_traces = resolver.describe_traces(&tx.ibc_denoms());
+            let mut coins = Vec::new();
+            if let Ok(mut message) = serde_json::to_value(&tx.message) {
+                if expanded.contains(&(tx.tx_hash.clone(), tx.msg_path.clone())) {
+                    if let Some(fields) = message.as_object_mut() {
+                        fields.remove("msgs");
+                    }
+                }
+                collect_coins(&message, &mut coins);
             }
-        }).cloned().collect()
+            tx.display_amounts = resolver.describe_amounts(&coins);
+        }
+    }
+
+    /// Keeps the messages of successful transactions, or those of failed ones.
+    pub fn filter_by_success(txs: &[Self], success: bool) -> Vec<Self> {
+        txs.iter().filter(|tx| (tx.code == 0) == success).cloned().collect()
+    }
+
+    /// Keeps the messages that emitted an event matching the filter.
+    pub fn filter_by_event(txs: &[Self], filter: &EventFilter) -> Vec<Self> {
+        txs.iter().filter(|tx| tx.events.iter().any(|event| event.matches(filter))).cloned().collect()
     }
 
-    pub fn sort_by<T: SortableField<Self>>(transactions: &mut Vec<Self>, ascending: bool) {
+    /// Keeps the CosmWasm messages addressed to the given contract.
+    pub fn filter_by_contract(txs: &[Self], contract: &str) -> Vec<Self> {
+        txs.iter().filter(|tx| tx.message.contract_address() == Some(contract)).cloned().collect()
+    }
+
+    /// Keeps the `MsgExecuteContract` calls of the given top-level method, such as `swap`.
+    pub fn filter_by_execute_method(txs: &[Self], method: &str) -> Vec<Self> {
+        txs.iter().filter(|tx| tx.message.execute_method() == Some(method)).cloned().collect()
+    }
+
+    pub fn sort_by<T: SortableField<Self>>(transactions: &mut [Self], ascending: bool) {
         transactions.sort_by(|a, b| {
             let a_val = T::get_field_value(a);
             let b_val = T::get_field_value(b);
@@ -168,23 +515,19 @@ impl IndividualMsgTx {
 
 
 
-/// Trait Implementations
+// Trait Implementations
 
-/// Implementations for translating between different types of transactions.
+// Implementations for translating between different types of transactions.
 
 /// Implementation of the `Translate` trait for the `ComprehensiveTx` structure.
 impl Translate<IndividualMsgTx> for ComprehensiveTx {
     fn translate(&self) -> Result<Vec<IndividualMsgTx>, TranslationError> {
-        let results: Vec<IndividualMsgTx> = self.messages.iter()
-            .map(|msg| self.to_individual_msg_txs(msg))
-            .collect();
-
-        Ok(results)
+        self.to_individual_transactions()
     }
 }
 
-// Implementation of the `Translate` trait for the `Vec<ComprehensiveTx>` structure.
-impl Translate<IndividualMsgTx> for Vec<ComprehensiveTx> {
+// Implementation of the `Translate` trait for a slice of `ComprehensiveTx`.
+impl Translate<IndividualMsgTx> for [ComprehensiveTx] {
     fn translate(&self) -> Result<Vec<IndividualMsgTx>, TranslationError> {
         let mut results = Vec::new();
         for comp_tx in self {
@@ -213,7 +556,7 @@ impl Translate<ComprehensiveTx> for ResponseData {
 }
 
 
-/// Implementations to extract specific sortable fields from transactions.
+// Implementations to extract specific sortable fields from transactions.
 
 /// Implementation of the `SortableField` trait for the `u64` type.
 impl SortableField<ComprehensiveTx> for u64 {
@@ -242,8 +585,8 @@ impl SortableField<IndividualMsgTx> for DateTime<Utc> {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseDataForHashQuery {
-    tx: Tx,
-    tx_response: TxResponse,
+    pub(crate) tx: Tx,
+    pub(crate) tx_response: TxResponse,
 }
 
 
@@ -252,53 +595,119 @@ pub struct ResponseDataForHashQuery {
 // The main structure for a transaction.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Tx {
-    body: Body,
+    pub(crate) body: Body,
     // The main content of the transaction.
-    auth_info: Value,
-    // Authentication information.
-    signatures: Vec<String>,    // Signatures associated with the transaction.
+    #[serde(default)]
+    pub(crate) auth_info: AuthInfo,
+    // Signers and fee.
+    pub(crate) signatures: Vec<String>,    // Signatures associated with the transaction.
 }
 
 // The body of a transaction, containing essential details.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Body {
-    messages: Vec<Message>,
+    pub(crate) messages: Vec<Message>,
     // The set of messages associated with this transaction.
-    memo: String,
+    pub(crate) memo: String,
     // A memo or note associated with the transaction.
-    timeout_height: String,       // The height at which the transaction times out.
+    pub(crate) timeout_height: String,       // The height at which the transaction times out.
 }
 
 // Structure for the response received after submitting a transaction.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TxResponse {
-    height: String,
+    pub(crate) height: String,
     // The height of the blockchain when the transaction was processed.
-    txhash: String,
+    pub(crate) txhash: String,
     // Unique hash identifier for the transaction.
-    codespace: String,
+    pub(crate) codespace: String,
     // A namespace for the transaction.
-    code: i32,
+    pub(crate) code: i32,
     // The response code after processing.
-    data: String,
+    pub(crate) data: String,
     // Any data associated with the response.
-    raw_log: Value,
+    pub(crate) raw_log: Value,
     // A raw log of the transaction processing.
-    logs: Value,
+    pub(crate) logs: Value,
     // More detailed logs, possibly in a structured format.
-    gas_wanted: String,
+    pub(crate) gas_wanted: String,
     // The amount of computational gas the transaction aimed to use.
-    gas_used: String,
+    pub(crate) gas_used: String,
     // The actual amount of computational gas used.
-    timestamp: String,          // Timestamp indicating when the transaction was processed.
+    pub(crate) timestamp: String,
+    // Timestamp indicating when the transaction was processed.
+    #[serde(default)]
+    pub(crate) events: Vec<Event>,   // Events emitted by the transaction (newer SDKs and the RPC).
+}
+
+/// One entry of `TxResponse::logs`, as emitted by SDKs before v0.50.
+#[derive(Deserialize)]
+struct MsgLog {
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+impl TxResponse {
+    /// The events emitted by the transaction.
+    ///
+    /// Newer SDKs and the RPC fill `events`; older SDKs only report them per message in `logs`.
+    pub fn all_events(&self) -> Vec<Event> {
+        if !self.events.is_empty() {
+            return self.decoded_events();
+        }
+        self.msg_logs().into_iter().flat_map(|log| log.events).collect()
+    }
+
+    /// The events of each of the `msg_count` messages of the transaction.
+    ///
+    /// Taken from `logs` when the node reports them, otherwise from the `msg_index` attribute
+    /// SDK v0.50+ add to the message events in `events`.
+    pub fn message_events(&self, msg_count: usize) -> Vec<Vec<Event>> {
+        let mut per_message = vec![Vec::new(); msg_count];
+        let logs = self.msg_logs();
+        if !logs.is_empty() {
+            for (events, log) in per_message.iter_mut().zip(logs) {
+                *events = log.events;
+            }
+            return per_message;
+        }
+        for event in self.decoded_events() {
+            let index = event.attribute("msg_index").and_then(|index| index.parse::<usize>().ok());
+            if let Some(events) = index.and_then(|index| per_message.get_mut(index)) {
+                events.push(event);
+            }
+        }
+        per_message
+    }
+
+    // Tendermint 0.34 based nodes return the attributes of `events` base64 encoded.
+    fn decoded_events(&self) -> Vec<Event> {
+        let mut events = self.events.clone();
+        for event in events.iter_mut() {
+            event.decode_base64_attributes();
+        }
+        events
+    }
+
+    // Some nodes leave `logs` empty and only report the per-message logs as JSON in `raw_log`.
+    fn msg_logs(&self) -> Vec<MsgLog> {
+        if let Ok(logs) = serde_json::from_value::<Vec<MsgLog>>(self.logs.clone()) {
+            if !logs.is_empty() {
+                return logs;
+            }
+        }
+        self.raw_log.as_str()
+            .and_then(|raw_log| serde_json::from_str::<Vec<MsgLog>>(raw_log).ok())
+            .unwrap_or_default()
+    }
 }
 
 // Main structure holding both the transactions and their corresponding responses.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseData {
-    txs: Vec<Tx>,
+    pub(crate) txs: Vec<Tx>,
     // A list of transactions.
-    tx_responses: Vec<TxResponse>,
+    pub(crate) tx_responses: Vec<TxResponse>,
     // Corresponding list of responses for the transactions.
     pub(crate) pagination: Pagination,  // Pagination details if the data is part of a paged response.
 }
@@ -319,10 +728,31 @@ fn build_comprehensive_tx(tx: &Tx, tx_response: &TxResponse) -> Result<Comprehen
         .map(|dt| dt.with_timezone(&Utc))
         .map_err(TranslationError::TimestampParseError)?;
 
+    // SDK v0.46+ report the account actually charged in the `tx` event.
+    let fee = &tx.auth_info.fee;
+    let fee_payer = if fee.payer.is_empty() {
+        tx_response.all_events().iter()
+            .filter(|event| event.kind == "tx")
+            .find_map(|event| event.attribute("fee_payer").map(str::to_string))
+            .unwrap_or_default()
+    } else {
+        fee.payer.clone()
+    };
+
+    // Failed transactions have no per-message logs, `raw_log` holds the error instead.
+    let failure_log = match (&tx_response.raw_log, tx_response.code) {
+        (_, 0) => String::new(),
+        (Value::String(log), _) => log.clone(),
+        (log, _) => log.to_string(),
+    };
+
     Ok(ComprehensiveTx {
         messages: tx.body.messages.clone(),
         height: tx_response.height.parse().unwrap_or_default(),
         tx_hash: tx_response.txhash.clone(),
+        code: tx_response.code,
+        codespace: tx_response.codespace.clone(),
+        failure_log,
         gas_used,
         gas_wanted: tx_response.gas_wanted.clone(),
         timestamp,
@@ -330,6 +760,15 @@ fn build_comprehensive_tx(tx: &Tx, tx_response: &TxResponse) -> Result<Comprehen
         signatures: tx.signatures.clone(),
         memo: tx.body.memo.clone(),
         timeout_height: tx.body.timeout_height.clone(),
+        fee: fee.amount.clone(),
+        gas_limit: fee.gas_limit(),
+        fee_payer,
+        fee_granter: fee.granter.clone(),
+        signer_infos: tx.auth_info.signer_infos.clone(),
+        events: tx_response.all_events(),
+        msg_events: tx_response.message_events(tx.body.messages.len()),
+        denom_traces: String::new(),
+        display_fee: String::new(),
     })
 }
 
@@ -337,9 +776,25 @@ fn build_comprehensive_tx(tx: &Tx, tx_response: &TxResponse) -> Result<Comprehen
 
 // Enum to represent different kinds of errors that can occur during translation.
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum TranslationError {
     GasUsedParseError(std::num::ParseIntError),
     TimestampParseError(chrono::format::ParseError),
+    Base64DecodeError(base64::DecodeError),
+    TxDecodeError(prost::DecodeError),
+    TxHashMismatch { reported: String, computed: String },
+}
+
+impl std::error::Error for TranslationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TranslationError::GasUsedParseError(e) => Some(e),
+            TranslationError::TimestampParseError(e) => Some(e),
+            TranslationError::Base64DecodeError(e) => Some(e),
+            TranslationError::TxDecodeError(e) => Some(e),
+            TranslationError::TxHashMismatch { .. } => None,
+        }
+    }
 }
 
 impl fmt::Display for TranslationError {
@@ -351,6 +806,15 @@ impl fmt::Display for TranslationError {
             TranslationError::TimestampParseError(e) => {
                 write!(f, "Error parsing timestamp: {}", e)
             }
+            TranslationError::Base64DecodeError(e) => {
+                write!(f, "Error decoding base64 tx bytes: {}", e)
+            }
+            TranslationError::TxDecodeError(e) => {
+                write!(f, "Error decoding protobuf tx: {}", e)
+            }
+            TranslationError::TxHashMismatch { reported, computed } => {
+                write!(f, "Tx hash mismatch: node reported {} but the tx bytes hash to {}", reported, computed)
+            }
         }
     }
 }
@@ -381,7 +845,7 @@ mod tests {
                 memo: "start".to_string(),
                 timeout_height: "ss".to_string(),
             },
-            auth_info: Value::Null,
+            auth_info: AuthInfo::default(),
             signatures: vec![],
         }
     }
@@ -399,6 +863,7 @@ mod tests {
             gas_wanted: "...".to_string(),
             gas_used: "1234".to_string(),
             timestamp: "2023-01-01T00:00:00Z".to_string(),
+            events: vec![],
         }
     }
 
@@ -439,14 +904,45 @@ mod tests {
     fn test_build_comprehensive_tx_other_message() {
         let mut tx = mock_tx();
         let tx_response = mock_tx_response();
-        // set other message
-        tx.body.messages = vec![Message::Other {}];
+        // set a message type we don't model
+        tx.body.messages = vec![serde_json::from_str(r#"{"@type":"/cosmos.gov.v1beta1.MsgVote","option":1}"#).unwrap()];
 
         let result = build_comprehensive_tx(&tx, &tx_response);
 
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_build_comprehensive_tx_fee_payer_from_event() {
+        let tx = mock_tx();
+        let mut tx_response = mock_tx_response();
+        tx_response.events = serde_json::from_value(serde_json::json!([
+            {"type": "tx", "attributes": [{"key": "fee", "value": "500uatom"}, {"key": "fee_payer", "value": "cosmos1payer"}]}
+        ])).unwrap();
+
+        let comp_tx = build_comprehensive_tx(&tx, &tx_response).unwrap();
+
+        assert_eq!(comp_tx.fee_payer, "cosmos1payer");
+    }
+
+    #[test]
+    fn test_build_comprehensive_tx_failed() {
+        let tx = mock_tx();
+        let mut tx_response = mock_tx_response();
+        tx_response.code = 5;
+        tx_response.codespace = "sdk".to_string();
+        tx_response.raw_log = Value::String("failed to execute message; message index: 0: 5uatom is smaller than 10uatom: insufficient funds".to_string());
+
+        let comp_tx = build_comprehensive_tx(&tx, &tx_response).unwrap();
+        assert!(!comp_tx.is_success());
+        assert_eq!(failure_reason(&comp_tx.failure_log), "insufficient funds");
+
+        let msg_txs = comp_tx.to_individual_transactions().unwrap();
+        assert_eq!(msg_txs[0].code, 5);
+        assert!(IndividualMsgTx::filter_by_success(&msg_txs, true).is_empty());
+        assert_eq!(ComprehensiveTx::filter_by_success(vec![comp_tx], false).len(), 1);
+    }
+
     fn mock_comprehensive_tx() -> ComprehensiveTx {
         ComprehensiveTx {
             messages: vec![ Message::MsgSend {
@@ -466,13 +962,25 @@ mod tests {
             }],
             height: 0,
             tx_hash: "".to_string(),
+            code: 0,
+            codespace: "".to_string(),
+            failure_log: "".to_string(),
             gas_used: 1000,
             gas_wanted: "".to_string(),
-            timestamp: Utc.ymd(2023, 9, 14).and_hms(4, 5, 6),
+            timestamp: Utc.with_ymd_and_hms(2023, 9, 14, 4, 5, 6).unwrap(),
             data: "".to_string(),
             signatures: vec![],
             memo: "".to_string(),
             timeout_height: "".to_string(),
+            fee: vec![],
+            gas_limit: 0,
+            fee_payer: "".to_string(),
+            fee_granter: "".to_string(),
+            signer_infos: vec![],
+            events: vec![],
+            msg_events: vec![],
+            denom_traces: "".to_string(),
+            display_fee: "".to_string(),
         }
     }
 
@@ -501,10 +1009,40 @@ mod tests {
     fn test_timestamp_sortable_field() {
         let tx = mock_comprehensive_tx();
         let timestamp: &DateTime<Utc> = SortableField::get_field_value(&tx);
-        assert_eq!(*timestamp, Utc.ymd(2023, 9, 14).and_hms(4, 5, 6));
+        assert_eq!(*timestamp, Utc.with_ymd_and_hms(2023, 9, 14, 4, 5, 6).unwrap());
     }
 
 
+    #[test]
+    fn test_annotate_denoms_renders_display_units() {
+        let mut comp_tx = mock_comprehensive_tx();
+        comp_tx.messages.truncate(1);
+        comp_tx.messages[0] = Message::MsgSend {
+            from_address: "cosmos12".to_string(),
+            to_address: "cosmos13".to_string(),
+            amount: vec![Amount { denom: "uatom".to_string(), amount: "2500000".to_string() }],
+        };
+        comp_tx.fee = vec![Amount { denom: "uatom".to_string(), amount: "5000".to_string() }];
+        let resolver = DenomResolver::new(vec![crate::models::coin::DenomMetadata { base: "uatom".to_string(), display: "ATOM".to_string(), exponent: 6 }]);
+
+        let mut comp_txs = vec![comp_tx];
+        ComprehensiveTx::annotate_denoms(&mut comp_txs, &resolver);
+        assert_eq!(comp_txs[0].display_fee, "0.005 ATOM");
+        assert!(comp_txs[0].to_string().contains("Display Fee: 0.005 ATOM"));
+
+        let mut msg_txs = comp_txs[0].to_individual_transactions().unwrap();
+        IndividualMsgTx::annotate_denoms(&mut msg_txs, &resolver);
+        assert!(msg_txs[0].to_string().contains("Amounts: 2.5 ATOM"));
+        let mut writer = csv::Writer::from_writer(vec![]);
+        writer.serialize(msg_txs[0].csv_row()).unwrap();
+        let mut tx_writer = csv::Writer::from_writer(vec![]);
+        tx_writer.serialize(comp_txs[0].csv_row()).unwrap();
+        assert!(String::from_utf8(tx_writer.into_inner().unwrap()).unwrap().contains("0.005 ATOM"));
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert!(csv.lines().next().unwrap().contains("display_amounts"));
+        assert!(csv.contains("2.5 ATOM"));
+    }
+
     #[test]
     fn test_to_individual_msg_txs() {
         let comp_tx = mock_comprehensive_tx();
@@ -525,6 +1063,116 @@ mod tests {
         assert_eq!(individual_msg_tx1.timeout_height, comp_tx.timeout_height);
     }
 
+    #[test]
+    fn test_all_events_falls_back_to_logs() {
+        let mut tx_response = mock_tx_response();
+        tx_response.logs = serde_json::json!([
+            {"msg_index": 0, "log": "", "events": [{"type": "send_packet", "attributes": [{"key": "packet_sequence", "value": "7"}]}]},
+            {"msg_index": 1, "log": "", "events": [{"type": "message", "attributes": []}]}
+        ]);
+        let kinds: Vec<String> = tx_response.all_events().into_iter().map(|event| event.kind).collect();
+        assert_eq!(kinds, vec!["send_packet", "message"]);
+    }
 
-}
+    #[test]
+    fn test_message_events() {
+        let mut tx_response = mock_tx_response();
+        tx_response.raw_log = serde_json::json!(r#"[{"msg_index":0,"events":[{"type":"withdraw_rewards","attributes":[{"key":"amount","value":"15uatom"}]}]}]"#);
+        let mut tx = mock_tx();
+        tx.body.messages = vec![mock_message(), mock_message()];
+
+        // Per-message logs only reported in raw_log.
+        let comp_tx = build_comprehensive_tx(&tx, &tx_response).unwrap();
+        let msg_txs = comp_tx.to_individual_transactions().unwrap();
+        assert_eq!(msg_txs[0].events[0].attribute("amount"), Some("15uatom"));
+        assert!(msg_txs[1].events.is_empty());
+        assert_eq!(comp_tx.events.len(), 1);
+
+        // SDK v0.50: message events tagged with msg_index, next to the ante handler ones.
+        tx_response.raw_log = Value::String(String::new());
+        tx_response.events = serde_json::from_value(serde_json::json!([
+            {"type": "tx", "attributes": [{"key": "fee", "value": "500uatom"}]},
+            {"type": "transfer", "attributes": [{"key": "amount", "value": "1000ustake"}, {"key": "msg_index", "value": "1"}]}
+        ])).unwrap();
+        let comp_tx = build_comprehensive_tx(&tx, &tx_response).unwrap();
+        let msg_txs = comp_tx.to_individual_transactions().unwrap();
+        assert!(msg_txs[0].events.is_empty());
+        assert_eq!(msg_txs[1].events[0].kind, "transfer");
+        assert_eq!(comp_tx.events.len(), 2);
+
+        let filter = "transfer.amount=1000ustake".parse().unwrap();
+        assert_eq!(IndividualMsgTx::filter_by_event(&msg_txs, &filter).len(), 1);
+        assert_eq!(ComprehensiveTx::filter_by_event(vec![comp_tx], &filter).len(), 1);
+    }
 
+    #[test]
+    fn test_serde_round_trip() {
+        let mut tx_response = mock_tx_response();
+        tx_response.events = serde_json::from_value(serde_json::json!([
+            {"type": "tx", "attributes": [{"key": "fee", "value": "500uatom"}]},
+            {"type": "transfer", "attributes": [{"key": "amount", "value": "1000ustake"}, {"key": "msg_index", "value": "0"}]}
+        ])).unwrap();
+        let mut comp_tx = build_comprehensive_tx(&mock_tx(), &tx_response).unwrap();
+        comp_tx.fee = vec![Amount { denom: "uatom".to_string(), amount: "500".to_string() }];
+
+        let json = serde_json::to_value(&comp_tx).unwrap();
+        let parsed: ComprehensiveTx = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(parsed.fee, comp_tx.fee);
+        assert_eq!(parsed.events, comp_tx.events);
+        assert_eq!(parsed.msg_events, comp_tx.msg_events);
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+
+        let msg_tx = &comp_tx.to_individual_transactions().unwrap()[0];
+        let parsed: IndividualMsgTx = serde_json::from_value(serde_json::to_value(msg_tx).unwrap()).unwrap();
+        assert_eq!(parsed.events, msg_tx.events);
+        assert_eq!(parsed.signatures, msg_tx.signatures);
+    }
+
+    #[test]
+    fn test_all_events_decodes_base64() {
+        let mut tx_response = mock_tx_response();
+        // "recipient" => "cosmos1abc"
+        tx_response.events = serde_json::from_value(serde_json::json!([
+            {"type": "transfer", "attributes": [{"key": "cmVjaXBpZW50", "value": "Y29zbW9zMWFiYw==", "index": true}]}
+        ])).unwrap();
+        assert_eq!(tx_response.all_events()[0].attribute("recipient"), Some("cosmos1abc"));
+    }
+
+    #[test]
+    fn test_expand_authz_messages() {
+        let delegate = Message::MsgDelegate {
+            delegator_address: "cosmos1granter".to_string(),
+            validator_address: "cosmosvaloper1abc".to_string(),
+            amount: Amount { denom: "uatom".to_string(), amount: "10".to_string() },
+        };
+        let inner_exec = Message::MsgExec { grantee: "cosmos1bot".to_string(), msgs: vec![delegate.clone()] };
+        let exec = Message::MsgExec { grantee: "cosmos1restake".to_string(), msgs: vec![delegate.clone(), inner_exec.clone()] };
+        let comp_tx = build_comprehensive_tx(&Tx { body: Body { messages: vec![mock_message(), exec.clone()], ..mock_tx().body }, ..mock_tx() }, &mock_tx_response()).unwrap();
+
+        assert_eq!(comp_tx.to_individual_transactions().unwrap().len(), 2);
+
+        let rows = comp_tx.to_expanded_individual_transactions().unwrap();
+        let summary: Vec<(&str, Option<&str>, &Message)> = rows.iter()
+            .map(|row| (row.msg_path.as_str(), row.grantee.as_deref(), &row.message))
+            .collect();
+        assert_eq!(summary, vec![
+            ("0", None, &mock_message()),
+            ("1", None, &exec),
+            ("1.0", Some("cosmos1restake"), &delegate),
+            ("1.1", Some("cosmos1restake"), &inner_exec),
+            ("1.1.0", Some("cosmos1bot"), &delegate),
+        ]);
+        assert_eq!(IndividualMsgTx::filter_by_type(&rows, MessageType::Name("MsgDelegate".to_string()), &MessageRegistry::builtin()).len(), 2);
+
+        // Each delegated coin is counted on the row of its MsgDelegate only.
+        let resolver = DenomResolver::new(vec![crate::models::coin::DenomMetadata { base: "uatom".to_string(), display: "ATOM".to_string(), exponent: 6 }]);
+        let mut rows = rows;
+        IndividualMsgTx::annotate_denoms(&mut rows, &resolver);
+        let amounts: Vec<&str> = rows.iter().map(|row| row.display_amounts.as_str()).collect();
+        assert_eq!(amounts, vec!["1000 ustake", "", "0.00001 ATOM", "", "0.00001 ATOM"]);
+
+        let mut rows = comp_tx.to_individual_transactions().unwrap();
+        IndividualMsgTx::annotate_denoms(&mut rows, &resolver);
+        assert_eq!(rows[1].display_amounts, "0.00001 ATOM, 0.00001 ATOM");
+    }
+}